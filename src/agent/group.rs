@@ -0,0 +1,184 @@
+//! Group chats: deciding when the agent is addressed, and the messages it
+//! sees as context when it is.
+
+use super::routing::AgentProfile;
+use crate::channel::IncomingMessage;
+use crate::utils::escape_regexp;
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+/// Group messages kept per chat when `groupChat.historyLimit` is unset.
+pub const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+/// Recognizes messages addressed to an agent.
+#[derive(Debug, Clone)]
+pub struct MentionMatcher {
+    patterns: Vec<Regex>,
+}
+
+impl MentionMatcher {
+    /// Matches `groupChat.mentionPatterns` (case-insensitive regexes), the
+    /// agent's configured name as a word, and `@<id>`.
+    pub fn for_agent(agent: &AgentProfile) -> Self {
+        let configured = agent.group_chat.as_ref()
+            .and_then(|g| g.mention_patterns.clone())
+            .unwrap_or_default();
+        let mut sources: Vec<String> = configured.into_iter().filter(|p| !p.trim().is_empty()).collect();
+        if agent.name != agent.id {
+            sources.push(format!(r"(?:^|\W)@?{}(?:\W|$)", escape_regexp(&agent.name)));
+        }
+        sources.push(format!(r"(?:^|\W)@{}(?:\W|$)", escape_regexp(&agent.id)));
+
+        let patterns = sources.iter()
+            .filter_map(|source| match RegexBuilder::new(source).case_insensitive(true).build() {
+                Ok(re) => Some(re),
+                Err(e) => {
+                    warn!("Ignoring invalid mention pattern for agent '{}': {}", agent.id, e);
+                    None
+                }
+            })
+            .collect();
+        Self { patterns }
+    }
+
+    pub fn is_mentioned(&self, text: &str) -> bool {
+        self.patterns.iter().any(|re| re.is_match(text))
+    }
+}
+
+/// Messages kept per group chat for `agent`; 0 turns the buffer off.
+pub fn history_limit(agent: &AgentProfile) -> usize {
+    agent.group_chat.as_ref()
+        .and_then(|g| g.history_limit)
+        .map(|limit| limit as usize)
+        .unwrap_or(DEFAULT_HISTORY_LIMIT)
+}
+
+/// A group message with who sent it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupMessage {
+    pub sender: String,
+    pub text: String,
+    pub timestamp: u64,
+}
+
+impl From<&IncomingMessage> for GroupMessage {
+    fn from(msg: &IncomingMessage) -> Self {
+        let sender = match msg.sender_name.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+            Some(name) => format!("{} ({})", name, msg.from),
+            None => msg.from.clone(),
+        };
+        Self { sender, text: msg.text.clone(), timestamp: msg.timestamp }
+    }
+}
+
+/// Recent group messages the agent wasn't asked about, per session key.
+#[derive(Debug, Clone, Default)]
+pub struct GroupHistory {
+    chats: Arc<RwLock<HashMap<String, VecDeque<GroupMessage>>>>,
+}
+
+impl GroupHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep `msg`, dropping the oldest messages beyond `limit`.
+    pub async fn record(&self, key: &str, msg: GroupMessage, limit: usize) {
+        if limit == 0 {
+            return;
+        }
+        let mut chats = self.chats.write().await;
+        let buffer = chats.entry(key.to_string()).or_default();
+        buffer.push_back(msg);
+        while buffer.len() > limit {
+            buffer.pop_front();
+        }
+    }
+
+    /// Messages since the agent last answered in `key`, oldest first. The
+    /// buffer is emptied; the messages become part of the session.
+    pub async fn take(&self, key: &str) -> Vec<GroupMessage> {
+        self.chats.write().await.remove(key).map(Vec::from).unwrap_or_default()
+    }
+
+    pub async fn len(&self, key: &str) -> usize {
+        self.chats.read().await.get(key).map_or(0, VecDeque::len)
+    }
+}
+
+/// Prompt for a group message addressed to the agent, with the chat since its
+/// last reply as context and every line attributed to its sender.
+pub fn group_prompt(history: &[GroupMessage], current: &GroupMessage) -> String {
+    let mut prompt = String::new();
+    if !history.is_empty() {
+        prompt.push_str("[Chat messages since your last reply - for context]\n");
+        for msg in history {
+            prompt.push_str(&format!("{}: {}\n", msg.sender, msg.text));
+        }
+        prompt.push('\n');
+    }
+    prompt.push_str("[Current message - respond to this]\n");
+    prompt.push_str(&format!("{}: {}", current.sender, current.text));
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{GroupChatConfig, OpenClawConfig};
+    use crate::agent::routing::AgentRouter;
+
+    fn agent(name: Option<&str>, group_chat: Option<GroupChatConfig>) -> AgentProfile {
+        let mut agent = AgentRouter::from_config(&OpenClawConfig::default()).default_agent().clone();
+        agent.id = "clawd".into();
+        agent.name = name.unwrap_or("clawd").into();
+        agent.group_chat = group_chat;
+        agent
+    }
+
+    #[test]
+    fn detects_mentions() {
+        let matcher = MentionMatcher::for_agent(&agent(Some("Claw.bot"), Some(GroupChatConfig {
+            mention_patterns: Some(vec![r"\bhey bot\b".into(), "(unclosed".into()]),
+            history_limit: None,
+        })));
+        assert!(matcher.is_mentioned("HEY BOT, what's the weather?"));
+        assert!(matcher.is_mentioned("thanks claw.bot!"));
+        assert!(matcher.is_mentioned("@clawd ping"));
+        // The name is escaped, so '.' only matches a literal dot
+        assert!(!matcher.is_mentioned("clawXbot should stay quiet"));
+        assert!(!matcher.is_mentioned("clawd is a nice word"));
+
+        let plain = MentionMatcher::for_agent(&agent(None, None));
+        assert!(plain.is_mentioned("@Clawd"));
+        assert!(!plain.is_mentioned("clawd"));
+    }
+
+    #[tokio::test]
+    async fn history_is_bounded_and_taken_once() {
+        let history = GroupHistory::new();
+        for i in 0..5 {
+            let msg = GroupMessage { sender: format!("+{}", i), text: format!("message {}", i), timestamp: i };
+            history.record("g1", msg, 3).await;
+        }
+        history.record("g2", GroupMessage { sender: "+9".into(), text: "ignored".into(), timestamp: 0 }, 0).await;
+        assert_eq!(history.len("g1").await, 3);
+        assert_eq!(history.len("g2").await, 0);
+
+        let taken = history.take("g1").await;
+        assert_eq!(taken.iter().map(|m| m.timestamp).collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert!(history.take("g1").await.is_empty());
+
+        let prompt = group_prompt(&taken, &GroupMessage { sender: "Ana (+7)".into(), text: "@clawd summarize".into(), timestamp: 5 });
+        assert!(prompt.starts_with("[Chat messages since your last reply"));
+        assert!(prompt.contains("+2: message 2\n+3: message 3"));
+        assert!(prompt.ends_with("[Current message - respond to this]\nAna (+7): @clawd summarize"));
+        assert!(!group_prompt(&[], &taken[0]).contains("for context"));
+    }
+}
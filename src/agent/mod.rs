@@ -0,0 +1,216 @@
+//! Agent turns: send a prompt with the session history to the model and run
+//! the tool calls it makes until it produces a final answer.
+
+pub mod group;
+pub mod routing;
+
+use crate::provider::types::{
+    CompletionRequest, ContentBlock, Message, MessageContent, MessageRole, Provider, ProviderError,
+    ToolDefinition, Usage,
+};
+use crate::session::Session;
+use crate::tools::executor::ToolContext;
+use crate::tools::{run_tool, Tool};
+use std::sync::Arc;
+use tracing::debug;
+
+/// Model round-trips allowed per turn before giving up.
+const DEFAULT_MAX_ITERATIONS: usize = 16;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AgentError {
+    #[error("No model provider configured")]
+    NoProvider,
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    #[error("Agent turn exceeded {0} model calls")]
+    TooManyIterations(usize),
+}
+
+/// Outcome of a completed turn.
+#[derive(Debug, Clone, Default)]
+pub struct TurnOutcome {
+    /// Final assistant text.
+    pub text: String,
+    pub tool_calls: usize,
+    pub iterations: usize,
+    pub usage: Usage,
+}
+
+/// Runs agent turns against a provider with a fixed tool set.
+#[derive(Clone)]
+pub struct AgentRunner {
+    provider: Arc<dyn Provider>,
+    model: String,
+    tools: Vec<Arc<dyn Tool>>,
+    max_iterations: usize,
+    max_tokens: u32,
+}
+
+impl AgentRunner {
+    pub fn new(provider: Arc<dyn Provider>, model: &str) -> Self {
+        Self {
+            provider,
+            model: model.to_string(),
+            tools: Vec::new(),
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            max_tokens: CompletionRequest::default().max_tokens,
+        }
+    }
+
+    pub fn with_tools(mut self, tools: Vec<Arc<dyn Tool>>) -> Self {
+        self.tools = tools;
+        self
+    }
+
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations.max(1);
+        self
+    }
+
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Add `prompt` to the session as a user message and run until the model
+    /// stops calling tools. The session keeps the full exchange.
+    pub async fn run_turn(&self, session: &mut Session, prompt: &str, ctx: &ToolContext) -> Result<TurnOutcome, AgentError> {
+        session.add_user_message(prompt);
+        let mut outcome = TurnOutcome::default();
+
+        let definitions: Vec<ToolDefinition> = self.tools.iter().map(|t| t.definition()).collect();
+        loop {
+            if outcome.iterations >= self.max_iterations {
+                return Err(AgentError::TooManyIterations(self.max_iterations));
+            }
+            outcome.iterations += 1;
+
+            let request = CompletionRequest {
+                model: self.model.clone(),
+                system: session.system_prompt.clone(),
+                messages: session.messages.clone(),
+                tools: definitions.clone(),
+                max_tokens: self.max_tokens,
+                ..Default::default()
+            };
+            let response = self.provider.complete(&request).await?;
+            add_usage(&mut outcome.usage, &response.usage);
+
+            // Thinking blocks can't be replayed without their signatures
+            let blocks: Vec<ContentBlock> = response.content.into_iter()
+                .filter(|b| matches!(b, ContentBlock::Text { .. } | ContentBlock::ToolUse { .. }))
+                .collect();
+            let calls: Vec<(String, String, serde_json::Value)> = blocks.iter()
+                .filter_map(|b| match b {
+                    ContentBlock::ToolUse { id, name, input } => Some((id.clone(), name.clone(), input.clone())),
+                    _ => None,
+                })
+                .collect();
+            outcome.text = MessageContent::Blocks(blocks.clone()).to_text();
+            session.add_assistant_tool_use(blocks);
+
+            if calls.is_empty() {
+                return Ok(outcome);
+            }
+
+            // All results go back in one user message, as the API expects
+            let mut results = Vec::with_capacity(calls.len());
+            for (id, name, input) in calls {
+                debug!("Agent tool call: {} ({})", name, id);
+                let result = match definitions.iter().position(|t| t.name == name) {
+                    Some(i) => run_tool(self.tools[i].as_ref(), &input, ctx).await,
+                    None => crate::tools::ToolResult {
+                        content: format!("Tool not available: {}", name),
+                        is_error: true,
+                        metadata: Default::default(),
+                    },
+                };
+                outcome.tool_calls += 1;
+                results.push(ContentBlock::ToolResult {
+                    tool_use_id: id,
+                    content: result.content,
+                    is_error: if result.is_error { Some(true) } else { None },
+                });
+            }
+            session.messages.push(Message {
+                role: MessageRole::User,
+                content: MessageContent::Blocks(results),
+            });
+        }
+    }
+}
+
+fn add_usage(total: &mut Usage, usage: &Usage) {
+    total.input_tokens += usage.input_tokens;
+    total.output_tokens += usage.output_tokens;
+    total.cache_creation_input_tokens += usage.cache_creation_input_tokens;
+    total.cache_read_input_tokens += usage.cache_read_input_tokens;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock::ScriptedProvider;
+
+    fn read_tool() -> Arc<dyn Tool> {
+        Arc::new(crate::tools::executor::ReadTool)
+    }
+
+    #[tokio::test]
+    async fn runs_tools_until_final_answer() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "The wifi password is hunter2").unwrap();
+        let provider = ScriptedProvider::new(vec![
+            vec![
+                ContentBlock::Text { text: "Let me check.".into() },
+                ContentBlock::ToolUse { id: "tu_1".into(), name: "Read".into(), input: serde_json::json!({ "path": "notes.txt" }) },
+            ],
+            vec![ContentBlock::Text { text: "It's hunter2.".into() }],
+        ]);
+        let runner = AgentRunner::new(Arc::new(provider.clone()), "test-model").with_tools(vec![read_tool()]);
+        let mut session = Session::new("k", "main", "cli");
+        let ctx = ToolContext::new(dir.path().to_str().unwrap());
+
+        let outcome = runner.run_turn(&mut session, "What's the wifi password?", &ctx).await.unwrap();
+        assert_eq!(outcome.text, "It's hunter2.");
+        assert_eq!((outcome.iterations, outcome.tool_calls), (2, 1));
+        assert_eq!(outcome.usage.input_tokens, 20);
+        // user, assistant tool_use, tool results, assistant answer
+        assert_eq!(session.message_count(), 4);
+
+        let second = provider.requests.lock().unwrap()[1].clone();
+        assert_eq!(second.tools.len(), 1);
+        match &second.messages[2].content {
+            MessageContent::Blocks(blocks) => match &blocks[0] {
+                ContentBlock::ToolResult { tool_use_id, content, .. } => {
+                    assert_eq!(tool_use_id, "tu_1");
+                    assert!(content.contains("hunter2"));
+                }
+                other => panic!("unexpected block {:?}", other),
+            },
+            other => panic!("unexpected content {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn unavailable_tools_and_iteration_limit() {
+        let provider = ScriptedProvider::new(vec![vec![
+            ContentBlock::ToolUse { id: "tu".into(), name: "exec".into(), input: serde_json::json!({ "command": "true" }) },
+        ]]);
+        let runner = AgentRunner::new(Arc::new(provider), "m").with_max_iterations(3);
+        let mut session = Session::new("k", "main", "cli");
+        let err = runner.run_turn(&mut session, "loop forever", &ToolContext::new("/tmp")).await.unwrap_err();
+        assert!(matches!(err, AgentError::TooManyIterations(3)));
+
+        let last_result = session.messages.last().unwrap().content.clone();
+        match last_result {
+            MessageContent::Blocks(blocks) => assert!(matches!(&blocks[0], ContentBlock::ToolResult { is_error: Some(true), .. })),
+            other => panic!("unexpected content {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,278 @@
+//! Multi-agent routing: which agent handles a message, and the workspace,
+//! model and tools that agent runs with.
+
+use crate::config::{GroupChatConfig, OpenClawConfig};
+use crate::session::build_session_key;
+use serde::Serialize;
+use tracing::warn;
+
+/// Id of the implicit agent when `agents.list` is empty.
+pub const DEFAULT_AGENT_ID: &str = "main";
+const DEFAULT_WORKSPACE: &str = "~/.openclaw/workspace";
+
+/// An agent with its settings resolved against `agents.defaults`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentProfile {
+    pub id: String,
+    pub name: String,
+    pub workspace_dir: String,
+    pub agent_dir: Option<String>,
+    /// Model id without the provider prefix.
+    pub model: String,
+    /// Only these tools, when set.
+    pub tools_allow: Option<Vec<String>>,
+    pub tools_deny: Vec<String>,
+    pub group_chat: Option<GroupChatConfig>,
+    pub default: bool,
+}
+
+impl AgentProfile {
+    /// Whether the agent's own policy lets it use `tool` (the global policy
+    /// is applied separately by the tool registry).
+    pub fn allows_tool(&self, tool: &str) -> bool {
+        if self.tools_deny.iter().any(|t| t == tool) {
+            return false;
+        }
+        self.tools_allow.as_ref().is_none_or(|allow| allow.iter().any(|t| t == tool))
+    }
+
+    /// Session key in this agent's namespace (`agent:<id>:<channel>:<chat>`).
+    pub fn session_key(&self, channel: &str, chat_id: &str) -> String {
+        build_session_key(&self.id, channel, chat_id)
+    }
+
+    /// Prefix shared by all of this agent's session keys.
+    pub fn session_prefix(&self) -> String {
+        format!("agent:{}:", self.id)
+    }
+}
+
+/// A `bindings` entry; unset fields match anything.
+#[derive(Debug, Clone)]
+struct Binding {
+    agent_id: String,
+    channel: Option<String>,
+    chat_id: Option<String>,
+    from: Option<String>,
+}
+
+impl Binding {
+    /// How specific the match is (a chat or sender beats a whole channel),
+    /// or `None` when the binding doesn't apply.
+    fn specificity(&self, channel: &str, chat_id: &str, from: &str) -> Option<u8> {
+        let mut score = 0;
+        for (want, have, weight) in [(&self.channel, channel, 1), (&self.chat_id, chat_id, 2), (&self.from, from, 2)] {
+            match want {
+                Some(want) if want == have => score += weight,
+                Some(_) => return None,
+                None => {}
+            }
+        }
+        Some(score)
+    }
+
+    fn matched_by(&self) -> &'static str {
+        match (&self.chat_id, &self.from, &self.channel) {
+            (Some(_), _, _) => "chatId",
+            (None, Some(_), _) => "from",
+            (None, None, Some(_)) => "channel",
+            (None, None, None) => "any",
+        }
+    }
+}
+
+/// Where a message goes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Route {
+    pub agent_id: String,
+    pub session_key: String,
+    /// Which binding field decided the route, or "default".
+    pub matched_by: &'static str,
+}
+
+/// Agents from `agents.list` plus the `bindings` that pick between them.
+#[derive(Debug, Clone)]
+pub struct AgentRouter {
+    agents: Vec<AgentProfile>,
+    bindings: Vec<Binding>,
+    default_index: usize,
+}
+
+impl AgentRouter {
+    pub fn from_config(config: &OpenClawConfig) -> Self {
+        let base_workspace = config.workspace_dir().unwrap_or(DEFAULT_WORKSPACE).to_string();
+        let default_model = model_id(config.primary_model());
+        let entries = config.agents.as_ref().and_then(|a| a.list.clone()).unwrap_or_default();
+
+        let mut agents: Vec<AgentProfile> = Vec::new();
+        for entry in &entries {
+            let Some(id) = entry.id.as_deref().map(str::trim).filter(|id| !id.is_empty()) else {
+                warn!("Ignoring agents.list entry without an id");
+                continue;
+            };
+            if agents.iter().any(|a| a.id == id) {
+                warn!("Ignoring duplicate agent id '{}'", id);
+                continue;
+            }
+            let tools = entry.tools.clone().unwrap_or_default();
+            agents.push(AgentProfile {
+                id: id.to_string(),
+                name: entry.name.clone().unwrap_or_else(|| id.to_string()),
+                workspace_dir: entry.workspace.clone().unwrap_or_default(),
+                agent_dir: entry.agent_dir.clone(),
+                model: entry.model.as_deref().map(|m| model_id(Some(m))).unwrap_or_else(|| default_model.clone()),
+                tools_allow: tools.allow,
+                tools_deny: tools.deny.unwrap_or_default(),
+                group_chat: entry.group_chat.clone(),
+                default: entry.default.unwrap_or(false),
+            });
+        }
+        if agents.is_empty() {
+            agents.push(AgentProfile {
+                id: DEFAULT_AGENT_ID.to_string(),
+                name: DEFAULT_AGENT_ID.to_string(),
+                workspace_dir: String::new(),
+                agent_dir: None,
+                model: default_model,
+                tools_allow: None,
+                tools_deny: Vec::new(),
+                group_chat: None,
+                default: true,
+            });
+        }
+
+        let default_index = agents.iter().position(|a| a.default).unwrap_or(0);
+        for (i, agent) in agents.iter_mut().enumerate() {
+            agent.default = i == default_index;
+            // The default agent uses the shared workspace; others get their own
+            if agent.workspace_dir.is_empty() {
+                agent.workspace_dir = if agent.default {
+                    base_workspace.clone()
+                } else {
+                    format!("{}-{}", base_workspace.trim_end_matches('/'), agent.id)
+                };
+            }
+        }
+
+        let mut bindings = Vec::new();
+        for binding in config.bindings.iter().flatten() {
+            let Some(agent_id) = binding.agent_id.clone() else { continue };
+            if !agents.iter().any(|a| a.id == agent_id) {
+                warn!("Ignoring binding for unknown agent '{}'", agent_id);
+                continue;
+            }
+            let rule = binding.match_rule.clone().unwrap_or_default();
+            bindings.push(Binding { agent_id, channel: rule.channel, chat_id: rule.chat_id, from: rule.from });
+        }
+
+        Self { agents, bindings, default_index }
+    }
+
+    pub fn list(&self) -> &[AgentProfile] {
+        &self.agents
+    }
+
+    pub fn get(&self, id: &str) -> Option<&AgentProfile> {
+        self.agents.iter().find(|a| a.id == id)
+    }
+
+    pub fn default_agent(&self) -> &AgentProfile {
+        &self.agents[self.default_index]
+    }
+
+    /// The agent for a message, by the most specific matching binding (the
+    /// first one listed on ties), else the default agent.
+    pub fn resolve(&self, channel: &str, chat_id: &str, from: &str) -> Route {
+        let best = self.bindings.iter()
+            .filter_map(|b| b.specificity(channel, chat_id, from).map(|score| (score, b)))
+            .fold(None::<(u8, &Binding)>, |best, (score, b)| match best {
+                Some((top, _)) if top >= score => best,
+                _ => Some((score, b)),
+            });
+        let (agent, matched_by) = match best.and_then(|(_, b)| self.get(&b.agent_id).map(|a| (a, b.matched_by()))) {
+            Some(found) => found,
+            None => (self.default_agent(), "default"),
+        };
+        Route {
+            agent_id: agent.id.clone(),
+            session_key: agent.session_key(channel, chat_id),
+            matched_by,
+        }
+    }
+}
+
+fn model_id(model: Option<&str>) -> String {
+    model
+        .map(|m| OpenClawConfig::parse_model_id(m).1)
+        .unwrap_or_else(|| crate::provider::CompletionRequest::default().model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn router() -> AgentRouter {
+        let config: OpenClawConfig = serde_json::from_value(serde_json::json!({
+            "agents": {
+                "defaults": { "workspace": "/srv/ws", "model": { "primary": "anthropic/claude-sonnet" } },
+                "list": [
+                    { "id": "home", "default": true },
+                    { "id": "work", "name": "Work", "model": "anthropic/claude-opus", "tools": { "deny": ["exec"] } },
+                    { "id": "family", "workspace": "/srv/family", "tools": { "allow": ["Read", "message"] } },
+                    { "id": "work" },
+                    { "name": "nameless" }
+                ]
+            },
+            "bindings": [
+                { "agentId": "work", "match": { "channel": "telegram" } },
+                { "agentId": "family", "match": { "channel": "whatsapp", "chatId": "family-group@g.us" } },
+                { "agentId": "work", "match": { "from": "+15550001111" } },
+                { "agentId": "ghost", "match": { "channel": "whatsapp" } }
+            ]
+        })).unwrap();
+        AgentRouter::from_config(&config)
+    }
+
+    #[test]
+    fn profiles_resolve_against_defaults() {
+        let router = router();
+        assert_eq!(router.list().len(), 3);
+        assert_eq!(router.default_agent().id, "home");
+
+        let home = router.get("home").unwrap();
+        assert_eq!((home.workspace_dir.as_str(), home.model.as_str()), ("/srv/ws", "claude-sonnet"));
+        let work = router.get("work").unwrap();
+        assert_eq!((work.workspace_dir.as_str(), work.model.as_str(), work.name.as_str()), ("/srv/ws-work", "claude-opus", "Work"));
+        assert!(!work.allows_tool("exec") && work.allows_tool("Read"));
+        let family = router.get("family").unwrap();
+        assert_eq!(family.workspace_dir, "/srv/family");
+        assert!(family.allows_tool("message") && !family.allows_tool("Write"));
+        assert_eq!(family.session_key("whatsapp", "g1"), "agent:family:whatsapp:g1");
+    }
+
+    #[test]
+    fn most_specific_binding_wins() {
+        let router = router();
+        let route = router.resolve("whatsapp", "family-group@g.us", "+15559998888");
+        assert_eq!((route.agent_id.as_str(), route.matched_by), ("family", "chatId"));
+        assert_eq!(route.session_key, "agent:family:whatsapp:family-group@g.us");
+
+        assert_eq!(router.resolve("telegram", "42", "42").agent_id, "work");
+        // The sender binding beats the channel-wide one
+        assert_eq!(router.resolve("whatsapp", "+15550001111", "+15550001111").matched_by, "from");
+        let fallback = router.resolve("whatsapp", "+15552223333", "+15552223333");
+        assert_eq!((fallback.agent_id.as_str(), fallback.matched_by), ("home", "default"));
+    }
+
+    #[test]
+    fn single_agent_without_list() {
+        let router = AgentRouter::from_config(&OpenClawConfig::default());
+        assert_eq!(router.list().len(), 1);
+        let main = router.default_agent();
+        assert_eq!(main.id, DEFAULT_AGENT_ID);
+        assert_eq!(main.workspace_dir, DEFAULT_WORKSPACE);
+        assert_eq!(router.resolve("cli", "local", "me").session_key, "agent:main:cli:local");
+    }
+}
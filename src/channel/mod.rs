@@ -1,5 +1,6 @@
 pub mod whatsapp;
 
+use crate::polls::{normalize_poll_input, NormalizePollOptions, NormalizedPollInput, PollError, PollInput};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
@@ -9,6 +10,9 @@ pub struct IncomingMessage {
     pub id: String,
     pub channel: String,
     pub from: String,
+    /// Display name of the sender, when the channel provides one.
+    #[serde(default)]
+    pub sender_name: Option<String>,
     pub chat_id: String,
     pub text: String,
     pub timestamp: u64,
@@ -26,14 +30,112 @@ pub struct MediaAttachment {
     pub filename: Option<String>,
 }
 
+/// A quick-reply button attached to an outgoing message.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MessageButton {
+    pub id: String,
+    pub label: String,
+}
+
 /// An outgoing message to a channel.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct OutgoingMessage {
     pub channel: String,
     pub to: String,
     pub text: String,
     pub reply_to: Option<String>,
-    pub media: Option<MediaAttachment>,
+    #[serde(default)]
+    pub attachments: Vec<MediaAttachment>,
+    #[serde(default)]
+    pub buttons: Vec<MessageButton>,
+}
+
+impl OutgoingMessage {
+    /// Plain text message with no attachments or buttons.
+    pub fn text(channel: &str, to: &str, text: &str) -> Self {
+        Self {
+            channel: channel.to_string(),
+            to: to.to_string(),
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// What a channel can do natively. Anything missing is degraded by
+/// `ChannelManager::dispatch` instead of failing the caller.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelCapabilities {
+    pub polls: bool,
+    pub max_poll_options: Option<usize>,
+    pub reactions: bool,
+    pub edits: bool,
+    pub deletes: bool,
+    pub typing: bool,
+    pub buttons: bool,
+    pub max_buttons: Option<usize>,
+    pub max_attachments: usize,
+    pub max_text_length: Option<usize>,
+    pub replies: bool,
+}
+
+impl Default for ChannelCapabilities {
+    /// Text-only channel: one message at a time, nothing else.
+    fn default() -> Self {
+        Self {
+            polls: false,
+            max_poll_options: None,
+            reactions: false,
+            edits: false,
+            deletes: false,
+            typing: false,
+            buttons: false,
+            max_buttons: None,
+            max_attachments: 0,
+            max_text_length: None,
+            replies: false,
+        }
+    }
+}
+
+/// An outbound action to perform on a channel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum OutboundAction {
+    Send(OutgoingMessage),
+    #[serde(rename_all = "camelCase")]
+    Edit { channel: String, chat_id: String, message_id: String, text: String },
+    #[serde(rename_all = "camelCase")]
+    Delete { channel: String, chat_id: String, message_id: String },
+    #[serde(rename_all = "camelCase")]
+    React { channel: String, chat_id: String, message_id: String, emoji: String },
+    Poll { channel: String, to: String, poll: PollInput },
+    #[serde(rename_all = "camelCase")]
+    Typing { channel: String, chat_id: String, active: bool },
+}
+
+impl OutboundAction {
+    pub fn channel(&self) -> &str {
+        match self {
+            Self::Send(msg) => &msg.channel,
+            Self::Edit { channel, .. }
+            | Self::Delete { channel, .. }
+            | Self::React { channel, .. }
+            | Self::Poll { channel, .. }
+            | Self::Typing { channel, .. } => channel,
+        }
+    }
+}
+
+/// Result of dispatching an outbound action.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DispatchOutcome {
+    /// Ids of the messages the channel created (empty for edits, reactions, etc.).
+    pub message_ids: Vec<String>,
+    /// Set when the action was degraded because the channel lacks a capability.
+    pub fallback: Option<String>,
 }
 
 /// Channel plugin trait.
@@ -42,12 +144,47 @@ pub trait ChannelPlugin: Send + Sync {
     /// Channel name (e.g., "whatsapp", "telegram").
     fn name(&self) -> &str;
 
-    /// Send a message.
-    async fn send(&self, message: &OutgoingMessage) -> Result<(), ChannelError>;
+    /// What this channel supports natively.
+    fn capabilities(&self) -> ChannelCapabilities;
+
+    /// Send a message, returning the channel's id for it.
+    async fn send(&self, message: &OutgoingMessage) -> Result<String, ChannelError>;
 
     /// React to a message with an emoji.
     async fn react(&self, chat_id: &str, message_id: &str, emoji: &str) -> Result<(), ChannelError>;
 
+    /// Replace the text of a previously sent message.
+    async fn edit(&self, _chat_id: &str, _message_id: &str, _text: &str) -> Result<(), ChannelError> {
+        Err(ChannelError::Unsupported("edit".into()))
+    }
+
+    /// Delete a previously sent message.
+    async fn delete(&self, _chat_id: &str, _message_id: &str) -> Result<(), ChannelError> {
+        Err(ChannelError::Unsupported("delete".into()))
+    }
+
+    /// Send a native poll, returning the channel's id for it.
+    async fn send_poll(&self, _to: &str, _poll: &NormalizedPollInput) -> Result<String, ChannelError> {
+        Err(ChannelError::Unsupported("poll".into()))
+    }
+
+    /// Show or clear the typing indicator in a chat.
+    async fn set_typing(&self, _chat_id: &str, _active: bool) -> Result<(), ChannelError> {
+        Err(ChannelError::Unsupported("typing".into()))
+    }
+
+    /// Whether the agent only answers group messages in `chat_id` that
+    /// mention it. Other group messages are kept as context.
+    fn requires_mention(&self, _chat_id: &str) -> bool {
+        true
+    }
+
+    /// Whether the sender of `msg` may reach the agent at all (allowlist,
+    /// DM and group policy). Mentions are checked separately.
+    fn accepts_sender(&self, _msg: &IncomingMessage) -> bool {
+        true
+    }
+
     /// Check if the plugin is connected/ready.
     fn is_connected(&self) -> bool;
 }
@@ -58,10 +195,96 @@ pub enum ChannelError {
     NotConnected,
     #[error("Send failed: {0}")]
     SendFailed(String),
+    #[error("Unsupported by channel: {0}")]
+    Unsupported(String),
+    #[error("Invalid poll: {0}")]
+    InvalidPoll(#[from] PollError),
     #[error("Channel error: {0}")]
     Other(String),
 }
 
+/// Render a poll as a numbered list for channels without native polls.
+pub fn render_poll_as_text(poll: &NormalizedPollInput) -> String {
+    let mut lines = vec![format!("📊 {}", poll.question)];
+    for (idx, option) in poll.options.iter().enumerate() {
+        lines.push(format!("{}. {}", idx + 1, option));
+    }
+    if poll.max_selections > 1 {
+        lines.push(format!("Reply with up to {} numbers.", poll.max_selections));
+    } else {
+        lines.push("Reply with a number to vote.".to_string());
+    }
+    lines.join("\n")
+}
+
+/// Split text into chunks of at most `max_len` chars, preferring line breaks.
+pub fn chunk_text(text: &str, max_len: usize) -> Vec<String> {
+    if max_len == 0 || text.chars().count() <= max_len {
+        return vec![text.to_string()];
+    }
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+    for line in text.split_inclusive('\n') {
+        let line_len = line.chars().count();
+        if current_len + line_len > max_len && !current.is_empty() {
+            chunks.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+        if line_len > max_len {
+            // Hard-split a single oversized line
+            for ch in line.chars() {
+                if current_len == max_len {
+                    chunks.push(std::mem::take(&mut current));
+                    current_len = 0;
+                }
+                current.push(ch);
+                current_len += 1;
+            }
+        } else {
+            current.push_str(line);
+            current_len += line_len;
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Fold buttons and surplus attachments into the text when the channel can't carry them.
+fn degrade_message(message: &OutgoingMessage, caps: &ChannelCapabilities) -> (OutgoingMessage, Vec<&'static str>) {
+    let mut msg = message.clone();
+    let mut degraded = Vec::new();
+
+    let max_buttons = caps.max_buttons.unwrap_or(usize::MAX);
+    if !msg.buttons.is_empty() && (!caps.buttons || msg.buttons.len() > max_buttons) {
+        let options: Vec<String> = msg.buttons.iter().enumerate()
+            .map(|(idx, b)| format!("{}. {}", idx + 1, b.label))
+            .collect();
+        msg.text = format!("{}\n\n{}", msg.text, options.join("\n"));
+        msg.buttons.clear();
+        degraded.push("buttons");
+    }
+
+    if msg.attachments.len() > caps.max_attachments {
+        let overflow = msg.attachments.split_off(caps.max_attachments);
+        let links: Vec<String> = overflow.iter()
+            .filter_map(|a| a.url.clone().or_else(|| a.filename.clone()))
+            .collect();
+        if !links.is_empty() {
+            msg.text = format!("{}\n\n{}", msg.text, links.join("\n"));
+        }
+        degraded.push("attachments");
+    }
+
+    if msg.reply_to.is_some() && !caps.replies {
+        msg.reply_to = None;
+    }
+
+    (msg, degraded)
+}
+
 /// Channel manager — routes messages to the appropriate channel plugin.
 pub struct ChannelManager {
     plugins: Vec<Box<dyn ChannelPlugin>>,
@@ -80,10 +303,117 @@ impl ChannelManager {
         self.plugins.iter().find(|p| p.name() == channel).map(|p| p.as_ref())
     }
 
-    pub async fn send(&self, message: &OutgoingMessage) -> Result<(), ChannelError> {
-        let plugin = self.get(&message.channel)
-            .ok_or_else(|| ChannelError::Other(format!("No plugin for channel: {}", message.channel)))?;
-        plugin.send(message).await
+    fn plugin(&self, channel: &str) -> Result<&dyn ChannelPlugin, ChannelError> {
+        self.get(channel)
+            .ok_or_else(|| ChannelError::Other(format!("No plugin for channel: {}", channel)))
+    }
+
+    /// Whether group messages in `chat_id` need a mention; unknown channels
+    /// require one.
+    pub fn requires_mention(&self, channel: &str, chat_id: &str) -> bool {
+        self.get(channel).is_none_or(|p| p.requires_mention(chat_id))
+    }
+
+    /// Whether the channel lets the sender of `msg` through; messages for
+    /// unregistered channels are refused.
+    pub fn accepts_sender(&self, msg: &IncomingMessage) -> bool {
+        self.get(&msg.channel).is_some_and(|p| p.accepts_sender(msg))
+    }
+
+    /// Capabilities of a registered channel.
+    pub fn capabilities(&self, channel: &str) -> Option<ChannelCapabilities> {
+        self.get(channel).map(|p| p.capabilities())
+    }
+
+    pub async fn send(&self, message: &OutgoingMessage) -> Result<DispatchOutcome, ChannelError> {
+        self.dispatch(&OutboundAction::Send(message.clone())).await
+    }
+
+    /// Perform an outbound action, degrading gracefully when the channel
+    /// lacks the capability (e.g. a poll becomes a numbered list).
+    pub async fn dispatch(&self, action: &OutboundAction) -> Result<DispatchOutcome, ChannelError> {
+        let plugin = self.plugin(action.channel())?;
+        let caps = plugin.capabilities();
+
+        match action {
+            OutboundAction::Send(message) => {
+                let (msg, degraded) = degrade_message(message, &caps);
+                let mut outcome = self.send_chunked(plugin, &msg, &caps).await?;
+                if !degraded.is_empty() {
+                    outcome.fallback = Some(degraded.join(","));
+                }
+                Ok(outcome)
+            }
+            OutboundAction::Edit { chat_id, message_id, text, .. } => {
+                if caps.edits {
+                    plugin.edit(chat_id, message_id, text).await?;
+                    return Ok(DispatchOutcome::default());
+                }
+                // No native edits: send the corrected text as a new message
+                let msg = OutgoingMessage::text(plugin.name(), chat_id, text);
+                let mut outcome = self.send_chunked(plugin, &msg, &caps).await?;
+                outcome.fallback = Some("edit".into());
+                Ok(outcome)
+            }
+            OutboundAction::Delete { chat_id, message_id, .. } => {
+                if !caps.deletes {
+                    return Err(ChannelError::Unsupported("delete".into()));
+                }
+                plugin.delete(chat_id, message_id).await?;
+                Ok(DispatchOutcome::default())
+            }
+            OutboundAction::React { chat_id, message_id, emoji, .. } => {
+                if !caps.reactions {
+                    return Err(ChannelError::Unsupported("react".into()));
+                }
+                plugin.react(chat_id, message_id, emoji).await?;
+                Ok(DispatchOutcome::default())
+            }
+            OutboundAction::Poll { to, poll, .. } => {
+                let normalized = normalize_poll_input(poll, &NormalizePollOptions::for_channel(&caps))?;
+                if caps.polls {
+                    let id = plugin.send_poll(to, &normalized).await?;
+                    return Ok(DispatchOutcome { message_ids: vec![id], fallback: None });
+                }
+                let msg = OutgoingMessage::text(plugin.name(), to, &render_poll_as_text(&normalized));
+                let mut outcome = self.send_chunked(plugin, &msg, &caps).await?;
+                outcome.fallback = Some("poll".into());
+                Ok(outcome)
+            }
+            OutboundAction::Typing { chat_id, active, .. } => {
+                if !caps.typing {
+                    // Typing indicators are cosmetic; silently skip
+                    return Ok(DispatchOutcome { message_ids: vec![], fallback: Some("typing".into()) });
+                }
+                plugin.set_typing(chat_id, *active).await?;
+                Ok(DispatchOutcome::default())
+            }
+        }
+    }
+
+    async fn send_chunked(
+        &self,
+        plugin: &dyn ChannelPlugin,
+        message: &OutgoingMessage,
+        caps: &ChannelCapabilities,
+    ) -> Result<DispatchOutcome, ChannelError> {
+        let chunks = match caps.max_text_length {
+            Some(max) => chunk_text(&message.text, max),
+            None => vec![message.text.clone()],
+        };
+        let mut outcome = DispatchOutcome::default();
+        for (idx, chunk) in chunks.into_iter().enumerate() {
+            let mut part = message.clone();
+            part.text = chunk;
+            if idx > 0 {
+                // Attachments, buttons and reply context ride on the first chunk only
+                part.attachments.clear();
+                part.buttons.clear();
+                part.reply_to = None;
+            }
+            outcome.message_ids.push(plugin.send(&part).await?);
+        }
+        Ok(outcome)
     }
 
     pub fn list_channels(&self) -> Vec<&str> {
@@ -91,6 +421,81 @@ impl ChannelManager {
     }
 }
 
+impl Default for ChannelManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// In-memory channel plugin used by tests across the crate.
+#[cfg(test)]
+pub(crate) mod mock {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    pub struct MockChannel {
+        pub name: String,
+        pub caps: ChannelCapabilities,
+        pub sent: Arc<Mutex<Vec<OutgoingMessage>>>,
+        pub polls: Arc<Mutex<Vec<NormalizedPollInput>>>,
+        pub calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl MockChannel {
+        pub fn new(name: &str, caps: ChannelCapabilities) -> Self {
+            Self { name: name.to_string(), caps, ..Default::default() }
+        }
+    }
+
+    #[async_trait]
+    impl ChannelPlugin for MockChannel {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn capabilities(&self) -> ChannelCapabilities {
+            self.caps.clone()
+        }
+
+        async fn send(&self, message: &OutgoingMessage) -> Result<String, ChannelError> {
+            let mut sent = self.sent.lock().unwrap();
+            sent.push(message.clone());
+            Ok(format!("msg-{}", sent.len()))
+        }
+
+        async fn react(&self, _chat_id: &str, message_id: &str, emoji: &str) -> Result<(), ChannelError> {
+            self.calls.lock().unwrap().push(format!("react:{}:{}", message_id, emoji));
+            Ok(())
+        }
+
+        async fn edit(&self, _chat_id: &str, message_id: &str, text: &str) -> Result<(), ChannelError> {
+            self.calls.lock().unwrap().push(format!("edit:{}:{}", message_id, text));
+            Ok(())
+        }
+
+        async fn delete(&self, _chat_id: &str, message_id: &str) -> Result<(), ChannelError> {
+            self.calls.lock().unwrap().push(format!("delete:{}", message_id));
+            Ok(())
+        }
+
+        async fn send_poll(&self, _to: &str, poll: &NormalizedPollInput) -> Result<String, ChannelError> {
+            let mut polls = self.polls.lock().unwrap();
+            polls.push(poll.clone());
+            Ok(format!("poll-{}", polls.len()))
+        }
+
+        async fn set_typing(&self, chat_id: &str, active: bool) -> Result<(), ChannelError> {
+            self.calls.lock().unwrap().push(format!("typing:{}:{}", chat_id, active));
+            Ok(())
+        }
+
+        fn is_connected(&self) -> bool {
+            true
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -101,6 +506,7 @@ mod tests {
             id: "1".into(),
             channel: "whatsapp".into(),
             from: "+1555".into(),
+            sender_name: None,
             chat_id: "group@g.us".into(),
             text: "hello".into(),
             timestamp: 1234567890,
@@ -119,4 +525,120 @@ mod tests {
         assert!(mgr.list_channels().is_empty());
         assert!(mgr.get("whatsapp").is_none());
     }
+
+    fn poll_input(options: &[&str]) -> PollInput {
+        PollInput {
+            question: "Lunch?".into(),
+            options: options.iter().map(|o| o.to_string()).collect(),
+            max_selections: None,
+            duration_seconds: None,
+            duration_hours: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn poll_falls_back_to_numbered_list() {
+        let channel = mock::MockChannel::new("plain", ChannelCapabilities::default());
+        let mut mgr = ChannelManager::new();
+        mgr.register(Box::new(channel.clone()));
+
+        let outcome = mgr.dispatch(&OutboundAction::Poll {
+            channel: "plain".into(),
+            to: "+1555".into(),
+            poll: poll_input(&["Pizza", "Sushi"]),
+        }).await.unwrap();
+
+        assert_eq!(outcome.fallback.as_deref(), Some("poll"));
+        let sent = channel.sent.lock().unwrap();
+        assert!(sent[0].text.contains("1. Pizza"));
+        assert!(sent[0].text.contains("2. Sushi"));
+    }
+
+    #[tokio::test]
+    async fn native_poll_respects_channel_limits() {
+        let caps = ChannelCapabilities { polls: true, max_poll_options: Some(2), ..Default::default() };
+        let channel = mock::MockChannel::new("polly", caps);
+        let mut mgr = ChannelManager::new();
+        mgr.register(Box::new(channel.clone()));
+
+        let ok = mgr.dispatch(&OutboundAction::Poll {
+            channel: "polly".into(),
+            to: "+1555".into(),
+            poll: poll_input(&["A", "B"]),
+        }).await.unwrap();
+        assert_eq!(ok.message_ids, vec!["poll-1".to_string()]);
+
+        let err = mgr.dispatch(&OutboundAction::Poll {
+            channel: "polly".into(),
+            to: "+1555".into(),
+            poll: poll_input(&["A", "B", "C"]),
+        }).await.unwrap_err();
+        assert!(matches!(err, ChannelError::InvalidPoll(_)));
+    }
+
+    #[tokio::test]
+    async fn send_degrades_buttons_and_chunks_text() {
+        let caps = ChannelCapabilities { max_text_length: Some(10), ..Default::default() };
+        let channel = mock::MockChannel::new("sms", caps);
+        let mut mgr = ChannelManager::new();
+        mgr.register(Box::new(channel.clone()));
+
+        let mut msg = OutgoingMessage::text("sms", "+1555", "Pick one");
+        msg.buttons = vec![
+            MessageButton { id: "y".into(), label: "Yes".into() },
+            MessageButton { id: "n".into(), label: "No".into() },
+        ];
+        let outcome = mgr.send(&msg).await.unwrap();
+        assert_eq!(outcome.fallback.as_deref(), Some("buttons"));
+        assert!(outcome.message_ids.len() > 1);
+        let sent = channel.sent.lock().unwrap();
+        let joined: String = sent.iter().map(|m| m.text.as_str()).collect();
+        assert!(joined.contains("1. Yes"));
+        assert!(sent.iter().all(|m| m.buttons.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn edit_falls_back_to_new_message() {
+        let channel = mock::MockChannel::new("plain", ChannelCapabilities::default());
+        let mut mgr = ChannelManager::new();
+        mgr.register(Box::new(channel.clone()));
+
+        let outcome = mgr.dispatch(&OutboundAction::Edit {
+            channel: "plain".into(),
+            chat_id: "+1555".into(),
+            message_id: "m1".into(),
+            text: "fixed".into(),
+        }).await.unwrap();
+        assert_eq!(outcome.fallback.as_deref(), Some("edit"));
+        assert_eq!(channel.sent.lock().unwrap()[0].text, "fixed");
+    }
+
+    #[tokio::test]
+    async fn delete_without_capability_is_unsupported() {
+        let channel = mock::MockChannel::new("plain", ChannelCapabilities::default());
+        let mut mgr = ChannelManager::new();
+        mgr.register(Box::new(channel));
+
+        let err = mgr.dispatch(&OutboundAction::Delete {
+            channel: "plain".into(),
+            chat_id: "+1555".into(),
+            message_id: "m1".into(),
+        }).await.unwrap_err();
+        assert!(matches!(err, ChannelError::Unsupported(_)));
+    }
+
+    #[test]
+    fn chunk_text_prefers_line_breaks() {
+        let chunks = chunk_text("aaaa\nbbbb\ncccc", 10);
+        assert_eq!(chunks, vec!["aaaa\nbbbb\n".to_string(), "cccc".to_string()]);
+        assert_eq!(chunk_text("abcdef", 4), vec!["abcd".to_string(), "ef".to_string()]);
+    }
+
+    #[test]
+    fn outbound_action_serde_tag() {
+        let json = r#"{"action":"react","channel":"whatsapp","chatId":"c","messageId":"m","emoji":"👍"}"#;
+        let action: OutboundAction = serde_json::from_str(json).unwrap();
+        assert_eq!(action.channel(), "whatsapp");
+        assert!(matches!(action, OutboundAction::React { .. }));
+    }
 }
@@ -1,8 +1,15 @@
-use super::{ChannelError, ChannelPlugin, IncomingMessage, OutgoingMessage};
+use super::{ChannelCapabilities, ChannelError, ChannelPlugin, IncomingMessage, OutgoingMessage};
+use crate::polls::NormalizedPollInput;
 use crate::config::WhatsAppConfig;
 use async_trait::async_trait;
 use tracing::debug;
 
+/// WhatsApp caps native polls at 12 options.
+pub const WHATSAPP_MAX_POLL_OPTIONS: usize = 12;
+
+/// Chunk size for outgoing text (matches OpenClaw's textChunkLimit).
+pub const WHATSAPP_TEXT_CHUNK_LIMIT: usize = 4000;
+
 /// WhatsApp channel plugin.
 /// Communicates via the OpenClaw WebSocket protocol to the WhatsApp bridge.
 pub struct WhatsAppPlugin {
@@ -35,45 +42,13 @@ impl WhatsAppPlugin {
         }
     }
 
-    /// Check if a group message requires a mention based on group config.
-    pub fn requires_mention(&self, group_id: &str) -> bool {
-        if let Some(groups) = &self.config.groups {
-            // Check specific group config first
-            if let Some(group_config) = groups.get(group_id) {
-                return group_config.require_mention.unwrap_or(true);
-            }
-            // Check wildcard
-            if let Some(wildcard) = groups.get("*") {
-                return wildcard.require_mention.unwrap_or(true);
-            }
-        }
-        true // Default: require mention
-    }
-
     /// Check if a message should be processed.
     pub fn should_process(&self, msg: &IncomingMessage) -> bool {
-        // Check sender allowlist
-        if !self.is_sender_allowed(&msg.from) {
-            // For groups, check group policy
-            if msg.is_group {
-                let policy = self.config.group_policy.as_deref().unwrap_or("closed");
-                if policy != "open" {
-                    return false;
-                }
-            } else {
-                let dm_policy = self.config.dm_policy.as_deref().unwrap_or("disabled");
-                return dm_policy != "disabled";
-            }
+        if !self.accepts_sender(msg) {
+            return false;
         }
-
         // For groups, check mention requirement
-        if msg.is_group {
-            if self.requires_mention(&msg.chat_id) && !msg.mentions_bot {
-                return false;
-            }
-        }
-
-        true
+        !(msg.is_group && self.requires_mention(&msg.chat_id) && !msg.mentions_bot)
     }
 
     /// Get the debounce delay in milliseconds.
@@ -88,13 +63,29 @@ impl ChannelPlugin for WhatsAppPlugin {
         "whatsapp"
     }
 
-    async fn send(&self, message: &OutgoingMessage) -> Result<(), ChannelError> {
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            polls: true,
+            max_poll_options: Some(WHATSAPP_MAX_POLL_OPTIONS),
+            reactions: true,
+            edits: true,
+            deletes: true,
+            typing: true,
+            buttons: false,
+            max_buttons: None,
+            max_attachments: 1,
+            max_text_length: Some(WHATSAPP_TEXT_CHUNK_LIMIT),
+            replies: true,
+        }
+    }
+
+    async fn send(&self, message: &OutgoingMessage) -> Result<String, ChannelError> {
         if !self.connected {
             return Err(ChannelError::NotConnected);
         }
         // In a real implementation, this would send via the WhatsApp bridge WS connection
         debug!("WhatsApp send to {}: {}", message.to, message.text);
-        Ok(())
+        Ok(uuid::Uuid::new_v4().to_string())
     }
 
     async fn react(&self, chat_id: &str, message_id: &str, emoji: &str) -> Result<(), ChannelError> {
@@ -105,6 +96,65 @@ impl ChannelPlugin for WhatsAppPlugin {
         Ok(())
     }
 
+    async fn edit(&self, chat_id: &str, message_id: &str, text: &str) -> Result<(), ChannelError> {
+        if !self.connected {
+            return Err(ChannelError::NotConnected);
+        }
+        debug!("WhatsApp edit {} in {}: {}", message_id, chat_id, text);
+        Ok(())
+    }
+
+    async fn delete(&self, chat_id: &str, message_id: &str) -> Result<(), ChannelError> {
+        if !self.connected {
+            return Err(ChannelError::NotConnected);
+        }
+        debug!("WhatsApp delete {} in {}", message_id, chat_id);
+        Ok(())
+    }
+
+    async fn send_poll(&self, to: &str, poll: &NormalizedPollInput) -> Result<String, ChannelError> {
+        if !self.connected {
+            return Err(ChannelError::NotConnected);
+        }
+        debug!("WhatsApp poll to {}: {} ({} options)", to, poll.question, poll.options.len());
+        Ok(uuid::Uuid::new_v4().to_string())
+    }
+
+    async fn set_typing(&self, chat_id: &str, active: bool) -> Result<(), ChannelError> {
+        if !self.connected {
+            return Err(ChannelError::NotConnected);
+        }
+        debug!("WhatsApp typing={} in {}", active, chat_id);
+        Ok(())
+    }
+
+    fn accepts_sender(&self, msg: &IncomingMessage) -> bool {
+        if self.is_sender_allowed(&msg.from) {
+            return true;
+        }
+        // Unlisted senders depend on the group or DM policy
+        if msg.is_group {
+            self.config.group_policy.as_deref().unwrap_or("closed") == "open"
+        } else {
+            self.config.dm_policy.as_deref().unwrap_or("disabled") != "disabled"
+        }
+    }
+
+    /// Check if a group message requires a mention based on group config.
+    fn requires_mention(&self, group_id: &str) -> bool {
+        if let Some(groups) = &self.config.groups {
+            // Check specific group config first
+            if let Some(group_config) = groups.get(group_id) {
+                return group_config.require_mention.unwrap_or(true);
+            }
+            // Check wildcard
+            if let Some(wildcard) = groups.get("*") {
+                return wildcard.require_mention.unwrap_or(true);
+            }
+        }
+        true // Default: require mention
+    }
+
     fn is_connected(&self) -> bool {
         self.connected
     }
@@ -163,6 +213,7 @@ mod tests {
             id: "1".into(),
             channel: "whatsapp".into(),
             from: "+1999999999".into(), // Not in allowFrom
+            sender_name: None,
             chat_id: "group@g.us".into(),
             text: "hello".into(),
             timestamp: 0,
@@ -182,6 +233,7 @@ mod tests {
             id: "1".into(),
             channel: "whatsapp".into(),
             from: "+1999999999".into(),
+            sender_name: None,
             chat_id: "+1999999999".into(),
             text: "hello".into(),
             timestamp: 0,
@@ -194,6 +246,15 @@ mod tests {
         assert!(!plugin.should_process(&msg));
     }
 
+    #[test]
+    fn capabilities_advertise_native_polls() {
+        let plugin = WhatsAppPlugin::new(make_config());
+        let caps = plugin.capabilities();
+        assert!(caps.polls);
+        assert_eq!(caps.max_poll_options, Some(WHATSAPP_MAX_POLL_OPTIONS));
+        assert!(!caps.buttons);
+    }
+
     #[test]
     fn debounce_from_config() {
         let plugin = WhatsAppPlugin::new(make_config());
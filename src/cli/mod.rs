@@ -34,6 +34,16 @@ pub enum Commands {
         #[command(subcommand)]
         action: ConfigAction,
     },
+    /// Inspect or rebuild the memory search index
+    Memory {
+        #[command(subcommand)]
+        action: MemoryAction,
+    },
+    /// Manage scheduled jobs on the running gateway
+    Cron {
+        #[command(subcommand)]
+        action: CronAction,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -67,6 +77,71 @@ pub enum ConfigAction {
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum MemoryAction {
+    /// Show index status (files, chunks, last sync, errors)
+    Status,
+    /// Re-index changed memory files now
+    Sync,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CronAction {
+    /// List jobs with their next run
+    List,
+    /// Schedule a new agent turn
+    Add {
+        /// Interval ("30m"), cron expression ("0 0 9 * * *") or ISO 8601 timestamp
+        #[arg(long)]
+        schedule: String,
+        /// Prompt the agent runs
+        #[arg(long)]
+        prompt: String,
+        #[arg(long)]
+        id: Option<String>,
+        #[arg(long)]
+        name: Option<String>,
+        /// IANA timezone for the schedule
+        #[arg(long)]
+        timezone: Option<String>,
+        /// Session to run in: "isolated" (default), "main" or a name
+        #[arg(long)]
+        session: Option<String>,
+        /// Channel to deliver the reply on (with --to)
+        #[arg(long)]
+        channel: Option<String>,
+        #[arg(long)]
+        to: Option<String>,
+    },
+    /// Change a job's schedule, prompt or name
+    Update {
+        id: String,
+        #[arg(long)]
+        schedule: Option<String>,
+        #[arg(long)]
+        prompt: Option<String>,
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        timezone: Option<String>,
+    },
+    /// Delete a job
+    Remove { id: String },
+    /// Resume a disabled job
+    Enable { id: String },
+    /// Pause a job without deleting it
+    Disable { id: String },
+    /// Run a job now
+    Run {
+        id: String,
+        /// Wait for the run to finish and print it
+        #[arg(long)]
+        wait: bool,
+    },
+    /// Show a job's recent runs
+    History { id: String },
+}
+
 /// Run the CLI application.
 pub fn run() {
     let cli = Cli::parse();
@@ -101,6 +176,11 @@ pub fn run() {
                             }
                         }
 
+                        // --yes: run exec commands without asking
+                        if cli.yes {
+                            config.approvals.get_or_insert_with(Default::default).mode = Some("off".into());
+                        }
+
                         if let Err(e) = crate::gateway::start_gateway(config).await {
                             eprintln!("Gateway error: {}", e);
                             std::process::exit(1);
@@ -197,6 +277,24 @@ pub fn run() {
                 }
             }
         }
+        Some(Commands::Memory { action }) => {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            rt.block_on(async {
+                if let Err(e) = run_memory_action(action).await {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            });
+        }
+        Some(Commands::Cron { action }) => {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            rt.block_on(async {
+                if let Err(e) = run_cron_action(action).await {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            });
+        }
         None => {
             println!("rustyclaw {} — run with --help for usage", crate::VERSION);
         }
@@ -219,6 +317,129 @@ fn to_camel_case(s: &str) -> String {
     result
 }
 
+/// Build the memory index the gateway would use, from the on-disk config.
+fn load_memory_index(config: &crate::config::OpenClawConfig) -> Result<Option<crate::memory::MemoryIndex>, Box<dyn std::error::Error>> {
+    let options = crate::memory::MemorySearchOptions::from_config(config);
+    let workspace_dir = config.workspace_dir().unwrap_or("~/.openclaw/workspace").to_string();
+    let provider = crate::memory::embedding::provider_from_config(&config.memory.clone().unwrap_or_default())?;
+    Ok(provider.map(|provider| {
+        crate::memory::MemoryIndex::new(&workspace_dir, options, provider)
+            .with_store_path(crate::utils::resolve_config_dir().join("memory").join("index.json"))
+    }))
+}
+
+async fn run_memory_action(action: MemoryAction) -> Result<(), Box<dyn std::error::Error>> {
+    let config = crate::config::load_config()?;
+    let Some(index) = load_memory_index(&config)? else {
+        println!("No embedding provider configured (memory.provider); memory_search uses BM25 text search.");
+        return Ok(());
+    };
+    match action {
+        MemoryAction::Status => {
+            println!("{}", serde_json::to_string_pretty(&index.status().await)?);
+        }
+        MemoryAction::Sync => {
+            let stats = index.sync().await?;
+            println!(
+                "Indexed {} files ({} unchanged, {} removed); {} chunks total",
+                stats.indexed, stats.unchanged, stats.removed, stats.chunks,
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Call the local gateway's HTTP API, authenticating with the configured
+/// token. Error responses become errors carrying the gateway's message.
+async fn gateway_request(
+    method: reqwest::Method,
+    path: &str,
+    body: Option<serde_json::Value>,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let config = crate::config::load_config()?;
+    let port = crate::config::resolve_gateway_port(&config);
+    let mut request = reqwest::Client::new()
+        .request(method, format!("http://127.0.0.1:{}{}", port, path))
+        .timeout(std::time::Duration::from_secs(330));
+    if let Some(token) = crate::config::resolve_gateway_auth_token(&config) {
+        request = request.bearer_auth(token);
+    }
+    if let Some(body) = body {
+        request = request.json(&body);
+    }
+    let resp = request.send().await
+        .map_err(|e| format!("gateway not reachable on port {}: {}", port, e))?;
+    let status = resp.status();
+    let body: serde_json::Value = resp.json().await.unwrap_or(serde_json::Value::Null);
+    if !status.is_success() {
+        let message = body["error"].as_str().map(String::from).unwrap_or_else(|| status.to_string());
+        return Err(message.into());
+    }
+    Ok(body)
+}
+
+/// One line per job: id, state, schedule, next run and name.
+fn format_cron_jobs(jobs: &[serde_json::Value]) -> String {
+    if jobs.is_empty() {
+        return "No cron jobs.".into();
+    }
+    jobs.iter().map(|job| {
+        let state = if job["enabled"].as_bool().unwrap_or(true) { "on " } else { "off" };
+        format!(
+            "{:<14} {} {:<20} next: {:<26} {}",
+            job["id"].as_str().unwrap_or(""),
+            state,
+            job["schedule"].as_str().unwrap_or(""),
+            job["nextRun"].as_str().unwrap_or("-"),
+            job["name"].as_str().unwrap_or(""),
+        )
+    }).collect::<Vec<_>>().join("\n")
+}
+
+async fn run_cron_action(action: CronAction) -> Result<(), Box<dyn std::error::Error>> {
+    use reqwest::Method;
+    let job_path = |id: &str| format!("/v1/cron/jobs/{}", id);
+    let result = match action {
+        CronAction::List => {
+            let body = gateway_request(Method::GET, "/v1/cron/jobs", None).await?;
+            println!("{}", format_cron_jobs(body["jobs"].as_array().map(Vec::as_slice).unwrap_or(&[])));
+            return Ok(());
+        }
+        CronAction::Add { schedule, prompt, id, name, timezone, session, channel, to } => {
+            let body = serde_json::json!({
+                "id": id,
+                "name": name,
+                "schedule": schedule,
+                "prompt": prompt,
+                "timezone": timezone,
+                "sessionTarget": session,
+                "channel": channel,
+                "to": to,
+            });
+            gateway_request(Method::POST, "/v1/cron/jobs", Some(body)).await?
+        }
+        CronAction::Update { id, schedule, prompt, name, timezone } => {
+            let body = serde_json::json!({ "schedule": schedule, "prompt": prompt, "name": name, "timezone": timezone });
+            gateway_request(Method::PATCH, &job_path(&id), Some(body)).await?
+        }
+        CronAction::Remove { id } => gateway_request(Method::DELETE, &job_path(&id), None).await?,
+        CronAction::Enable { id } => {
+            gateway_request(Method::PATCH, &job_path(&id), Some(serde_json::json!({ "enabled": true }))).await?
+        }
+        CronAction::Disable { id } => {
+            gateway_request(Method::PATCH, &job_path(&id), Some(serde_json::json!({ "enabled": false }))).await?
+        }
+        CronAction::Run { id, wait } => {
+            gateway_request(Method::POST, &format!("{}/run?wait={}", job_path(&id), wait), None).await?
+        }
+        CronAction::History { id } => {
+            gateway_request(Method::GET, &format!("{}/history", job_path(&id)), None).await?
+        }
+    };
+    println!("{}", serde_json::to_string_pretty(&result)?);
+    Ok(())
+}
+
 async fn check_gateway_status() -> Result<String, Box<dyn std::error::Error>> {
     let config = crate::config::load_config()?;
     let port = crate::config::resolve_gateway_port(&config);
@@ -244,4 +465,33 @@ mod tests {
         assert_eq!(to_camel_case("simple"), "simple");
         assert_eq!(to_camel_case("a_b_c"), "aBC");
     }
+
+    #[test]
+    fn cron_subcommands_parse() {
+        let cli = Cli::try_parse_from(["rustyclaw", "cron", "add", "--schedule", "0 0 9 * * *", "--prompt", "Plan my day", "--timezone", "Europe/Berlin"]).unwrap();
+        match cli.command {
+            Some(Commands::Cron { action: CronAction::Add { schedule, timezone, id, .. } }) => {
+                assert_eq!(schedule, "0 0 9 * * *");
+                assert_eq!(timezone.as_deref(), Some("Europe/Berlin"));
+                assert!(id.is_none());
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        let cli = Cli::try_parse_from(["rustyclaw", "cron", "run", "abc", "--wait"]).unwrap();
+        assert!(matches!(cli.command, Some(Commands::Cron { action: CronAction::Run { wait: true, .. } })));
+        assert!(Cli::try_parse_from(["rustyclaw", "cron", "add", "--prompt", "no schedule"]).is_err());
+    }
+
+    #[test]
+    fn formats_cron_jobs() {
+        let jobs = vec![serde_json::json!({
+            "id": "standup", "name": "Standup", "schedule": "0 0 9 * * Mon-Fri",
+            "enabled": false, "nextRun": "2026-05-04T07:00:00Z",
+        })];
+        let out = format_cron_jobs(&jobs);
+        assert!(out.starts_with("standup"));
+        assert!(out.contains(" off "));
+        assert!(out.ends_with("Standup"));
+        assert_eq!(format_cron_jobs(&[]), "No cron jobs.");
+    }
 }
@@ -30,6 +30,8 @@ pub struct OpenClawConfig {
     pub ui: Option<UiConfig>,
     pub logging: Option<LoggingConfig>,
     pub approvals: Option<ApprovalsConfig>,
+    /// Rules sending channels, chats or senders to agents in `agents.list`.
+    pub bindings: Option<Vec<AgentBinding>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -134,12 +136,19 @@ pub struct MemorySyncConfig {
 #[serde(rename_all = "camelCase")]
 pub struct MemoryQueryConfig {
     pub hybrid: Option<HybridConfig>,
+    /// Boost for recent daily notes (memory/YYYY-MM-DD.md); 0 disables.
+    pub recency_weight: Option<f64>,
+    pub recency_half_life_days: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct HybridConfig {
     pub enabled: Option<bool>,
+    /// Reciprocal rank fusion constant (default 60).
+    pub rrf_k: Option<f64>,
+    pub vector_weight: Option<f64>,
+    pub text_weight: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -180,6 +189,36 @@ pub struct AgentEntry {
     pub agent_dir: Option<String>,
     pub model: Option<String>,
     pub group_chat: Option<GroupChatConfig>,
+    /// Handles messages no binding matches; the first agent when none is marked.
+    pub default: Option<bool>,
+    /// Tool policy applied on top of the global `tools` allow/deny.
+    pub tools: Option<AgentToolsConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentToolsConfig {
+    /// Only these tools, when set.
+    pub allow: Option<Vec<String>>,
+    pub deny: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentBinding {
+    pub agent_id: Option<String>,
+    #[serde(rename = "match")]
+    pub match_rule: Option<BindingMatch>,
+}
+
+/// Fields a binding matches on; unset fields match anything.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BindingMatch {
+    pub channel: Option<String>,
+    pub chat_id: Option<String>,
+    /// Sender id, e.g. a phone number.
+    pub from: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -419,6 +458,8 @@ pub struct PluginEntry {
 #[serde(rename_all = "camelCase")]
 pub struct CronConfig {
     pub jobs: Option<Vec<CronJobConfig>>,
+    /// IANA timezone for jobs that don't set their own; UTC when unset.
+    pub timezone: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -433,6 +474,13 @@ pub struct CronJobConfig {
     pub session_target: Option<String>,
     pub channel: Option<String>,
     pub to: Option<String>,
+    pub timeout_seconds: Option<u64>,
+    /// Missed-run policy after downtime: "skip", "runOnce" (default) or "runAllMissed".
+    pub catch_up: Option<String>,
+    /// IANA timezone for cron expressions and bare "at" timestamps.
+    pub timezone: Option<String>,
+    /// Agent the job runs as; the default agent when unset.
+    pub agent_id: Option<String>,
 }
 
 // ── Memory ──
@@ -441,8 +489,13 @@ pub struct CronJobConfig {
 #[serde(rename_all = "camelCase")]
 pub struct MemoryConfig {
     pub enabled: Option<bool>,
+    /// Embedding provider: "openai" (any OpenAI-compatible endpoint) or "hash" (local).
     pub provider: Option<String>,
     pub embedding_model: Option<String>,
+    pub base_url: Option<String>,
+    pub api_key: Option<String>,
+    /// Vector size for the local hashing embedder.
+    pub dimensions: Option<usize>,
 }
 
 // ── Tools ──
@@ -453,6 +506,109 @@ pub struct ToolsConfig {
     pub deny: Option<Vec<String>>,
     pub allow: Option<Vec<String>>,
     pub also_allow: Option<Vec<String>>,
+    pub message: Option<MessageToolConfig>,
+    pub web: Option<WebToolsConfig>,
+    pub exec: Option<ExecToolConfig>,
+    pub files: Option<FileAccessConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FileAccessConfig {
+    /// Keep Read/Write/Edit inside the workspace and `roots` (default true).
+    pub confine: Option<bool>,
+    /// Extra directories the file tools may use.
+    pub roots: Option<Vec<String>>,
+    /// Glob patterns that are never read or written, on top of the defaults
+    /// (~/.ssh, ~/.gnupg, ~/.aws, ~/.netrc and the gateway config).
+    pub deny: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecToolConfig {
+    /// Output kept from a command (stdout and stderr together); the command
+    /// is stopped once it writes more.
+    pub max_output_bytes: Option<usize>,
+    pub sandbox: Option<ExecSandboxConfig>,
+    /// Background processes running at once.
+    pub max_processes: Option<usize>,
+    /// Output kept per background process; older output is dropped.
+    pub process_buffer_bytes: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecSandboxConfig {
+    /// "off" (default), "auto" (bubblewrap if installed, else resource
+    /// limits), "bubblewrap" or "rlimit".
+    pub mode: Option<String>,
+    /// CPU time limit per command; 0 for none.
+    pub cpu_seconds: Option<u64>,
+    /// Address-space limit per command; 0 for none.
+    pub memory_mb: Option<u64>,
+    /// Allow network access (only enforced with bubblewrap).
+    pub network: Option<bool>,
+    /// Environment variables passed through; everything else is dropped.
+    pub pass_env: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageToolConfig {
+    /// Outbound targets the agent may message ("whatsapp:+1555…", "whatsapp:*", "*").
+    pub allow: Option<Vec<String>>,
+    /// Contact aliases, e.g. "mom" → "whatsapp:+1555…".
+    pub aliases: Option<HashMap<String, String>>,
+    pub default_channel: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WebToolsConfig {
+    pub fetch: Option<WebFetchConfig>,
+    pub search: Option<WebSearchConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WebSearchConfig {
+    pub enabled: Option<bool>,
+    /// Backend: "brave" (default), "searxng" or "json".
+    pub provider: Option<String>,
+    pub api_key: Option<String>,
+    /// Endpoint override (Brave API URL, SearXNG base URL, or JSON endpoint).
+    pub base_url: Option<String>,
+    pub max_results: Option<usize>,
+    pub timeout_seconds: Option<u64>,
+    pub cache_ttl_minutes: Option<u64>,
+    pub json: Option<JsonSearchConfig>,
+}
+
+/// Field mapping for a generic JSON-over-HTTP search endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonSearchConfig {
+    pub query_param: Option<String>,
+    pub count_param: Option<String>,
+    /// Dot path to the results array, e.g. "data.items".
+    pub results_path: Option<String>,
+    pub title_field: Option<String>,
+    pub url_field: Option<String>,
+    pub snippet_field: Option<String>,
+    pub headers: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WebFetchConfig {
+    pub max_bytes: Option<usize>,
+    pub max_chars: Option<usize>,
+    pub timeout_seconds: Option<u64>,
+    pub max_redirects: Option<usize>,
+    /// Allow fetching loopback/private/link-local addresses (off by default).
+    pub allow_private_network: Option<bool>,
+    pub user_agent: Option<String>,
 }
 
 // ── Hooks ──
@@ -542,7 +698,17 @@ pub struct LoggingConfig {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ApprovalsConfig {
+    /// "off", "on-miss" (ask for commands off the allowlist) or "always".
     pub mode: Option<String>,
+    /// Command patterns that run without asking; `*` matches anything.
+    pub allow: Option<Vec<String>>,
+    /// Command patterns that never run, whatever the mode.
+    pub deny: Option<Vec<String>>,
+    /// How long to wait for a decision before failing the call.
+    pub timeout_seconds: Option<u64>,
+    /// Also ask in this chat (channel name, with `to`).
+    pub target: Option<String>,
+    pub to: Option<String>,
 }
 
 impl OpenClawConfig {
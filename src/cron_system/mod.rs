@@ -1,12 +1,81 @@
+pub mod run;
+pub mod schedule;
+
+pub use run::{CronExecutor, CronRun, RunStatus, SessionTarget};
+pub use schedule::{parse_timezone, Schedule};
+
+use crate::channel::{ChannelManager, OutgoingMessage};
 use crate::config::CronJobConfig;
+use crate::utils::excerpt;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{info, warn};
+use sha2::Digest;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{Notify, RwLock};
+use tracing::{debug, info, warn};
+
+/// Most runs made at once when catching up on missed occurrences.
+pub const MAX_CATCH_UP_RUNS: usize = 24;
+
+/// How late an occurrence may fire and still count as on time.
+const MISSED_GRACE: chrono::Duration = chrono::Duration::seconds(90);
+
+/// Job kinds the service can run.
+pub const SUPPORTED_KINDS: &[&str] = &["agentTurn"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum CronError {
+    #[error("Cron job not found: {0}")]
+    NotFound(String),
+    #[error("Cron job already exists: {0}")]
+    Duplicate(String),
+    #[error("Invalid cron job: {0}")]
+    Invalid(String),
+}
+
+/// What to do about occurrences missed while the gateway was down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CatchUp {
+    /// Drop missed runs and wait for the next occurrence.
+    Skip,
+    /// Run once for all missed occurrences.
+    #[default]
+    RunOnce,
+    /// Run once per missed occurrence, up to `MAX_CATCH_UP_RUNS`.
+    RunAllMissed,
+}
+
+impl CatchUp {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().replace(['-', '_'], "").as_str() {
+            "skip" => Some(Self::Skip),
+            "runonce" | "once" => Some(Self::RunOnce),
+            "runallmissed" | "runall" | "all" => Some(Self::RunAllMissed),
+            _ => None,
+        }
+    }
+}
+
+/// Where a job was defined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobSource {
+    /// `cron.jobs` in the config file; dropped from the store when removed there.
+    Config,
+    /// Added at runtime through the API or CLI.
+    #[default]
+    Runtime,
+    /// Created by the agent with the `cron` tool.
+    Agent,
+}
 
 /// A scheduled cron job.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct CronJob {
     pub id: String,
     pub name: String,
@@ -17,6 +86,18 @@ pub struct CronJob {
     pub session_target: Option<String>,
     pub channel: Option<String>,
     pub to: Option<String>,
+    /// Per-run limit; `run::DEFAULT_JOB_TIMEOUT` when unset.
+    pub timeout_seconds: Option<u64>,
+    /// IANA timezone the schedule is evaluated in; UTC when unset.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub catch_up: CatchUp,
+    #[serde(default)]
+    pub source: JobSource,
+    /// Agent the job runs as; the default agent when unset.
+    #[serde(default)]
+    pub agent_id: Option<String>,
     pub last_run: Option<DateTime<Utc>>,
     pub next_run: Option<DateTime<Utc>>,
     pub run_count: u64,
@@ -24,13 +105,34 @@ pub struct CronJob {
 
 impl CronJob {
     pub fn from_config(config: &CronJobConfig) -> Self {
-        let id = config.id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        Self::from_config_in(config, None)
+    }
+
+    /// Build from config, using `default_timezone` when the job sets none.
+    pub fn from_config_in(config: &CronJobConfig, default_timezone: Option<&str>) -> Self {
         let schedule = config.schedule.clone().unwrap_or_default();
-        let next_run = parse_next_run(&schedule);
+        let name = config.name.clone().unwrap_or_default();
+        // Without an explicit id, derive a stable one so persisted state survives restarts
+        let id = config.id.clone().unwrap_or_else(|| {
+            if name.is_empty() && schedule.is_empty() {
+                return uuid::Uuid::new_v4().to_string();
+            }
+            let digest = sha2::Sha256::digest(format!("{}\n{}\n{}", name, schedule, config.prompt.as_deref().unwrap_or("")));
+            hex::encode(&digest[..6])
+        });
+        let catch_up = match config.catch_up.as_deref() {
+            Some(raw) => CatchUp::parse(raw).unwrap_or_else(|| {
+                warn!("Unknown catchUp '{}' for cron job {}, using runOnce", raw, id);
+                CatchUp::RunOnce
+            }),
+            None => CatchUp::default(),
+        };
+        let timezone = config.timezone.clone().or_else(|| default_timezone.map(String::from));
+        let next_run = parse_next_run(&schedule, timezone.as_deref());
 
         Self {
             id,
-            name: config.name.clone().unwrap_or_default(),
+            name,
             schedule,
             enabled: config.enabled.unwrap_or(true),
             kind: config.kind.clone().unwrap_or_else(|| "agentTurn".to_string()),
@@ -38,6 +140,11 @@ impl CronJob {
             session_target: config.session_target.clone(),
             channel: config.channel.clone(),
             to: config.to.clone(),
+            timeout_seconds: config.timeout_seconds,
+            timezone,
+            catch_up,
+            source: JobSource::Config,
+            agent_id: config.agent_id.clone(),
             last_run: None,
             next_run,
             run_count: 0,
@@ -55,30 +162,149 @@ impl CronJob {
         }
     }
 
+    pub fn timeout(&self) -> Duration {
+        self.timeout_seconds
+            .filter(|s| *s > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(run::DEFAULT_JOB_TIMEOUT)
+    }
+
+    /// The job's schedule in its timezone.
+    pub fn parsed_schedule(&self) -> Result<Schedule, String> {
+        let tz = match self.timezone.as_deref() {
+            Some(name) => parse_timezone(name)?,
+            None => chrono_tz::Tz::UTC,
+        };
+        Schedule::parse_in(&self.schedule, tz)
+    }
+
+    /// The next `n` run times.
+    pub fn preview(&self, n: usize) -> Result<Vec<DateTime<Utc>>, String> {
+        let schedule = self.parsed_schedule()?;
+        Ok(schedule.upcoming(Utc::now(), self.next_run, n))
+    }
+
     /// Advance to the next scheduled run.
     pub fn advance(&mut self) {
         self.last_run = Some(Utc::now());
         self.run_count += 1;
-        self.next_run = parse_next_run(&self.schedule);
+        self.next_run = parse_next_run(&self.schedule, self.timezone.as_deref());
     }
-}
 
-/// Parse a cron schedule string and compute the next run time.
-fn parse_next_run(schedule: &str) -> Option<DateTime<Utc>> {
-    if schedule.is_empty() {
-        return None;
-    }
+    /// If due at `now`, move `next_run` past `now` and return how many runs
+    /// to make under the job's catch-up policy (0 when missed runs are skipped).
+    pub fn take_due(&mut self, now: DateTime<Utc>) -> usize {
+        if !self.should_run(&now) {
+            return 0;
+        }
+        let Some(due) = self.next_run else { return 0 };
+        let schedule = match self.parsed_schedule() {
+            Ok(schedule) => schedule,
+            Err(e) => {
+                warn!("Cron job {}: {}", self.id, e);
+                self.next_run = None;
+                return 0;
+            }
+        };
+
+        let occurrences = schedule.occurrences(due, now, MAX_CATCH_UP_RUNS);
+        let latest = occurrences.last().copied().unwrap_or(due);
+        let runs = match self.catch_up {
+            CatchUp::Skip => usize::from(now - latest <= MISSED_GRACE),
+            CatchUp::RunOnce => 1,
+            CatchUp::RunAllMissed => occurrences.len().max(1),
+        };
+        if occurrences.len() > 1 || runs == 0 {
+            info!("Cron job {} missed {} run(s), catching up with {}", self.id, occurrences.len(), runs);
+        }
 
-    // Support simple interval format: "30m", "1h", "24h"
-    if let Some(duration) = parse_interval(schedule) {
-        return Some(Utc::now() + duration);
+        self.next_run = schedule.next_after(now, Some(due));
+        if runs > 0 {
+            self.last_run = Some(now);
+            self.run_count += runs as u64;
+        }
+        runs
     }
 
-    // Try standard cron expression
-    match schedule.parse::<cron::Schedule>() {
-        Ok(sched) => {
-            sched.upcoming(Utc).next()
+    /// Apply the fields set in `patch` (its `id` is ignored) and validate the
+    /// result. The next run is recomputed when the schedule or timezone changes.
+    pub fn apply(&mut self, patch: &CronJobConfig) -> Result<(), CronError> {
+        let mut job = self.clone();
+        let set = |field: &mut Option<String>, value: &Option<String>| {
+            if let Some(value) = value {
+                let value = value.trim();
+                *field = (!value.is_empty()).then(|| value.to_string());
+            }
+        };
+        if let Some(name) = &patch.name {
+            job.name = name.trim().to_string();
+        }
+        if let Some(schedule) = &patch.schedule {
+            job.schedule = schedule.trim().to_string();
+        }
+        if let Some(enabled) = patch.enabled {
+            job.enabled = enabled;
+        }
+        if let Some(kind) = &patch.kind {
+            job.kind = kind.trim().to_string();
+        }
+        set(&mut job.prompt, &patch.prompt);
+        set(&mut job.session_target, &patch.session_target);
+        set(&mut job.channel, &patch.channel);
+        set(&mut job.to, &patch.to);
+        set(&mut job.timezone, &patch.timezone);
+        set(&mut job.agent_id, &patch.agent_id);
+        if let Some(timeout) = patch.timeout_seconds {
+            job.timeout_seconds = (timeout > 0).then_some(timeout);
+        }
+        if let Some(raw) = &patch.catch_up {
+            job.catch_up = CatchUp::parse(raw)
+                .ok_or_else(|| CronError::Invalid(format!("unknown catchUp '{}'", raw)))?;
+        }
+
+        if !SUPPORTED_KINDS.contains(&job.kind.as_str()) {
+            return Err(CronError::Invalid(format!("unsupported kind '{}'", job.kind)));
         }
+        if job.prompt.is_none() {
+            return Err(CronError::Invalid("prompt is required".into()));
+        }
+        if job.channel.is_some() != job.to.is_some() {
+            return Err(CronError::Invalid("channel and to must be set together".into()));
+        }
+        if job.schedule.is_empty() {
+            return Err(CronError::Invalid("schedule is required".into()));
+        }
+        if job.schedule != self.schedule || job.timezone != self.timezone {
+            let next = validate_schedule(&job.schedule, job.timezone.as_deref()).map_err(CronError::Invalid)?;
+            job.next_run = Some(next);
+        }
+        if job.name.is_empty() {
+            job.name = job.id.clone();
+        }
+        *self = job;
+        Ok(())
+    }
+}
+
+/// Parse and validate a schedule, returning the first run after now.
+pub fn validate_schedule(schedule: &str, timezone: Option<&str>) -> Result<DateTime<Utc>, String> {
+    let tz = match timezone {
+        Some(name) => parse_timezone(name)?,
+        None => chrono_tz::Tz::UTC,
+    };
+    let now = Utc::now();
+    let parsed = Schedule::parse_in(schedule, tz)?;
+    parsed.validate(now)?;
+    parsed.next_after(now, None).ok_or_else(|| "schedule never fires".into())
+}
+
+/// Parse a schedule string and compute the next run time.
+fn parse_next_run(schedule: &str, timezone: Option<&str>) -> Option<DateTime<Utc>> {
+    if schedule.is_empty() {
+        return None;
+    }
+    match validate_schedule(schedule, timezone) {
+        Ok(next) => Some(next),
         Err(e) => {
             warn!("Invalid cron schedule '{}': {}", schedule, e);
             None
@@ -86,20 +312,19 @@ fn parse_next_run(schedule: &str) -> Option<DateTime<Utc>> {
     }
 }
 
-/// Parse interval strings like "30m", "1h", "24h", "60s".
-fn parse_interval(s: &str) -> Option<chrono::Duration> {
-    let s = s.trim();
-    if s.ends_with('s') {
-        s[..s.len()-1].parse::<i64>().ok().map(chrono::Duration::seconds)
-    } else if s.ends_with('m') {
-        s[..s.len()-1].parse::<i64>().ok().map(chrono::Duration::minutes)
-    } else if s.ends_with('h') {
-        s[..s.len()-1].parse::<i64>().ok().map(chrono::Duration::hours)
-    } else if s.ends_with('d') {
-        s[..s.len()-1].parse::<i64>().ok().map(chrono::Duration::days)
-    } else {
-        None
-    }
+/// A job that came due, with how many times to run it.
+#[derive(Debug, Clone)]
+pub struct DueJob {
+    pub job: CronJob,
+    pub runs: usize,
+}
+
+/// On-disk form of the service state.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CronStore {
+    jobs: Vec<CronJob>,
+    #[serde(default)]
+    runs: HashMap<String, VecDeque<CronRun>>,
 }
 
 /// The cron service manages scheduled jobs.
@@ -107,6 +332,30 @@ fn parse_interval(s: &str) -> Option<chrono::Duration> {
 pub struct CronService {
     jobs: Arc<RwLock<Vec<CronJob>>>,
     running: Arc<RwLock<bool>>,
+    executor: Option<Arc<dyn CronExecutor>>,
+    channels: Option<Arc<RwLock<ChannelManager>>>,
+    /// Ids of jobs with a run in progress.
+    in_flight: Arc<Mutex<HashSet<String>>>,
+    history: Arc<RwLock<HashMap<String, VecDeque<CronRun>>>>,
+    store_path: Option<PathBuf>,
+    default_timezone: Option<String>,
+    /// Wakes the scheduler when jobs change.
+    wake: Arc<Notify>,
+}
+
+/// Longest the scheduler sleeps before re-checking, in case the clock jumps.
+const MAX_IDLE: Duration = Duration::from_secs(60);
+
+/// Clears a job's in-flight mark when its run ends, even on panic.
+struct InFlightGuard {
+    in_flight: Arc<Mutex<HashSet<String>>>,
+    job_id: String,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.in_flight.lock().unwrap().remove(&self.job_id);
+    }
 }
 
 impl CronService {
@@ -114,23 +363,168 @@ impl CronService {
         Self {
             jobs: Arc::new(RwLock::new(Vec::new())),
             running: Arc::new(RwLock::new(false)),
+            executor: None,
+            channels: None,
+            in_flight: Arc::new(Mutex::new(HashSet::new())),
+            history: Arc::new(RwLock::new(HashMap::new())),
+            store_path: None,
+            default_timezone: None,
+            wake: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Timezone for config jobs that don't set one (`cron.timezone`).
+    pub fn with_timezone(mut self, timezone: &str) -> Self {
+        match parse_timezone(timezone) {
+            Ok(_) => self.default_timezone = Some(timezone.trim().to_string()),
+            Err(e) => warn!("Ignoring cron.timezone: {}", e),
+        }
+        self
+    }
+
+    /// Persist jobs, their schedule state and run history to a JSON file,
+    /// loading whatever is already stored there.
+    pub fn with_store_path(mut self, path: PathBuf) -> Self {
+        match std::fs::read_to_string(&path) {
+            Ok(raw) => match serde_json::from_str::<CronStore>(&raw) {
+                Ok(store) => {
+                    info!("Loaded {} cron jobs from {}", store.jobs.len(), path.display());
+                    self.jobs = Arc::new(RwLock::new(store.jobs));
+                    self.history = Arc::new(RwLock::new(store.runs));
+                }
+                Err(e) => warn!("Ignoring unreadable cron store {}: {}", path.display(), e),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Failed to read cron store {}: {}", path.display(), e),
+        }
+        self.store_path = Some(path);
+        self
+    }
+
+    async fn persist(&self) {
+        let Some(path) = &self.store_path else { return };
+        let store = CronStore {
+            jobs: self.jobs.read().await.clone(),
+            runs: self.history.read().await.clone(),
+        };
+        let result = serde_json::to_string_pretty(&store)
+            .map_err(std::io::Error::other)
+            .and_then(|json| {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                let tmp = path.with_extension("json.tmp");
+                std::fs::write(&tmp, json)?;
+                std::fs::rename(&tmp, path)
+            });
+        if let Err(e) = result {
+            warn!("Failed to write cron store {}: {}", path.display(), e);
         }
     }
 
-    /// Load jobs from config.
+    /// Runs `agentTurn` jobs; without one they fail with an error run.
+    pub fn with_executor(mut self, executor: Arc<dyn CronExecutor>) -> Self {
+        self.executor = Some(executor);
+        self
+    }
+
+    /// Delivers results of jobs that set `channel` and `to`.
+    pub fn with_channels(mut self, channels: Arc<RwLock<ChannelManager>>) -> Self {
+        self.channels = Some(channels);
+        self
+    }
+
+    /// Load jobs from config. Jobs already in the store keep their run state
+    /// (and their pending next run if the schedule is unchanged); config jobs
+    /// no longer in the config are dropped, runtime jobs are kept.
     pub async fn load_from_config(&self, jobs: &[CronJobConfig]) {
-        let mut store = self.jobs.write().await;
-        store.clear();
-        for job_config in jobs {
-            store.push(CronJob::from_config(job_config));
+        {
+            let mut store = self.jobs.write().await;
+            let previous = std::mem::take(&mut *store);
+            for job_config in jobs {
+                let mut job = CronJob::from_config_in(job_config, self.default_timezone.as_deref());
+                if let Some(prev) = previous.iter().find(|p| p.id == job.id) {
+                    job.last_run = prev.last_run;
+                    job.run_count = prev.run_count;
+                    if prev.schedule == job.schedule && prev.timezone == job.timezone {
+                        job.next_run = prev.next_run;
+                    }
+                }
+                store.push(job);
+            }
+            for prev in previous {
+                if prev.source != JobSource::Config && !store.iter().any(|j| j.id == prev.id) {
+                    store.push(prev);
+                }
+            }
+            info!("Loaded {} cron jobs", store.len());
         }
-        info!("Loaded {} cron jobs", store.len());
+        self.persist().await;
+        self.wake.notify_one();
     }
 
     /// Add a job.
     pub async fn add_job(&self, job: CronJob) {
-        let mut jobs = self.jobs.write().await;
-        jobs.push(job);
+        self.jobs.write().await.push(job);
+        self.persist().await;
+        self.wake.notify_one();
+    }
+
+    /// Create and add a job from API input. Without an `id` a short random
+    /// one is assigned; jobs use the service timezone unless they set one.
+    pub async fn create_job(&self, input: &CronJobConfig, source: JobSource) -> Result<CronJob, CronError> {
+        let id = match input.id.as_deref().map(str::trim) {
+            Some("") | None => uuid::Uuid::new_v4().simple().to_string()[..12].to_string(),
+            Some(id) => id.to_string(),
+        };
+        let mut job = CronJob::from_config_in(
+            &CronJobConfig { id: Some(id.clone()), ..Default::default() },
+            self.default_timezone.as_deref(),
+        );
+        job.source = source;
+        job.apply(input)?;
+
+        {
+            let mut jobs = self.jobs.write().await;
+            if jobs.iter().any(|j| j.id == id) {
+                return Err(CronError::Duplicate(id));
+            }
+            jobs.push(job.clone());
+        }
+        info!("Added cron job {} ({})", job.id, job.schedule);
+        self.persist().await;
+        self.wake.notify_one();
+        Ok(job)
+    }
+
+    /// Change a job's fields; see [`CronJob::apply`]. Config jobs revert to
+    /// their configured values on the next config load.
+    pub async fn update_job(&self, id: &str, patch: &CronJobConfig) -> Result<CronJob, CronError> {
+        let job = {
+            let mut jobs = self.jobs.write().await;
+            let job = jobs.iter_mut().find(|j| j.id == id).ok_or_else(|| CronError::NotFound(id.to_string()))?;
+            job.apply(patch)?;
+            job.clone()
+        };
+        self.persist().await;
+        self.wake.notify_one();
+        Ok(job)
+    }
+
+    /// Run a job immediately, outside its schedule.
+    pub async fn run_now(&self, id: &str) -> Result<CronRun, CronError> {
+        let job = self.get_job(id).await.ok_or_else(|| CronError::NotFound(id.to_string()))?;
+        Ok(self.run_job(&job).await)
+    }
+
+    /// Start a run of a job in the background; see `history` for the result.
+    pub async fn trigger(&self, id: &str) -> Result<(), CronError> {
+        let job = self.get_job(id).await.ok_or_else(|| CronError::NotFound(id.to_string()))?;
+        let service = self.clone();
+        tokio::spawn(async move {
+            service.run_job(&job).await;
+        });
+        Ok(())
     }
 
     /// List all jobs.
@@ -147,40 +541,183 @@ impl CronService {
 
     /// Enable/disable a job.
     pub async fn set_enabled(&self, id: &str, enabled: bool) -> bool {
-        let mut jobs = self.jobs.write().await;
-        if let Some(job) = jobs.iter_mut().find(|j| j.id == id) {
-            job.enabled = enabled;
-            true
-        } else {
-            false
+        let found = {
+            let mut jobs = self.jobs.write().await;
+            match jobs.iter_mut().find(|j| j.id == id) {
+                Some(job) => {
+                    job.enabled = enabled;
+                    true
+                }
+                None => false,
+            }
+        };
+        if found {
+            self.persist().await;
+            self.wake.notify_one();
         }
+        found
     }
 
     /// Remove a job.
     pub async fn remove_job(&self, id: &str) -> bool {
-        let mut jobs = self.jobs.write().await;
-        let len_before = jobs.len();
-        jobs.retain(|j| j.id != id);
-        jobs.len() < len_before
+        let removed = {
+            let mut jobs = self.jobs.write().await;
+            let len_before = jobs.len();
+            jobs.retain(|j| j.id != id);
+            jobs.len() < len_before
+        };
+        if removed {
+            self.history.write().await.remove(id);
+            self.persist().await;
+        }
+        removed
     }
 
-    /// Check for due jobs and return them.
-    pub async fn check_due_jobs(&self) -> Vec<CronJob> {
-        let now = Utc::now();
-        let mut jobs = self.jobs.write().await;
+    /// Advance every due job past `now` and return those that should run,
+    /// applying each job's catch-up policy to missed occurrences.
+    pub async fn check_due_jobs_at(&self, now: DateTime<Utc>) -> Vec<DueJob> {
         let mut due = Vec::new();
+        let mut changed = false;
+        {
+            let mut jobs = self.jobs.write().await;
+            for job in jobs.iter_mut() {
+                if !job.should_run(&now) {
+                    continue;
+                }
+                changed = true;
+                let runs = job.take_due(now);
+                if runs > 0 {
+                    due.push(DueJob { job: job.clone(), runs });
+                }
+            }
+        }
+        if changed {
+            self.persist().await;
+        }
+        due
+    }
+
+    /// Upcoming run times of a job.
+    pub async fn preview(&self, id: &str, n: usize) -> Option<Result<Vec<DateTime<Utc>>, String>> {
+        self.get_job(id).await.map(|job| job.preview(n))
+    }
+
+    /// Earliest `next_run` among enabled jobs.
+    pub async fn next_wake(&self) -> Option<DateTime<Utc>> {
+        let jobs = self.jobs.read().await;
+        jobs.iter().filter(|j| j.enabled).filter_map(|j| j.next_run).min()
+    }
+
+    /// Check for due jobs and return them.
+    pub async fn check_due_jobs(&self) -> Vec<DueJob> {
+        self.check_due_jobs_at(Utc::now()).await
+    }
+
+    /// Execute a job now: run its agent turn with a timeout, deliver the
+    /// reply and record the run. A job whose previous run hasn't finished is
+    /// skipped.
+    pub async fn run_job(&self, job: &CronJob) -> CronRun {
+        if !self.in_flight.lock().unwrap().insert(job.id.clone()) {
+            warn!("Cron job {} still running, skipping this run", job.id);
+            let run = CronRun::skipped(&job.id, "previous run still in progress");
+            self.record(run.clone()).await;
+            return run;
+        }
+        let _guard = InFlightGuard { in_flight: self.in_flight.clone(), job_id: job.id.clone() };
+
+        let started_at = Utc::now();
+        let clock = Instant::now();
+        let result = match tokio::time::timeout(job.timeout(), self.execute(job)).await {
+            Ok(result) => result,
+            Err(_) => Err((RunStatus::Timeout, format!("timed out after {}s", job.timeout().as_secs()))),
+        };
 
-        for job in jobs.iter_mut() {
-            if job.should_run(&now) {
-                due.push(job.clone());
-                job.advance();
+        let mut run = CronRun {
+            job_id: job.id.clone(),
+            started_at,
+            duration_ms: 0,
+            status: RunStatus::Ok,
+            excerpt: None,
+            error: None,
+            delivered: Vec::new(),
+        };
+        match result {
+            Ok(reply) => {
+                run.excerpt = Some(excerpt(&reply));
+                match self.deliver(job, &reply).await {
+                    Ok(ids) => run.delivered = ids,
+                    Err(e) => {
+                        run.status = RunStatus::Error;
+                        run.error = Some(format!("delivery failed: {}", e));
+                    }
+                }
+            }
+            Err((status, error)) => {
+                run.status = status;
+                run.error = Some(error);
             }
         }
+        run.duration_ms = clock.elapsed().as_millis() as u64;
 
-        due
+        match run.status {
+            RunStatus::Ok => info!("Cron job {} finished in {}ms", job.id, run.duration_ms),
+            _ => warn!("Cron job {} {:?}: {}", job.id, run.status, run.error.as_deref().unwrap_or("")),
+        }
+        self.record(run.clone()).await;
+        run
+    }
+
+    async fn execute(&self, job: &CronJob) -> Result<String, (RunStatus, String)> {
+        let fail = |e: String| (RunStatus::Error, e);
+        if job.kind != "agentTurn" {
+            return Err(fail(format!("unsupported job kind '{}'", job.kind)));
+        }
+        let prompt = job.prompt.as_deref().map(str::trim).unwrap_or("");
+        if prompt.is_empty() {
+            return Err(fail("job has no prompt".into()));
+        }
+        let executor = self.executor.as_ref().ok_or_else(|| fail("no agent executor configured".into()))?;
+        let target = SessionTarget::parse(job.session_target.as_deref());
+        executor.run_agent_turn(job, prompt, &target).await.map_err(fail)
+    }
+
+    async fn deliver(&self, job: &CronJob, reply: &str) -> Result<Vec<String>, String> {
+        let (Some(channel), Some(to)) = (job.channel.as_deref(), job.to.as_deref()) else {
+            return Ok(Vec::new());
+        };
+        if reply.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        let channels = self.channels.as_ref().ok_or("no channels configured")?;
+        let outcome = channels.read().await
+            .send(&OutgoingMessage::text(channel, to, reply))
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(outcome.message_ids)
+    }
+
+    async fn record(&self, run: CronRun) {
+        {
+            let mut history = self.history.write().await;
+            let runs = history.entry(run.job_id.clone()).or_default();
+            runs.push_back(run);
+            while runs.len() > run::MAX_RUN_HISTORY {
+                runs.pop_front();
+            }
+        }
+        self.persist().await;
+    }
+
+    /// Recent runs of a job, newest first.
+    pub async fn history(&self, id: &str) -> Vec<CronRun> {
+        let history = self.history.read().await;
+        history.get(id)
+            .map(|runs| runs.iter().rev().cloned().collect())
+            .unwrap_or_default()
     }
 
-    /// Start the cron tick loop.
+    /// Start the scheduler: sleep until the earliest `next_run` (or until
+    /// jobs change), then run whatever is due.
     pub async fn start(&self) {
         {
             let mut running = self.running.write().await;
@@ -190,30 +727,37 @@ impl CronService {
             *running = true;
         }
 
-        let jobs = self.jobs.clone();
-        let running = self.running.clone();
+        let service = self.clone();
 
         tokio::spawn(async move {
             info!("Cron service started");
             loop {
                 {
-                    let is_running = running.read().await;
+                    let is_running = service.running.read().await;
                     if !*is_running {
                         break;
                     }
                 }
 
-                // Check every 30 seconds
-                tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+                for due in service.check_due_jobs().await {
+                    info!("Cron job due: {} ({}), {} run(s)", due.job.name, due.job.id, due.runs);
+                    let service = service.clone();
+                    tokio::spawn(async move {
+                        // Catch-up runs go one after another
+                        for _ in 0..due.runs {
+                            service.run_job(&due.job).await;
+                        }
+                    });
+                }
 
-                let now = Utc::now();
-                let mut store = jobs.write().await;
-                for job in store.iter_mut() {
-                    if job.should_run(&now) {
-                        info!("Cron job due: {} ({})", job.name, job.id);
-                        job.advance();
-                        // In a full implementation, this would trigger the agent run
-                    }
+                let wait = match service.next_wake().await {
+                    Some(next) => (next - Utc::now()).to_std().unwrap_or(Duration::ZERO).min(MAX_IDLE),
+                    None => MAX_IDLE,
+                };
+                debug!("Cron sleeping {:?}", wait);
+                tokio::select! {
+                    _ = tokio::time::sleep(wait) => {}
+                    _ = service.wake.notified() => {}
                 }
             }
             info!("Cron service stopped");
@@ -224,34 +768,42 @@ impl CronService {
     pub async fn stop(&self) {
         let mut running = self.running.write().await;
         *running = false;
+        self.wake.notify_one();
+    }
+}
+
+impl Default for CronService {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::schedule::parse_interval;
 
     #[test]
     fn parse_interval_seconds() {
-        let d = parse_interval("60s").unwrap();
+        let d = parse_interval("60s").unwrap().unwrap();
         assert_eq!(d.num_seconds(), 60);
     }
 
     #[test]
     fn parse_interval_minutes() {
-        let d = parse_interval("30m").unwrap();
+        let d = parse_interval("30m").unwrap().unwrap();
         assert_eq!(d.num_minutes(), 30);
     }
 
     #[test]
     fn parse_interval_hours() {
-        let d = parse_interval("2h").unwrap();
+        let d = parse_interval("2h").unwrap().unwrap();
         assert_eq!(d.num_hours(), 2);
     }
 
     #[test]
     fn parse_interval_days() {
-        let d = parse_interval("1d").unwrap();
+        let d = parse_interval("1d").unwrap().unwrap();
         assert_eq!(d.num_days(), 1);
     }
 
@@ -273,6 +825,10 @@ mod tests {
             session_target: None,
             channel: None,
             to: None,
+            timeout_seconds: None,
+            catch_up: None,
+            timezone: None,
+            agent_id: None,
         };
         let job = CronJob::from_config(&config);
         assert_eq!(job.id, "test");
@@ -359,4 +915,315 @@ mod tests {
         svc.load_from_config(&configs).await;
         assert_eq!(svc.list_jobs().await.len(), 2);
     }
+
+    #[tokio::test]
+    async fn create_and_update_validate_input() {
+        let svc = CronService::new().with_timezone("Europe/Berlin");
+        let job = svc.create_job(&CronJobConfig {
+            schedule: Some("0 0 8 * * *".into()),
+            prompt: Some("Plan my day".into()),
+            ..Default::default()
+        }, JobSource::Runtime).await.unwrap();
+        assert_eq!(job.id.len(), 12);
+        assert_eq!(job.name, job.id);
+        assert_eq!(job.timezone.as_deref(), Some("Europe/Berlin"));
+        assert_eq!(job.source, JobSource::Runtime);
+        assert!(job.next_run.is_some());
+
+        let dup = CronJobConfig { id: Some(job.id.clone()), schedule: Some("1h".into()), prompt: Some("x".into()), ..Default::default() };
+        assert!(matches!(svc.create_job(&dup, JobSource::Runtime).await, Err(CronError::Duplicate(_))));
+        for bad in [
+            CronJobConfig { prompt: Some("x".into()), ..Default::default() },
+            CronJobConfig { schedule: Some("1h".into()), ..Default::default() },
+            CronJobConfig { schedule: Some("whenever".into()), prompt: Some("x".into()), ..Default::default() },
+            CronJobConfig { schedule: Some("2020-01-01T00:00:00Z".into()), prompt: Some("x".into()), ..Default::default() },
+            CronJobConfig { schedule: Some("1h".into()), prompt: Some("x".into()), kind: Some("shell".into()), ..Default::default() },
+            CronJobConfig { schedule: Some("1h".into()), prompt: Some("x".into()), channel: Some("whatsapp".into()), ..Default::default() },
+        ] {
+            assert!(matches!(svc.create_job(&bad, JobSource::Runtime).await, Err(CronError::Invalid(_))), "{:?}", bad);
+        }
+
+        let before = job.next_run;
+        let updated = svc.update_job(&job.id, &CronJobConfig {
+            name: Some("Morning plan".into()),
+            enabled: Some(false),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(updated.name, "Morning plan");
+        assert!(!updated.enabled);
+        assert_eq!(updated.next_run, before);
+
+        let updated = svc.update_job(&job.id, &CronJobConfig { schedule: Some("30m".into()), ..Default::default() }).await.unwrap();
+        assert_ne!(updated.next_run, before);
+        assert!(svc.update_job(&job.id, &CronJobConfig { timezone: Some("Mars/Olympus".into()), ..Default::default() }).await.is_err());
+        assert_eq!(svc.get_job(&job.id).await.unwrap().schedule, "30m");
+        assert!(matches!(svc.update_job("nope", &CronJobConfig::default()).await, Err(CronError::NotFound(_))));
+    }
+
+    struct EchoExecutor {
+        delay: Duration,
+        calls: Mutex<Vec<(String, SessionTarget)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl CronExecutor for EchoExecutor {
+        async fn run_agent_turn(&self, _job: &CronJob, prompt: &str, target: &SessionTarget) -> Result<String, String> {
+            self.calls.lock().unwrap().push((prompt.to_string(), target.clone()));
+            tokio::time::sleep(self.delay).await;
+            if prompt == "fail" {
+                return Err("model unavailable".into());
+            }
+            Ok(format!("Done: {}", prompt))
+        }
+    }
+
+    fn executor(delay_ms: u64) -> Arc<EchoExecutor> {
+        Arc::new(EchoExecutor { delay: Duration::from_millis(delay_ms), calls: Mutex::new(Vec::new()) })
+    }
+
+    fn agent_job(id: &str, prompt: &str) -> CronJob {
+        CronJob::from_config(&CronJobConfig {
+            id: Some(id.into()),
+            schedule: Some("1h".into()),
+            prompt: Some(prompt.into()),
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn run_job_delivers_and_records_history() {
+        use crate::channel::mock::MockChannel;
+        use crate::channel::ChannelCapabilities;
+
+        let channel = MockChannel::new("whatsapp", ChannelCapabilities::default());
+        let mut manager = ChannelManager::new();
+        manager.register(Box::new(channel.clone()));
+        let exec = executor(0);
+        let svc = CronService::new()
+            .with_executor(exec.clone())
+            .with_channels(Arc::new(RwLock::new(manager)));
+
+        let mut job = agent_job("digest", "Summarize my inbox");
+        job.session_target = Some("main".into());
+        job.channel = Some("whatsapp".into());
+        job.to = Some("+15551234567".into());
+        let run = svc.run_job(&job).await;
+        assert_eq!(run.status, RunStatus::Ok);
+        assert_eq!(run.excerpt.as_deref(), Some("Done: Summarize my inbox"));
+        assert_eq!(run.delivered, vec!["msg-1"]);
+        assert_eq!(channel.sent.lock().unwrap()[0].to, "+15551234567");
+        assert_eq!(exec.calls.lock().unwrap()[0].1, SessionTarget::Named("main".into()));
+
+        let failed = svc.run_job(&agent_job("digest", "fail")).await;
+        assert_eq!(failed.status, RunStatus::Error);
+        let history = svc.history("digest").await;
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].status, RunStatus::Error);
+        assert_eq!(history[0].error.as_deref(), Some("model unavailable"));
+    }
+
+    #[tokio::test]
+    async fn overlapping_runs_are_skipped_and_timeouts_enforced() {
+        let svc = CronService::new().with_executor(executor(300));
+        let job = agent_job("slow", "take your time");
+
+        let first = {
+            let svc = svc.clone();
+            let job = job.clone();
+            tokio::spawn(async move { svc.run_job(&job).await })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let second = svc.run_job(&job).await;
+        assert_eq!(second.status, RunStatus::Skipped);
+        assert_eq!(first.await.unwrap().status, RunStatus::Ok);
+
+        let mut quick = agent_job("quick", "hurry");
+        quick.timeout_seconds = Some(1);
+        let svc = CronService::new().with_executor(executor(1500));
+        let run = svc.run_job(&quick).await;
+        assert_eq!(run.status, RunStatus::Timeout);
+        // The in-flight mark is cleared after a timeout
+        assert!(svc.in_flight.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn unsupported_or_unconfigured_jobs_fail() {
+        let svc = CronService::new();
+        let run = svc.run_job(&agent_job("a", "hello")).await;
+        assert_eq!(run.error.as_deref(), Some("no agent executor configured"));
+
+        let mut other = agent_job("b", "hello");
+        other.kind = "webhook".into();
+        let run = svc.with_executor(executor(0)).run_job(&other).await;
+        assert_eq!(run.status, RunStatus::Error);
+        assert!(run.error.unwrap().contains("unsupported job kind"));
+    }
+
+    #[tokio::test]
+    async fn run_now_records_history() {
+        let svc = CronService::new().with_executor(executor(0));
+        svc.add_job(agent_job("c", "hello")).await;
+        let run = svc.run_now("c").await.unwrap();
+        assert_eq!(run.excerpt.as_deref(), Some("Done: hello"));
+        assert_eq!(svc.history("c").await.len(), 1);
+        assert!(matches!(svc.run_now("missing").await, Err(CronError::NotFound(_))));
+    }
+
+    fn hourly(policy: CatchUp, next_run: DateTime<Utc>) -> CronJob {
+        let mut job = agent_job("hourly", "check in");
+        job.catch_up = policy;
+        job.next_run = Some(next_run);
+        job
+    }
+
+    #[test]
+    fn catch_up_policies() {
+        let now = Utc::now();
+        let missed_since = now - chrono::Duration::minutes(330);
+
+        let mut skip = hourly(CatchUp::Skip, missed_since);
+        assert_eq!(skip.take_due(now), 0);
+        // Phase is kept: the next slot is 30 minutes out
+        assert_eq!(skip.next_run, Some(missed_since + chrono::Duration::hours(6)));
+        assert_eq!(skip.run_count, 0);
+
+        let mut once = hourly(CatchUp::RunOnce, missed_since);
+        assert_eq!(once.take_due(now), 1);
+        assert_eq!(once.run_count, 1);
+
+        let mut all = hourly(CatchUp::RunAllMissed, missed_since);
+        assert_eq!(all.take_due(now), 6);
+        assert_eq!(all.run_count, 6);
+        assert!(all.next_run.unwrap() > now);
+
+        // A slot that just came due runs even under skip
+        let mut on_time = hourly(CatchUp::Skip, now - chrono::Duration::seconds(10));
+        assert_eq!(on_time.take_due(now), 1);
+        assert_eq!(on_time.take_due(now), 0);
+
+        assert_eq!(CatchUp::parse("run-all-missed"), Some(CatchUp::RunAllMissed));
+        assert_eq!(CatchUp::parse("sometimes"), None);
+    }
+
+    #[test]
+    fn one_shot_jobs_run_once() {
+        let at = Utc::now() + chrono::Duration::minutes(5);
+        let mut job = CronJob::from_config(&CronJobConfig {
+            id: Some("reminder".into()),
+            schedule: Some(at.to_rfc3339()),
+            ..Default::default()
+        });
+        assert_eq!(job.next_run.map(|t| t.timestamp()), Some(at.timestamp()));
+        assert_eq!(job.take_due(Utc::now()), 0);
+
+        let later = at + chrono::Duration::hours(2);
+        assert_eq!(job.take_due(later), 1);
+        assert_eq!(job.next_run, None);
+        assert_eq!(job.take_due(later + chrono::Duration::days(1)), 0);
+    }
+
+    #[test]
+    fn config_jobs_get_stable_ids() {
+        let config = CronJobConfig { name: Some("Digest".into()), schedule: Some("1d".into()), ..Default::default() };
+        assert_eq!(CronJob::from_config(&config).id, CronJob::from_config(&config).id);
+        assert_eq!(CronJob::from_config(&config).source, JobSource::Config);
+    }
+
+    #[tokio::test]
+    async fn store_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cron.json");
+        let configs = vec![
+            CronJobConfig { id: Some("a".into()), schedule: Some("1h".into()), prompt: Some("hi".into()), ..Default::default() },
+            CronJobConfig { id: Some("b".into()), schedule: Some("2h".into()), ..Default::default() },
+        ];
+
+        let svc = CronService::new().with_store_path(path.clone()).with_executor(executor(0));
+        svc.load_from_config(&configs).await;
+        let mut runtime = agent_job("adhoc", "added at runtime");
+        runtime.source = JobSource::Runtime;
+        runtime.next_run = Some(Utc::now() + chrono::Duration::days(1));
+        svc.add_job(runtime).await;
+        let reminder = CronJobConfig {
+            id: Some("reminder".into()),
+            schedule: Some("1d".into()),
+            prompt: Some("stretch".into()),
+            agent_id: Some("work".into()),
+            ..Default::default()
+        };
+        svc.create_job(&reminder, JobSource::Agent).await.unwrap();
+
+        let first_due = svc.get_job("a").await.unwrap().next_run.unwrap();
+        let due = svc.check_due_jobs_at(first_due + chrono::Duration::minutes(1)).await;
+        assert_eq!(due.iter().map(|d| d.job.id.as_str()).collect::<Vec<_>>(), vec!["a"]);
+        svc.run_job(&due[0].job).await;
+        let next = svc.get_job("a").await.unwrap().next_run.unwrap();
+
+        // Restart with "b" removed from the config
+        let restarted = CronService::new().with_store_path(path);
+        restarted.load_from_config(&configs[..1]).await;
+        let ids: Vec<String> = restarted.list_jobs().await.into_iter().map(|j| j.id).collect();
+        assert_eq!(ids, vec!["a", "adhoc", "reminder"]);
+        let reminder = restarted.get_job("reminder").await.unwrap();
+        assert_eq!((reminder.source, reminder.agent_id.as_deref()), (JobSource::Agent, Some("work")));
+        let a = restarted.get_job("a").await.unwrap();
+        assert_eq!(a.run_count, 1);
+        assert_eq!(a.next_run, Some(next));
+        assert!(a.last_run.is_some());
+        assert_eq!(restarted.history("a").await.len(), 1);
+    }
+
+    #[test]
+    fn timezones_and_preview() {
+        let config = CronJobConfig {
+            id: Some("morning".into()),
+            schedule: Some("0 0 8 * * *".into()),
+            ..Default::default()
+        };
+        let job = CronJob::from_config_in(&config, Some("Asia/Tokyo"));
+        assert_eq!(job.timezone.as_deref(), Some("Asia/Tokyo"));
+        let runs = job.preview(3).unwrap();
+        assert_eq!(runs.len(), 3);
+        // 08:00 in Tokyo is 23:00 UTC
+        assert!(runs.iter().all(|t| t.format("%H:%M").to_string() == "23:00"));
+        assert_eq!(runs[1] - runs[0], chrono::Duration::days(1));
+
+        let own = CronJob::from_config_in(&CronJobConfig { timezone: Some("UTC".into()), ..config.clone() }, Some("Asia/Tokyo"));
+        assert_eq!(own.preview(1).unwrap()[0].format("%H:%M").to_string(), "08:00");
+
+        assert!(validate_schedule("0 0 0 30 2 *", None).is_err());
+        assert!(validate_schedule("0 0 8 * * *", Some("Nowhere/Special")).is_err());
+        assert!(validate_schedule("2020-01-01T00:00:00Z", None).unwrap_err().contains("in the past"));
+        let never = CronJob::from_config(&CronJobConfig { schedule: Some("0 0 0 30 2 *".into()), ..Default::default() });
+        assert_eq!(never.next_run, None);
+    }
+
+    #[tokio::test]
+    async fn scheduler_fires_at_next_run() {
+        let svc = CronService::new().with_executor(executor(0));
+        svc.start().await;
+
+        // Added while the scheduler is idle; it wakes and fires without waiting for a tick
+        let at = Utc::now() + chrono::Duration::milliseconds(300);
+        svc.add_job(CronJob::from_config(&CronJobConfig {
+            id: Some("soon".into()),
+            schedule: Some(at.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)),
+            prompt: Some("ping".into()),
+            ..Default::default()
+        })).await;
+
+        let mut fired = false;
+        for _ in 0..40 {
+            if !svc.history("soon").await.is_empty() {
+                fired = true;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        svc.stop().await;
+        assert!(fired);
+        let run = &svc.history("soon").await[0];
+        assert!(run.started_at >= at);
+        assert_eq!(svc.get_job("soon").await.unwrap().next_run, None);
+    }
 }
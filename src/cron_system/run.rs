@@ -0,0 +1,99 @@
+use super::CronJob;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Runs kept per job.
+pub const MAX_RUN_HISTORY: usize = 20;
+/// Used when a job sets no `timeoutSeconds`.
+pub const DEFAULT_JOB_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Which session an `agentTurn` job runs in (`sessionTarget`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionTarget {
+    /// A fresh session per run, discarded afterwards (the default).
+    Isolated,
+    /// A persistent session shared by every run that names it.
+    Named(String),
+}
+
+impl SessionTarget {
+    pub fn parse(target: Option<&str>) -> Self {
+        match target.map(str::trim) {
+            None | Some("") | Some("isolated") => Self::Isolated,
+            Some(name) => Self::Named(name.to_string()),
+        }
+    }
+
+    /// Session key for a named target; "main" is the main session of `agent_id`.
+    pub fn session_key(&self, agent_id: &str) -> Option<String> {
+        match self {
+            Self::Isolated => None,
+            Self::Named(name) if name == "main" => Some(crate::session::build_session_key(agent_id, "main", "main")),
+            Self::Named(name) => Some(format!("cron:{}", name)),
+        }
+    }
+}
+
+/// Runs agent turns on behalf of cron jobs.
+#[async_trait]
+pub trait CronExecutor: Send + Sync {
+    /// Run `prompt` as an agent turn and return the final reply.
+    async fn run_agent_turn(&self, job: &CronJob, prompt: &str, target: &SessionTarget) -> Result<String, String>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    Ok,
+    Error,
+    Timeout,
+    /// Not started because the previous run was still going.
+    Skipped,
+}
+
+/// One execution of a job.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CronRun {
+    pub job_id: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub status: RunStatus,
+    /// Start of the agent's reply.
+    pub excerpt: Option<String>,
+    pub error: Option<String>,
+    /// Message ids of the delivered result, when the job has a channel/to.
+    #[serde(default)]
+    pub delivered: Vec<String>,
+}
+
+impl CronRun {
+    pub fn skipped(job_id: &str, reason: &str) -> Self {
+        Self {
+            job_id: job_id.to_string(),
+            started_at: Utc::now(),
+            duration_ms: 0,
+            status: RunStatus::Skipped,
+            excerpt: None,
+            error: Some(reason.to_string()),
+            delivered: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_targets() {
+        assert_eq!(SessionTarget::parse(None), SessionTarget::Isolated);
+        assert_eq!(SessionTarget::parse(Some("isolated")), SessionTarget::Isolated);
+        assert_eq!(SessionTarget::parse(Some("main")).session_key("main").unwrap(), "agent:main:main:main");
+        assert_eq!(SessionTarget::parse(Some("main")).session_key("home").unwrap(), "agent:home:main:main");
+        assert_eq!(SessionTarget::parse(Some("digest")).session_key("main").unwrap(), "cron:digest");
+        assert_eq!(SessionTarget::Isolated.session_key("main"), None);
+    }
+}
@@ -0,0 +1,303 @@
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::str::FromStr;
+
+/// Candidate wall-clock times examined per lookup before giving up.
+const MAX_CRON_CANDIDATES: usize = 10_000;
+
+/// Longest interval a schedule or delay may use.
+pub const MAX_INTERVAL: Duration = Duration::days(365);
+
+/// A parsed job schedule.
+#[derive(Debug, Clone)]
+pub enum Schedule {
+    /// Fixed interval ("30m", "1h", "2d").
+    Every(Duration),
+    /// Standard cron expression, evaluated on the wall clock of a timezone.
+    Cron(Box<cron::Schedule>, Tz),
+    /// One-shot at an ISO 8601 timestamp.
+    At(DateTime<Utc>),
+}
+
+impl Schedule {
+    /// Parse with cron expressions and bare timestamps in UTC.
+    pub fn parse(schedule: &str) -> Result<Self, String> {
+        Self::parse_in(schedule, Tz::UTC)
+    }
+
+    /// Parse with cron expressions and timestamps without an offset taken
+    /// as wall-clock time in `tz`.
+    pub fn parse_in(schedule: &str, tz: Tz) -> Result<Self, String> {
+        let schedule = schedule.trim();
+        if schedule.is_empty() {
+            return Err("schedule is empty".into());
+        }
+        if let Some(duration) = parse_interval(schedule) {
+            let duration = duration?;
+            if duration <= Duration::zero() {
+                return Err(format!("interval must be positive: '{}'", schedule));
+            }
+            return Ok(Self::Every(duration));
+        }
+        if let Some(at) = parse_timestamp(schedule.strip_prefix("at ").unwrap_or(schedule), tz) {
+            return Ok(Self::At(at));
+        }
+        cron::Schedule::from_str(schedule)
+            .map(|s| Self::Cron(Box::new(s), tz))
+            .map_err(|e| format!("invalid schedule '{}': {}", schedule, e))
+    }
+
+    /// Reject schedules with no run after `now` (e.g. "0 0 0 30 2 *", or a
+    /// one-shot time in the past).
+    pub fn validate(&self, now: DateTime<Utc>) -> Result<(), String> {
+        match (self, self.next_after(now, None)) {
+            (_, Some(_)) => Ok(()),
+            (Self::At(at), None) => Err(format!("{} is in the past", at.to_rfc3339())),
+            (_, None) => Err("schedule never fires".into()),
+        }
+    }
+
+    /// The next `n` runs, starting with `first` (a stored `next_run`) when given.
+    pub fn upcoming(&self, now: DateTime<Utc>, first: Option<DateTime<Utc>>, n: usize) -> Vec<DateTime<Utc>> {
+        let mut out = Vec::with_capacity(n);
+        let mut next = first.or_else(|| self.next_after(now, None));
+        while let Some(t) = next {
+            if out.len() >= n {
+                break;
+            }
+            out.push(t);
+            next = self.next_after(t, Some(t));
+        }
+        out
+    }
+
+    pub fn is_one_shot(&self) -> bool {
+        matches!(self, Self::At(_))
+    }
+
+    /// Shortest time between consecutive runs among the next `samples`
+    /// occurrences; `None` for one-shot schedules.
+    pub fn shortest_gap(&self, now: DateTime<Utc>, samples: usize) -> Option<Duration> {
+        match self {
+            Self::Every(interval) => Some(*interval),
+            Self::At(_) => None,
+            Self::Cron(..) => self.upcoming(now, None, samples.max(2))
+                .windows(2)
+                .map(|w| w[1] - w[0])
+                .min(),
+        }
+    }
+
+    /// First occurrence strictly after `after`. Intervals are counted from
+    /// `anchor` (a previous occurrence) so they keep their phase; without one
+    /// they start at `after`.
+    pub fn next_after(&self, after: DateTime<Utc>, anchor: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Every(interval) => match anchor {
+                Some(anchor) if anchor > after => Some(anchor),
+                Some(anchor) => {
+                    let elapsed = (after - anchor).num_milliseconds();
+                    let step = interval.num_milliseconds();
+                    let offset = (elapsed / step).checked_add(1)?.checked_mul(step)?;
+                    anchor.checked_add_signed(Duration::try_milliseconds(offset)?)
+                }
+                None => after.checked_add_signed(*interval),
+            },
+            Self::Cron(schedule, tz) => next_cron(schedule, *tz, after),
+            Self::At(at) => (*at > after).then_some(*at),
+        }
+    }
+
+    /// Occurrences in `[from, to]`, at most `limit`. `from` is assumed to be
+    /// an occurrence (a stored `next_run`).
+    pub fn occurrences(&self, from: DateTime<Utc>, to: DateTime<Utc>, limit: usize) -> Vec<DateTime<Utc>> {
+        let mut out = Vec::new();
+        if from > to {
+            return out;
+        }
+        match self {
+            Self::Every(interval) => {
+                let mut t = Some(from);
+                while let Some(current) = t.filter(|t| *t <= to) {
+                    if out.len() >= limit {
+                        break;
+                    }
+                    out.push(current);
+                    t = current.checked_add_signed(*interval);
+                }
+            }
+            Self::Cron(..) => {
+                let mut t = Some(from);
+                while let Some(current) = t.filter(|t| *t <= to) {
+                    if out.len() >= limit {
+                        break;
+                    }
+                    out.push(current);
+                    t = self.next_after(current, None);
+                }
+            }
+            Self::At(at) => {
+                if *at >= from && *at <= to {
+                    out.push(*at);
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Next cron slot after `after`, matched against wall-clock time in `tz`.
+/// Slots skipped by a DST jump fire at the same offset past the jump (02:30
+/// becomes 03:30); slots repeated when clocks go back fire once.
+fn next_cron(schedule: &cron::Schedule, tz: Tz, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    // Iterate wall-clock times by treating them as UTC
+    let wall_after = Utc.from_utc_datetime(&after.with_timezone(&tz).naive_local());
+    schedule.after(&wall_after)
+        .take(MAX_CRON_CANDIDATES)
+        .filter_map(|wall| resolve_local(tz, wall.naive_utc()))
+        .find(|t| *t > after)
+}
+
+/// Map a wall-clock time in `tz` to an instant: the earlier one when it
+/// occurs twice, shifted past the gap when it doesn't occur at all.
+pub(crate) fn resolve_local(tz: Tz, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+    match tz.from_local_datetime(&naive).earliest() {
+        Some(t) => Some(t.with_timezone(&Utc)),
+        None => tz.from_local_datetime(&(naive - Duration::hours(1))).earliest()
+            .map(|t| t.with_timezone(&Utc) + Duration::hours(1)),
+    }
+}
+
+/// RFC 3339, or a bare `YYYY-MM-DDTHH:MM[:SS]` taken as wall-clock time in `tz`.
+fn parse_timestamp(s: &str, tz: Tz) -> Option<DateTime<Utc>> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Some(t.with_timezone(&Utc));
+    }
+    ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"].iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(s, fmt).ok())
+        .and_then(|naive| resolve_local(tz, naive))
+}
+
+/// Parse an IANA timezone name such as "Europe/Berlin".
+pub fn parse_timezone(name: &str) -> Result<Tz, String> {
+    name.trim().parse::<Tz>().map_err(|_| format!("unknown timezone '{}'", name))
+}
+
+/// Parse interval strings like "30m", "1h", "24h", "60s". `None` when `s`
+/// isn't an interval, an error when it is one longer than `MAX_INTERVAL`.
+pub(crate) fn parse_interval(s: &str) -> Option<Result<Duration, String>> {
+    let s = s.trim();
+    let unit = s.chars().last()?;
+    let to_duration: fn(i64) -> Option<Duration> = match unit {
+        's' => Duration::try_seconds,
+        'm' => Duration::try_minutes,
+        'h' => Duration::try_hours,
+        'd' => Duration::try_days,
+        _ => return None,
+    };
+    let n = &s[..s.len() - 1];
+    let digits = n.strip_prefix('-').unwrap_or(n);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(
+        n.parse::<i64>().ok()
+            .and_then(to_duration)
+            .filter(|d| d.abs() <= MAX_INTERVAL)
+            .ok_or_else(|| format!("interval '{}' is longer than {} days", s, MAX_INTERVAL.num_days())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn t(h: u32, m: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 5, 1, h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_each_kind() {
+        assert!(matches!(Schedule::parse("15m").unwrap(), Schedule::Every(d) if d == Duration::minutes(15)));
+        assert!(matches!(Schedule::parse("0 0 8 * * *").unwrap(), Schedule::Cron(..)));
+        assert!(matches!(Schedule::parse("2026-05-01T09:30:00Z").unwrap(), Schedule::At(at) if at == t(9, 30)));
+        assert!(matches!(Schedule::parse("at 2026-05-01T09:30").unwrap(), Schedule::At(at) if at == t(9, 30)));
+        assert!(Schedule::parse("2026-05-01T11:30:00+02:00").unwrap().is_one_shot());
+        assert!(Schedule::parse("0m").is_err());
+        assert!(Schedule::parse("366d").unwrap_err().contains("longer than 365 days"));
+        assert!(Schedule::parse("9999999999999999h").is_err());
+        assert!(Schedule::parse("100000000d").is_err());
+        assert!(Schedule::parse("whenever").is_err());
+    }
+
+    #[test]
+    fn intervals_keep_their_phase() {
+        let every = Schedule::parse("1h").unwrap();
+        assert_eq!(every.next_after(t(10, 0), None), Some(t(11, 0)));
+        // Anchored at 09:15, the next slot after 12:40 is 13:15
+        assert_eq!(every.next_after(t(12, 40), Some(t(9, 15))), Some(t(13, 15)));
+        assert_eq!(every.next_after(t(9, 15), Some(t(9, 15))), Some(t(10, 15)));
+        assert_eq!(every.occurrences(t(9, 15), t(12, 40), 10), vec![t(9, 15), t(10, 15), t(11, 15), t(12, 15)]);
+        assert_eq!(every.occurrences(t(9, 15), t(12, 40), 2).len(), 2);
+    }
+
+    #[test]
+    fn cron_and_one_shot_occurrences() {
+        let hourly = Schedule::parse("0 0 * * * *").unwrap();
+        assert_eq!(hourly.next_after(t(10, 30), None), Some(t(11, 0)));
+        assert_eq!(hourly.occurrences(t(8, 0), t(10, 30), 10), vec![t(8, 0), t(9, 0), t(10, 0)]);
+        assert_eq!(hourly.shortest_gap(t(10, 30), 5), Some(Duration::hours(1)));
+        assert_eq!(Schedule::parse("0 */5 9 * * *").unwrap().shortest_gap(t(10, 30), 5), Some(Duration::minutes(5)));
+
+        let once = Schedule::parse("2026-05-01T09:00:00Z").unwrap();
+        assert_eq!(once.next_after(t(8, 0), None), Some(t(9, 0)));
+        assert_eq!(once.next_after(t(9, 0), None), None);
+        assert_eq!(once.occurrences(t(9, 0), t(12, 0), 10), vec![t(9, 0)]);
+        assert_eq!(once.shortest_gap(t(8, 0), 5), None);
+    }
+
+    fn new_york() -> Tz {
+        parse_timezone("America/New_York").unwrap()
+    }
+
+    fn local(tz: Tz, y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        tz.with_ymd_and_hms(y, mo, d, h, mi, 0).earliest().unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn cron_follows_local_time_across_dst() {
+        let tz = new_york();
+        let daily = Schedule::parse_in("0 0 8 * * *", tz).unwrap();
+        // 08:00 EST is 13:00 UTC; after the March switch 08:00 EDT is 12:00 UTC
+        let runs = daily.upcoming(local(tz, 2026, 3, 7, 9, 0), None, 2);
+        assert_eq!(runs, vec![
+            Utc.with_ymd_and_hms(2026, 3, 8, 12, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2026, 3, 9, 12, 0, 0).unwrap(),
+        ]);
+
+        // 02:30 doesn't exist on March 8th; it runs at 03:30 EDT instead of being skipped
+        let gap = Schedule::parse_in("0 30 2 * * *", tz).unwrap();
+        let runs = gap.upcoming(local(tz, 2026, 3, 7, 12, 0), None, 2);
+        assert_eq!(runs[0], Utc.with_ymd_and_hms(2026, 3, 8, 7, 30, 0).unwrap());
+        assert_eq!(runs[1], local(tz, 2026, 3, 9, 2, 30));
+
+        // 01:30 happens twice on November 1st; it runs once
+        let repeated = Schedule::parse_in("0 30 1 * * *", tz).unwrap();
+        let runs = repeated.upcoming(local(tz, 2026, 10, 31, 12, 0), None, 2);
+        assert_eq!(runs[0], Utc.with_ymd_and_hms(2026, 11, 1, 5, 30, 0).unwrap());
+        assert_eq!(runs[1], Utc.with_ymd_and_hms(2026, 11, 2, 6, 30, 0).unwrap());
+    }
+
+    #[test]
+    fn one_shot_in_timezone_and_validation() {
+        let tz = parse_timezone("Asia/Tokyo").unwrap();
+        let at = Schedule::parse_in("2026-05-01T09:00", tz).unwrap();
+        assert!(matches!(at, Schedule::At(t) if t == Utc.with_ymd_and_hms(2026, 5, 1, 0, 0, 0).unwrap()));
+        assert!(at.validate(t(0, 0) - Duration::hours(1)).is_ok());
+        assert!(at.validate(t(1, 0)).unwrap_err().contains("in the past"));
+
+        let never = Schedule::parse("0 0 0 30 2 *").unwrap();
+        assert_eq!(never.validate(t(0, 0)).unwrap_err(), "schedule never fires");
+        assert!(parse_timezone("Atlantis/Central").is_err());
+    }
+}
@@ -0,0 +1,251 @@
+//! Messages arriving from channels: poll votes, group context and agent turns.
+
+use super::state::GatewayState;
+use crate::agent::group::{group_prompt, history_limit, GroupMessage};
+use crate::agent::routing::Route;
+use crate::agent::AgentError;
+use crate::approvals::{ApprovalRequest, Decision};
+use crate::channel::{IncomingMessage, OutgoingMessage};
+use crate::polls::VoteOutcome;
+use tracing::{debug, warn};
+
+/// What the gateway does with an incoming message.
+#[derive(Debug, Clone)]
+pub enum Inbound {
+    /// The owner answering an exec approval request; `Err` when the reply
+    /// named no pending request.
+    Approval(Result<(ApprovalRequest, Decision), String>),
+    /// The channel doesn't accept this sender; nothing was done.
+    Dropped,
+    /// A reply to a text poll, recorded as a vote.
+    Vote(VoteOutcome),
+    /// A group message not addressed to the agent, kept as context.
+    Buffered(Route),
+    /// Needs an agent turn with this prompt.
+    Turn { route: Route, prompt: String },
+}
+
+impl GatewayState {
+    /// Decide what to do with `msg` without running the agent, so bridges can
+    /// acknowledge it straight away.
+    pub async fn accept_incoming(&self, msg: &IncomingMessage) -> Inbound {
+        if let Some(answer) = self.approvals.handle_reply(msg).await {
+            let confirmation = match &answer {
+                Ok((request, Decision::Deny)) => format!("Denied: {}", request.command),
+                Ok((request, _)) => format!("Approved: {}", request.command),
+                Err(e) => e.to_string(),
+            };
+            let reply = OutgoingMessage::text(&msg.channel, &msg.chat_id, &confirmation);
+            if let Err(e) = self.channel_manager.read().await.send(&reply).await {
+                warn!("Failed to confirm approval in {}:{}: {}", msg.channel, msg.chat_id, e);
+            }
+            return Inbound::Approval(answer.map_err(|e| e.to_string()));
+        }
+        if !self.channel_manager.read().await.accepts_sender(msg) {
+            debug!("Dropping message from {} on {}: sender not allowed", msg.from, msg.channel);
+            return Inbound::Dropped;
+        }
+        if let Some(vote) = self.poll_service.handle_incoming(msg).await {
+            return Inbound::Vote(vote);
+        }
+        let route = self.route(&msg.channel, &msg.chat_id, &msg.from);
+        if !msg.is_group {
+            return Inbound::Turn { route, prompt: msg.text.clone() };
+        }
+
+        let agent = self.agents.get(&route.agent_id).unwrap_or_else(|| self.agents.default_agent());
+        let current = GroupMessage::from(msg);
+        let addressed = msg.mentions_bot
+            || self.mentions.get(&agent.id).is_some_and(|m| m.is_mentioned(&msg.text))
+            || !self.channel_manager.read().await.requires_mention(&msg.channel, &msg.chat_id);
+        if !addressed {
+            self.group_history.record(&route.session_key, current, history_limit(agent)).await;
+            return Inbound::Buffered(route);
+        }
+        let history = self.group_history.take(&route.session_key).await;
+        Inbound::Turn { prompt: group_prompt(&history, &current), route }
+    }
+
+    /// Run the agent turn for an accepted message and send the reply back to
+    /// its chat (threaded under the message in groups).
+    pub async fn answer_incoming(&self, msg: &IncomingMessage, route: &Route, prompt: &str) -> Result<String, AgentError> {
+        let agent = self.agents.get(&route.agent_id).unwrap_or_else(|| self.agents.default_agent());
+        let mut session = self.open_session(&route.session_key, &agent.id, &msg.channel).await;
+        let ctx = self.tool_context_for(agent).with_origin(&msg.channel, &msg.chat_id);
+        let outcome = self.run_agent_turn(&mut session, prompt, &ctx).await?;
+
+        if !outcome.text.trim().is_empty() {
+            let mut reply = OutgoingMessage::text(&msg.channel, &msg.chat_id, &outcome.text);
+            if msg.is_group {
+                reply.reply_to = Some(msg.id.clone());
+            }
+            if let Err(e) = self.channel_manager.read().await.send(&reply).await {
+                warn!("Failed to deliver reply to {}:{}: {}", msg.channel, msg.chat_id, e);
+            }
+        }
+        Ok(outcome.text)
+    }
+
+    /// Accept `msg` and answer it if needed, returning the agent's reply.
+    pub async fn handle_incoming(&self, msg: &IncomingMessage) -> Result<Option<String>, AgentError> {
+        match self.accept_incoming(msg).await {
+            Inbound::Turn { route, prompt } => self.answer_incoming(msg, &route, &prompt).await.map(Some),
+            Inbound::Approval(_) | Inbound::Dropped | Inbound::Vote(_) | Inbound::Buffered(_) => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::mock::MockChannel;
+    use crate::channel::ChannelCapabilities;
+    use crate::config::OpenClawConfig;
+    use crate::provider::mock::ScriptedProvider;
+    use std::sync::Arc;
+
+    fn group_message(id: &str, from: &str, text: &str) -> IncomingMessage {
+        IncomingMessage {
+            id: id.into(),
+            channel: "whatsapp".into(),
+            from: from.into(),
+            sender_name: None,
+            chat_id: "family@g.us".into(),
+            text: text.into(),
+            timestamp: 0,
+            is_group: true,
+            mentions_bot: false,
+            reply_to: None,
+            media: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn group_messages_wait_for_a_mention() {
+        let config: OpenClawConfig = serde_json::from_value(serde_json::json!({
+            "agents": { "list": [{ "id": "clawd", "name": "Clawd", "groupChat": { "historyLimit": 2 } }] }
+        })).unwrap();
+        let provider = ScriptedProvider::text("Pizza it is");
+        let state = GatewayState::new(config).with_provider(Arc::new(provider.clone()));
+        let channel = MockChannel::new("whatsapp", ChannelCapabilities { replies: true, ..Default::default() });
+        state.channel_manager.write().await.register(Box::new(channel.clone()));
+
+        for (i, text) in ["Dinner tonight?", "Pizza?", "Or sushi"].iter().enumerate() {
+            let msg = group_message(&format!("m{}", i), &format!("+{}", i), text);
+            assert!(matches!(state.accept_incoming(&msg).await, Inbound::Buffered(_)));
+        }
+
+        let mut mention = group_message("m9", "+9", "clawd, pick one");
+        mention.sender_name = Some("Ana".into());
+        let reply = state.handle_incoming(&mention).await.unwrap();
+        assert_eq!(reply.as_deref(), Some("Pizza it is"));
+
+        let prompt = serde_json::to_string(&provider.requests.lock().unwrap()[0].messages).unwrap();
+        assert!(!prompt.contains("Dinner tonight?"), "history is bounded by historyLimit");
+        assert!(prompt.contains("+1: Pizza?") && prompt.contains("+2: Or sushi"));
+        assert!(prompt.contains("Ana (+9): clawd, pick one"));
+
+        let sent = channel.sent.lock().unwrap().clone();
+        assert_eq!((sent[0].to.as_str(), sent[0].reply_to.as_deref()), ("family@g.us", Some("m9")));
+        assert_eq!(state.group_history.len("agent:clawd:whatsapp:family@g.us").await, 0);
+        assert!(state.session_manager.get("agent:clawd:whatsapp:family@g.us").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn owner_answers_approvals_in_chat() {
+        let config: OpenClawConfig = serde_json::from_value(serde_json::json!({
+            "approvals": { "mode": "always", "target": "whatsapp", "to": "+1555" }
+        })).unwrap();
+        let mut state = GatewayState::new(config);
+        let channel = MockChannel::new("whatsapp", ChannelCapabilities::default());
+        state.channel_manager.write().await.register(Box::new(channel.clone()));
+        state.approvals = state.approvals.clone().with_channels(state.channel_manager.clone());
+
+        let mut events = state.approvals.subscribe();
+        let waiting = tokio::spawn({
+            let approvals = state.approvals.clone();
+            async move { approvals.authorize("make deploy", "/srv", Default::default()).await }
+        });
+        let crate::approvals::ApprovalEvent::Requested(request) = events.recv().await.unwrap() else { panic!("expected a request") };
+        assert!(channel.sent.lock().unwrap()[0].text.contains(&format!("/approve {}", request.id)));
+
+        let mut reply = group_message("r1", "+1555", &format!("/approve {}", request.id));
+        reply.is_group = false;
+        reply.chat_id = "+1555".into();
+        assert!(matches!(state.accept_incoming(&reply).await, Inbound::Approval(Ok((_, Decision::AllowOnce)))));
+        assert!(waiting.await.unwrap().is_ok());
+        assert_eq!(channel.sent.lock().unwrap()[1].text, "Approved: make deploy");
+
+        // Unknown ids are reported, and other senders can't answer
+        assert!(matches!(state.accept_incoming(&reply).await, Inbound::Approval(Err(_))));
+        let mut stranger = reply.clone();
+        stranger.from = "+1999".into();
+        stranger.chat_id = "+1999".into();
+        assert!(matches!(state.accept_incoming(&stranger).await, Inbound::Turn { .. }));
+    }
+
+    #[tokio::test]
+    async fn poll_replies_are_votes_only_when_they_answer_the_poll() {
+        let state = GatewayState::new(OpenClawConfig::default());
+        let channel = MockChannel::new("whatsapp", ChannelCapabilities::default());
+        state.channel_manager.write().await.register(Box::new(channel.clone()));
+        let input = crate::polls::PollInput {
+            question: "Dinner?".into(),
+            options: vec!["Pizza".into(), "Sushi".into()],
+            max_selections: None,
+            duration_seconds: None,
+            duration_hours: None,
+        };
+        let poll = {
+            let channels = state.channel_manager.read().await;
+            state.poll_service.create(&channels, "whatsapp", "family@g.us", &input).await.unwrap()
+        };
+
+        match state.accept_incoming(&group_message("v1", "+1", "2")).await {
+            Inbound::Vote(vote) => assert_eq!((vote.poll_id, vote.selections), (poll.id.clone(), vec![1])),
+            other => panic!("unexpected {:?}", other),
+        }
+        // Already voted: a bare number is ordinary chat
+        assert!(matches!(state.accept_incoming(&group_message("v2", "+1", "1")).await, Inbound::Buffered(_)));
+        let mut quoted = group_message("v3", "+1", "1");
+        quoted.reply_to = poll.message_ids.first().cloned();
+        assert!(matches!(state.accept_incoming(&quoted).await, Inbound::Vote(_)));
+        assert_eq!(state.poll_service.results(&poll.id).await.unwrap().options[0].votes, 1);
+    }
+
+    #[tokio::test]
+    async fn only_allowed_senders_reach_the_agent() {
+        let state = GatewayState::new(OpenClawConfig::default())
+            .with_provider(Arc::new(ScriptedProvider::text("Hi!")));
+        let whatsapp = crate::config::WhatsAppConfig {
+            dm_policy: Some("disabled".into()),
+            allow_from: Some(vec!["+1".into()]),
+            ..Default::default()
+        };
+        state.channel_manager.write().await
+            .register(Box::new(crate::channel::whatsapp::WhatsAppPlugin::new(whatsapp)));
+        let direct = |from: &str| {
+            let mut msg = group_message("d1", from, "hello");
+            msg.is_group = false;
+            msg.chat_id = from.into();
+            msg
+        };
+
+        assert!(matches!(state.accept_incoming(&direct("+2")).await, Inbound::Dropped));
+        let mut mention = group_message("g1", "+2", "run rm -rf ~");
+        mention.mentions_bot = true;
+        assert!(matches!(state.accept_incoming(&mention).await, Inbound::Dropped));
+        assert_eq!(state.handle_incoming(&direct("+2")).await.unwrap(), None);
+
+        match state.accept_incoming(&direct("+1")).await {
+            Inbound::Turn { route, prompt } => {
+                assert_eq!(prompt, "hello");
+                assert_eq!(route.session_key, "agent:main:whatsapp:+1");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        // Not connected: the reply is still returned
+        assert_eq!(state.handle_incoming(&direct("+1")).await.unwrap().as_deref(), Some("Hi!"));
+        assert_eq!(state.session_manager.count().await, 1);
+    }
+}
@@ -1,5 +1,6 @@
 pub mod server;
 pub mod auth;
+pub mod inbound;
 pub mod ws;
 pub mod routes;
 pub mod state;
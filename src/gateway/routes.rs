@@ -1,11 +1,15 @@
 use axum::{
-    extract::State,
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
     response::Json,
-    routing::{get, post},
+    routing::{get, patch, post},
     Router,
 };
+use serde::Deserialize;
 use serde_json::{json, Value};
+use crate::agent::routing::AgentProfile;
+use crate::config::CronJobConfig;
+use crate::cron_system::{CronError, CronService, JobSource};
 use crate::gateway::state::GatewayState;
 use crate::version::VERSION;
 
@@ -17,8 +21,14 @@ pub fn build_router(state: GatewayState) -> Router {
         .route("/v1/status", get(status))
         .route("/v1/config", get(get_config))
         .route("/v1/sessions", get(list_sessions))
+        .route("/v1/agents", get(list_agents))
+        .route("/v1/agents/{id}", get(get_agent))
         .route("/v1/tools", get(list_tools))
         .route("/v1/chat/completions", post(chat_completions))
+        .route("/v1/cron/jobs", get(list_cron_jobs).post(add_cron_job))
+        .route("/v1/cron/jobs/{id}", patch(update_cron_job).delete(remove_cron_job))
+        .route("/v1/cron/jobs/{id}/run", post(run_cron_job))
+        .route("/v1/cron/jobs/{id}/history", get(cron_job_history))
         .with_state(state)
 }
 
@@ -47,6 +57,8 @@ async fn status(State(state): State<GatewayState>) -> Json<Value> {
         "channels": channels,
         "model": config.primary_model(),
         "workspace": config.workspace_dir(),
+        "memory": state.memory_status().await,
+        "heartbeat": state.heartbeat_status().await,
     }))
 }
 
@@ -66,14 +78,52 @@ async fn get_config(State(state): State<GatewayState>) -> Json<Value> {
     }))
 }
 
-async fn list_sessions(State(state): State<GatewayState>) -> Json<Value> {
-    let keys = state.session_manager.list_keys().await;
-    Json(json!({
+#[derive(Debug, Deserialize)]
+struct SessionParams {
+    /// Only sessions belonging to this agent.
+    agent: Option<String>,
+}
+
+async fn list_sessions(
+    State(state): State<GatewayState>,
+    Query(params): Query<SessionParams>,
+) -> Result<Json<Value>, ApiError> {
+    let mut keys = state.session_manager.list_keys().await;
+    if let Some(id) = params.agent {
+        let prefix = find_agent(&state, &id)?.session_prefix();
+        keys.retain(|k| k.starts_with(&prefix));
+    }
+    Ok(Json(json!({
         "count": keys.len(),
         "sessions": keys,
+    })))
+}
+
+fn find_agent<'a>(state: &'a GatewayState, id: &str) -> Result<&'a AgentProfile, ApiError> {
+    state.agents.get(id)
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, format!("Unknown agent: {}", id)))
+}
+
+async fn list_agents(State(state): State<GatewayState>) -> Json<Value> {
+    let agents = state.agents.list();
+    Json(json!({
+        "count": agents.len(),
+        "default": state.agents.default_agent().id,
+        "agents": agents,
     }))
 }
 
+async fn get_agent(
+    State(state): State<GatewayState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let agent = find_agent(&state, &id)?;
+    let prefix = agent.session_prefix();
+    let sessions: Vec<String> = state.session_manager.list_keys().await
+        .into_iter().filter(|k| k.starts_with(&prefix)).collect();
+    Ok(Json(json!({ "agent": agent, "sessions": sessions })))
+}
+
 async fn list_tools(State(state): State<GatewayState>) -> Json<Value> {
     let tools = state.tool_registry.list_definitions().await;
     let tool_list: Vec<Value> = tools.iter().map(|t| {
@@ -88,20 +138,118 @@ async fn list_tools(State(state): State<GatewayState>) -> Json<Value> {
     }))
 }
 
-/// OpenAI-compatible chat completions endpoint (stub).
+type ApiError = (StatusCode, Json<Value>);
+
+fn api_error(status: StatusCode, message: String) -> ApiError {
+    (status, Json(json!({ "error": message })))
+}
+
+impl From<CronError> for ApiError {
+    fn from(e: CronError) -> Self {
+        let status = match e {
+            CronError::NotFound(_) => StatusCode::NOT_FOUND,
+            CronError::Duplicate(_) => StatusCode::CONFLICT,
+            CronError::Invalid(_) => StatusCode::BAD_REQUEST,
+        };
+        api_error(status, e.to_string())
+    }
+}
+
+async fn cron_service(state: &GatewayState) -> Result<CronService, ApiError> {
+    state.cron().await
+        .ok_or_else(|| api_error(StatusCode::SERVICE_UNAVAILABLE, "Cron service is not running".into()))
+}
+
+async fn list_cron_jobs(State(state): State<GatewayState>) -> Result<Json<Value>, ApiError> {
+    let jobs = cron_service(&state).await?.list_jobs().await;
+    Ok(Json(json!({ "count": jobs.len(), "jobs": jobs })))
+}
+
+async fn add_cron_job(
+    State(state): State<GatewayState>,
+    Json(input): Json<CronJobConfig>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    let job = cron_service(&state).await?.create_job(&input, JobSource::Runtime).await?;
+    Ok((StatusCode::CREATED, Json(json!(job))))
+}
+
+async fn update_cron_job(
+    State(state): State<GatewayState>,
+    Path(id): Path<String>,
+    Json(patch): Json<CronJobConfig>,
+) -> Result<Json<Value>, ApiError> {
+    let job = cron_service(&state).await?.update_job(&id, &patch).await?;
+    Ok(Json(json!(job)))
+}
+
+async fn remove_cron_job(
+    State(state): State<GatewayState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    if !cron_service(&state).await?.remove_job(&id).await {
+        return Err(CronError::NotFound(id).into());
+    }
+    Ok(Json(json!({ "id": id, "removed": true })))
+}
+
+#[derive(Debug, Deserialize)]
+struct RunParams {
+    /// Wait for the run to finish and return it.
+    wait: Option<bool>,
+}
+
+async fn run_cron_job(
+    State(state): State<GatewayState>,
+    Path(id): Path<String>,
+    Query(params): Query<RunParams>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    let cron = cron_service(&state).await?;
+    if params.wait.unwrap_or(false) {
+        let run = cron.run_now(&id).await?;
+        return Ok((StatusCode::OK, Json(json!(run))));
+    }
+    cron.trigger(&id).await?;
+    Ok((StatusCode::ACCEPTED, Json(json!({ "id": id, "started": true }))))
+}
+
+async fn cron_job_history(
+    State(state): State<GatewayState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let cron = cron_service(&state).await?;
+    if cron.get_job(&id).await.is_none() {
+        return Err(CronError::NotFound(id).into());
+    }
+    Ok(Json(json!({ "id": id, "runs": cron.history(&id).await })))
+}
+
+/// Header naming the agent a chat completion goes to.
+const AGENT_HEADER: &str = "x-openclaw-agent-id";
+
+/// OpenAI-compatible chat completions endpoint (stub). The agent is picked by
+/// the `x-openclaw-agent-id` header or a `model` of `agent:<id>`, else the
+/// default agent answers.
 async fn chat_completions(
-    State(_state): State<GatewayState>,
+    State(state): State<GatewayState>,
+    headers: HeaderMap,
     Json(body): Json<Value>,
-) -> Result<Json<Value>, StatusCode> {
-    let model = body["model"].as_str().unwrap_or("claude-sonnet-4-20250514");
+) -> Result<Json<Value>, ApiError> {
+    let requested = headers.get(AGENT_HEADER).and_then(|v| v.to_str().ok())
+        .or_else(|| body["model"].as_str().and_then(|m| m.strip_prefix("agent:")));
+    let agent = match requested {
+        Some(id) => find_agent(&state, id.trim())?,
+        None => state.agents.default_agent(),
+    };
+    let model = agent.model.as_str();
     let messages = body["messages"].as_array()
-        .ok_or(StatusCode::BAD_REQUEST)?;
+        .ok_or_else(|| api_error(StatusCode::BAD_REQUEST, "messages is required".into()))?;
 
     // For now, return a structured response indicating the request was received
     Ok(Json(json!({
         "id": format!("chatcmpl-{}", uuid::Uuid::new_v4()),
         "object": "chat.completion",
         "model": model,
+        "agent": agent.id,
         "choices": [{
             "index": 0,
             "message": {
@@ -147,6 +295,10 @@ mod tests {
             .await
             .unwrap();
         assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["memory"]["index"]["mode"], "text");
+        assert_eq!(json["heartbeat"]["enabled"], false);
     }
 
     #[tokio::test]
@@ -168,4 +320,84 @@ mod tests {
             .unwrap();
         assert_eq!(response.status(), StatusCode::OK);
     }
+
+    async fn call(app: &Router, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map(|b| Body::from(b.to_string())).unwrap_or_else(Body::empty))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    async fn agent_endpoints() {
+        let config: crate::config::OpenClawConfig = serde_json::from_value(json!({
+            "agents": { "list": [{ "id": "home" }, { "id": "work", "model": "anthropic/claude-opus" }] }
+        })).unwrap();
+        let state = GatewayState::new(config);
+        state.open_session("agent:work:slack:C1", "work", "slack").await;
+        state.open_session("agent:home:cli:local", "home", "cli").await;
+        let app = build_router(state);
+
+        let (_, body) = call(&app, "GET", "/v1/agents", None).await;
+        assert_eq!((body["count"].as_u64(), body["default"].as_str()), (Some(2), Some("home")));
+        let (_, body) = call(&app, "GET", "/v1/agents/work", None).await;
+        assert_eq!(body["sessions"], json!(["agent:work:slack:C1"]));
+        assert_eq!(call(&app, "GET", "/v1/agents/nobody", None).await.0, StatusCode::NOT_FOUND);
+        let (_, body) = call(&app, "GET", "/v1/sessions?agent=home", None).await;
+        assert_eq!(body["sessions"], json!(["agent:home:cli:local"]));
+
+        let messages = json!([{ "role": "user", "content": "hi" }]);
+        let (_, body) = call(&app, "POST", "/v1/chat/completions", Some(json!({ "model": "agent:work", "messages": messages }))).await;
+        assert_eq!((body["agent"].as_str(), body["model"].as_str()), (Some("work"), Some("claude-opus")));
+        let (_, body) = call(&app, "POST", "/v1/chat/completions", Some(json!({ "model": "gpt-4o", "messages": messages }))).await;
+        assert_eq!(body["agent"], "home");
+        let request = Request::builder()
+            .method("POST")
+            .uri("/v1/chat/completions")
+            .header("content-type", "application/json")
+            .header(AGENT_HEADER, "ghost")
+            .body(Body::from(json!({ "messages": messages }).to_string()))
+            .unwrap();
+        assert_eq!(app.oneshot(request).await.unwrap().status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn cron_endpoints() {
+        let state = test_state();
+        let app = build_router(state.clone());
+        let (status, _) = call(&app, "GET", "/v1/cron/jobs", None).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+        *state.cron_service.write().await = Some(CronService::new());
+        let job = json!({ "id": "water", "schedule": "2h", "prompt": "Remind me to drink water" });
+        let (status, body) = call(&app, "POST", "/v1/cron/jobs", Some(job.clone())).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["schedule"], "2h");
+        assert_eq!(call(&app, "POST", "/v1/cron/jobs", Some(job)).await.0, StatusCode::CONFLICT);
+        let (status, body) = call(&app, "POST", "/v1/cron/jobs", Some(json!({ "schedule": "2h" }))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("prompt"));
+
+        let (status, body) = call(&app, "PATCH", "/v1/cron/jobs/water", Some(json!({ "schedule": "3h" }))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["schedule"], "3h");
+        let (_, body) = call(&app, "GET", "/v1/cron/jobs", None).await;
+        assert_eq!(body["count"], 1);
+
+        let (status, body) = call(&app, "POST", "/v1/cron/jobs/water/run?wait=true", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["jobId"], "water");
+        assert_eq!(call(&app, "POST", "/v1/cron/jobs/nope/run", None).await.0, StatusCode::NOT_FOUND);
+        let (_, body) = call(&app, "GET", "/v1/cron/jobs/water/history", None).await;
+        assert_eq!(body["runs"].as_array().unwrap().len(), 1);
+
+        assert_eq!(call(&app, "DELETE", "/v1/cron/jobs/water", None).await.0, StatusCode::OK);
+        assert_eq!(call(&app, "DELETE", "/v1/cron/jobs/water", None).await.0, StatusCode::NOT_FOUND);
+    }
 }
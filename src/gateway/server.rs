@@ -16,11 +16,75 @@ pub async fn start_gateway(config: OpenClawConfig) -> Result<(), Box<dyn std::er
     let port = config::resolve_gateway_port(&config);
     let bind_addr = config::resolve_gateway_bind(&config);
 
-    let state = GatewayState::new(config);
+    let mut state = GatewayState::new(config);
+    state.poll_service = crate::polls::PollService::new()
+        .with_store_path(crate::utils::resolve_config_dir().join("polls.json"));
+    state.message_policy = state.message_policy.clone()
+        .with_audit_path(crate::utils::resolve_config_dir().join("logs").join("message-audit.jsonl"));
+    state.memory_index = state.memory_index.take()
+        .map(|index| index.with_store_path(crate::utils::resolve_config_dir().join("memory").join("index.json")));
+    state.approvals = state.approvals.clone()
+        .with_store_path(crate::utils::resolve_config_dir().join("approvals.json"))
+        .with_channels(state.channel_manager.clone());
 
     // Register builtin tools
     state.tool_registry.register_builtins().await;
 
+    // Close expired polls in the background
+    state.poll_service.start(state.channel_manager.clone()).await;
+
+    // Scheduled agent turns
+    let cron_config = state.config.read().await.cron.clone().unwrap_or_default();
+    let mut cron = crate::cron_system::CronService::new()
+        .with_store_path(crate::utils::resolve_config_dir().join("cron").join("jobs.json"))
+        .with_executor(std::sync::Arc::new(state.clone()))
+        .with_channels(state.channel_manager.clone());
+    if let Some(tz) = &cron_config.timezone {
+        cron = cron.with_timezone(tz);
+    }
+    let cron_jobs = cron_config.jobs.unwrap_or_default();
+    cron.load_from_config(&cron_jobs).await;
+    cron.start().await;
+    *state.cron_service.write().await = Some(cron);
+
+    // Background subagent runs for sessions_spawn
+    let subagents = crate::subagents::SubagentService::from_config(&*state.config.read().await)
+        .with_executor(std::sync::Arc::new(state.clone()))
+        .with_cancel(state.shutdown.clone());
+    *state.subagents.write().await = Some(subagents);
+
+    // Periodic HEARTBEAT.md checks
+    let heartbeat_config = state.config.read().await.agents.as_ref()
+        .and_then(|a| a.defaults.as_ref())
+        .and_then(|d| d.heartbeat.clone());
+    if let Some(heartbeat_config) = heartbeat_config {
+        match crate::heartbeat::HeartbeatSettings::from_config(&heartbeat_config) {
+            Ok(Some(settings)) => {
+                let heartbeat = crate::heartbeat::HeartbeatService::new(settings, &state.workspace_dir)
+                    .with_executor(std::sync::Arc::new(state.clone()))
+                    .with_channels(state.channel_manager.clone());
+                heartbeat.start().await;
+                *state.heartbeat.write().await = Some(heartbeat);
+            }
+            Ok(None) => info!("Heartbeat disabled"),
+            Err(e) => tracing::warn!("Heartbeat disabled: {}", e),
+        }
+    }
+
+    // Re-index memory on file changes
+    let _memory_watcher = match &state.memory_index {
+        Some(index) if index.options().sync.watch => {
+            match crate::memory::MemoryWatcher::start(index.clone(), crate::memory::watcher::DEFAULT_DEBOUNCE) {
+                Ok(watcher) => Some(watcher),
+                Err(e) => {
+                    tracing::warn!("Memory watcher failed to start: {}", e);
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+
     // Build router
     let app = build_app(state.clone());
 
@@ -31,7 +95,17 @@ pub async fn start_gateway(config: OpenClawConfig) -> Result<(), Box<dyn std::er
     info!("  Auth: {}", if state.auth_token.is_some() { "token" } else { "none" });
 
     let listener = TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    let shutdown = state.shutdown.clone();
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async move {
+            let _ = tokio::signal::ctrl_c().await;
+            info!("Shutting down");
+            // Stop the tool calls of turns and subagents still running
+            shutdown.cancel();
+        })
+        .await?;
+    // Background commands don't outlive the gateway
+    state.processes.shutdown().await;
 
     Ok(())
 }
@@ -1,10 +1,33 @@
+use crate::approvals::{ApprovalMode, ApprovalService};
 use crate::config::OpenClawConfig;
 use crate::session::SessionManager;
-use crate::tools::ToolRegistry;
+use crate::tools::{Tool, ToolRegistry};
+use crate::channel::whatsapp::WhatsAppPlugin;
 use crate::channel::ChannelManager;
-use crate::cron_system::CronService;
+use crate::agent::group::{GroupHistory, MentionMatcher};
+use crate::agent::routing::{AgentProfile, AgentRouter, Route};
+use crate::agent::{AgentError, AgentRunner, TurnOutcome};
+use crate::cron_system::{CronExecutor, CronJob, CronService, SessionTarget};
+use crate::heartbeat::{is_ack, HeartbeatExecutor, HeartbeatService, HeartbeatSettings};
+use crate::memory::embedding::provider_from_config;
+use crate::memory::flush::flush_session;
+use crate::memory::journal::AppendOutcome;
+use crate::memory::{MemoryIndex, MemoryJournal, MemorySearchOptions};
+use crate::provider::{AnthropicProvider, Provider};
+use crate::session::Session;
+use crate::polls::PollService;
+use crate::process::ProcessManager;
+use crate::subagents::{concurrency_limits, SubagentExecutor, SubagentRun, SubagentService};
+use crate::tools::executor::ToolContext;
+use crate::tools::message::MessagePolicy;
+use crate::security::file_access::FileAccessPolicy;
+use crate::tools::sandbox::SandboxPolicy;
+use crate::tools::web_fetch::WebFetchOptions;
+use crate::tools::web_search::WebSearch;
+use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, Semaphore};
+use tokio_util::sync::CancellationToken;
 use chrono::{DateTime, Utc};
 
 /// Shared gateway state, accessible from all request handlers.
@@ -15,7 +38,42 @@ pub struct GatewayState {
     pub tool_registry: ToolRegistry,
     pub channel_manager: Arc<RwLock<ChannelManager>>,
     pub cron_service: Arc<RwLock<Option<CronService>>>,
+    /// Set when `agents.defaults.heartbeat` is configured.
+    pub heartbeat: Arc<RwLock<Option<HeartbeatService>>>,
+    /// Set once the gateway has started.
+    pub subagents: Arc<RwLock<Option<SubagentService>>>,
+    /// Slots for top-level agent turns (`agents.defaults.maxConcurrent`);
+    /// subagents run under their own limit.
+    pub turn_slots: Arc<Semaphore>,
+    pub poll_service: PollService,
+    /// Commands `exec` started in the background.
+    pub processes: ProcessManager,
+    /// Gate for the `exec` tool (`approvals`).
+    pub approvals: ApprovalService,
+    pub message_policy: MessagePolicy,
+    pub web_fetch: WebFetchOptions,
+    /// How `exec` commands are isolated (`tools.exec`).
+    pub sandbox: SandboxPolicy,
+    /// Where the file tools may read and write (`tools.files`).
+    pub files: FileAccessPolicy,
+    pub web_search: Option<WebSearch>,
+    pub memory_search: MemorySearchOptions,
+    pub memory_index: Option<MemoryIndex>,
+    pub memory_journal: MemoryJournal,
+    /// Model provider for gateway-initiated calls (memory flush); `None`
+    /// without credentials.
+    pub provider: Option<Arc<dyn Provider>>,
+    /// Model id from `agents.defaults.model.primary`, without the provider prefix.
+    pub model: String,
+    /// Agents from `agents.list` and the bindings routing messages to them.
+    pub agents: Arc<AgentRouter>,
+    /// Mention patterns per agent id.
+    pub mentions: Arc<HashMap<String, MentionMatcher>>,
+    /// Group messages waiting to be shown to the agent as context.
+    pub group_history: GroupHistory,
     pub start_time: DateTime<Utc>,
+    /// Cancelled when the gateway shuts down; running tool calls stop.
+    pub shutdown: CancellationToken,
     pub auth_token: Option<String>,
     pub workspace_dir: String,
 }
@@ -34,27 +92,356 @@ impl GatewayState {
             .and_then(|t| t.allow.clone())
             .unwrap_or_default();
 
+        let message_policy = MessagePolicy::from_config(&config);
+        let web_fetch = WebFetchOptions::from_config(&config);
+        let sandbox = SandboxPolicy::from_config(&config);
+        let processes = ProcessManager::from_config(&config);
+        let files = FileAccessPolicy::from_config(&config);
+        let memory_search = MemorySearchOptions::from_config(&config);
+        let memory_index = match provider_from_config(&config.memory.clone().unwrap_or_default()) {
+            Ok(Some(provider)) => Some(MemoryIndex::new(&workspace_dir, memory_search.clone(), provider)),
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("Memory embeddings disabled: {}", e);
+                None
+            }
+        };
+        let memory_journal = MemoryJournal::from_config(&workspace_dir, &config);
+        let provider = AnthropicProvider::api_key_from_env()
+            .map(|key| Arc::new(AnthropicProvider::new(key)) as Arc<dyn Provider>);
+        let model = config.primary_model()
+            .map(|m| OpenClawConfig::parse_model_id(m).1)
+            .unwrap_or_else(|| crate::provider::CompletionRequest::default().model);
+        let agents = Arc::new(AgentRouter::from_config(&config));
+        let (max_turns, _) = concurrency_limits(&config);
+        let mentions = agents.list().iter()
+            .map(|agent| (agent.id.clone(), MentionMatcher::for_agent(agent)))
+            .collect();
+        let approvals = match ApprovalService::from_config(&config.approvals.clone().unwrap_or_default()) {
+            Ok(approvals) => approvals,
+            Err(e) => {
+                // Fail closed: ask about every command rather than run them unchecked
+                tracing::error!("{}; asking for approval of every command", e);
+                ApprovalService::new(ApprovalMode::Always)
+            }
+        };
+        let web_search = match WebSearch::from_config(&config) {
+            Ok(search) => Some(search),
+            Err(e) => {
+                tracing::debug!("web_search unavailable: {}", e);
+                None
+            }
+        };
+
+        let mut channel_manager = ChannelManager::new();
+        if let Some(whatsapp) = config.channels.as_ref().and_then(|c| c.whatsapp.clone()) {
+            channel_manager.register(Box::new(WhatsAppPlugin::new(whatsapp)));
+        }
+
         Self {
             config: Arc::new(RwLock::new(config)),
             session_manager: SessionManager::new(1000),
             tool_registry: ToolRegistry::with_policy(tool_deny, tool_allow),
-            channel_manager: Arc::new(RwLock::new(ChannelManager::new())),
+            channel_manager: Arc::new(RwLock::new(channel_manager)),
             cron_service: Arc::new(RwLock::new(None)),
+            heartbeat: Arc::new(RwLock::new(None)),
+            subagents: Arc::new(RwLock::new(None)),
+            turn_slots: Arc::new(Semaphore::new(max_turns)),
+            poll_service: PollService::new(),
+            processes,
+            approvals,
+            message_policy,
+            web_fetch,
+            sandbox,
+            files,
+            web_search,
+            memory_search,
+            memory_index,
+            memory_journal,
+            provider,
+            model,
+            agents,
+            mentions: Arc::new(mentions),
+            group_history: GroupHistory::new(),
             start_time: Utc::now(),
+            shutdown: CancellationToken::new(),
             auth_token,
             workspace_dir,
         }
     }
 
+    pub fn with_provider(mut self, provider: Arc<dyn Provider>) -> Self {
+        self.provider = Some(provider);
+        self
+    }
+
+    /// Context handed to tool calls made by agents on this gateway.
+    pub fn tool_context(&self) -> ToolContext {
+        let ctx = ToolContext::new(&self.workspace_dir)
+            .with_files(self.files.clone())
+            .with_polls(self.poll_service.clone())
+            .with_approvals(self.approvals.clone())
+            .with_processes(self.processes.clone())
+            .with_channels(self.channel_manager.clone(), self.message_policy.clone())
+            .with_web_fetch(self.web_fetch.clone())
+            .with_sandbox(self.sandbox.clone())
+            .with_memory(self.memory_search.clone())
+            .with_memory_journal(self.memory_journal.clone())
+            .with_cancel(self.shutdown.child_token());
+        let ctx = match &self.memory_index {
+            Some(index) => ctx.with_memory_index(index.clone()),
+            None => ctx,
+        };
+        // Only contended while the gateway installs the service at startup
+        let ctx = match self.cron_service.try_read().ok().and_then(|c| c.clone()) {
+            Some(cron) => ctx.with_cron(cron),
+            None => ctx,
+        };
+        let ctx = match self.subagents.try_read().ok().and_then(|s| s.clone()) {
+            Some(subagents) => ctx.with_subagents(subagents),
+            None => ctx,
+        };
+        match &self.web_search {
+            Some(search) => ctx.with_web_search(search.clone()),
+            None => ctx,
+        }
+    }
+
+    /// Tool context for `agent`, confined to its workspace. Agents outside
+    /// the shared workspace search memory by text and keep their own notes.
+    pub fn tool_context_for(&self, agent: &AgentProfile) -> ToolContext {
+        let mut ctx = self.tool_context();
+        if agent.workspace_dir != self.workspace_dir {
+            ctx.memory_journal = Some(self.journal_for(agent));
+            ctx.memory_index = None;
+            ctx.workspace_dir = agent.workspace_dir.clone();
+        }
+        ctx
+    }
+
+    /// Daily notes of `agent`, in its own workspace.
+    fn journal_for(&self, agent: &AgentProfile) -> MemoryJournal {
+        if agent.workspace_dir == self.workspace_dir {
+            return self.memory_journal.clone();
+        }
+        let journal = MemoryJournal::new(&agent.workspace_dir);
+        match self.memory_journal.timezone() {
+            Some(tz) => journal.with_timezone(tz),
+            None => journal,
+        }
+    }
+
+    /// Which agent and session a message from `from` in `chat_id` goes to.
+    pub fn route(&self, channel: &str, chat_id: &str, from: &str) -> Route {
+        self.agents.resolve(channel, chat_id, from)
+    }
+
+    /// Agent owning `session`, falling back to the default agent.
+    pub fn agent_for(&self, session: &Session) -> &AgentProfile {
+        self.agents.get(&session.agent_id).unwrap_or_else(|| self.agents.default_agent())
+    }
+
+    /// The cron service, once the gateway has started it.
+    pub async fn cron(&self) -> Option<CronService> {
+        self.cron_service.read().await.clone()
+    }
+
+    /// Heartbeat counters and recent runs for status endpoints.
+    pub async fn heartbeat_status(&self) -> serde_json::Value {
+        match self.heartbeat.read().await.as_ref() {
+            Some(heartbeat) => {
+                let mut status = serde_json::json!(heartbeat.status().await);
+                status["enabled"] = serde_json::json!(true);
+                status
+            }
+            None => serde_json::json!({ "enabled": false }),
+        }
+    }
+
+    /// Memory search health for status endpoints.
+    pub async fn memory_status(&self) -> serde_json::Value {
+        match &self.memory_index {
+            Some(index) => serde_json::json!({
+                "enabled": self.memory_search.enabled,
+                "index": index.status().await,
+            }),
+            None => serde_json::json!({
+                "enabled": self.memory_search.enabled,
+                "index": { "mode": "text" },
+            }),
+        }
+    }
+
+    /// Get or create a session, firing session-start hooks for new ones and
+    /// flushing any session evicted to make room.
+    pub async fn open_session(&self, key: &str, agent_id: &str, channel: &str) -> Session {
+        let is_new = self.session_manager.get(key).await.is_none();
+        let (session, evicted) = self.session_manager.get_or_create_evicting(key, agent_id, channel).await;
+        if is_new {
+            if let Some(index) = &self.memory_index {
+                index.on_session_start();
+            }
+        }
+        if let Some(evicted) = evicted {
+            let state = self.clone();
+            tokio::spawn(async move {
+                state.flush_session_memory(&evicted).await;
+            });
+        }
+        session
+    }
+
+    /// End a session, saving durable facts to memory first.
+    pub async fn close_session(&self, key: &str) -> Option<Session> {
+        let session = self.session_manager.get(key).await?;
+        self.flush_session_memory(&session).await;
+        self.session_manager.remove(key).await
+    }
+
+    /// Summarize a session into today's note of the agent it belongs to. Call
+    /// before compacting or discarding its history; a no-op without a provider.
+    pub async fn flush_session_memory(&self, session: &Session) -> Option<AppendOutcome> {
+        let provider = self.provider.as_ref()?;
+        let journal = self.journal_for(self.agent_for(session));
+        match flush_session(session, provider.as_ref(), &self.model, &journal).await {
+            Ok(Some(outcome)) => {
+                tracing::info!("Flushed {} memory entries from session {} to {}", outcome.written, session.key, outcome.path.display());
+                Some(outcome)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("Memory flush for session {} failed: {}", session.key, e);
+                None
+            }
+        }
+    }
+
+    /// Runner for the default agent.
+    pub async fn agent_runner(&self) -> Result<AgentRunner, AgentError> {
+        self.agent_runner_for(self.agents.default_agent()).await
+    }
+
+    /// Tools both the gateway and `agent` allow.
+    pub async fn agent_tools(&self, agent: &AgentProfile) -> Vec<Arc<dyn Tool>> {
+        self.tool_registry.list_tools().await.into_iter()
+            .filter(|t| agent.allows_tool(&t.definition().name))
+            .collect()
+    }
+
+    /// Runner with `agent`'s model and the tools both the gateway and the
+    /// agent allow.
+    pub async fn agent_runner_for(&self, agent: &AgentProfile) -> Result<AgentRunner, AgentError> {
+        let provider = self.provider.clone().ok_or(AgentError::NoProvider)?;
+        Ok(AgentRunner::new(provider, &agent.model).with_tools(self.agent_tools(agent).await))
+    }
+
+    /// Run one agent turn in `session` as the session's agent, storing the
+    /// updated history. Waits for a free top-level turn slot first.
+    pub async fn run_agent_turn(&self, session: &mut Session, prompt: &str, ctx: &ToolContext) -> Result<TurnOutcome, AgentError> {
+        // The semaphore is never closed
+        let _slot = self.turn_slots.acquire().await.ok();
+        let runner = self.agent_runner_for(self.agent_for(session)).await?;
+        self.run_with(&runner, session, prompt, ctx).await
+    }
+
+    /// Run a turn with `runner`, marking the session on the tool context.
+    async fn run_with(&self, runner: &AgentRunner, session: &mut Session, prompt: &str, ctx: &ToolContext) -> Result<TurnOutcome, AgentError> {
+        let ctx = ctx.clone().with_session(&session.key, &session.agent_id);
+        let result = runner.run_turn(session, prompt, &ctx).await;
+        if self.session_manager.get(&session.key).await.is_some() {
+            self.session_manager.update(session).await;
+        }
+        result
+    }
+
     /// Gateway uptime in seconds.
     pub fn uptime_secs(&self) -> i64 {
         (Utc::now() - self.start_time).num_seconds()
     }
 }
 
+#[async_trait::async_trait]
+impl CronExecutor for GatewayState {
+    async fn run_agent_turn(&self, job: &CronJob, prompt: &str, target: &SessionTarget) -> Result<String, String> {
+        // A job runs as the agent that created it; one whose agent is gone doesn't run
+        let agent = match &job.agent_id {
+            Some(id) => self.agents.get(id).ok_or_else(|| format!("Unknown agent: {}", id))?,
+            None => self.agents.default_agent(),
+        };
+        let mut session = match target.session_key(&agent.id) {
+            Some(key) => self.open_session(&key, &agent.id, "cron").await,
+            None => Session::new(&format!("cron:{}:{}", job.id, uuid::Uuid::new_v4()), &agent.id, "cron"),
+        };
+        let mut ctx = self.tool_context_for(agent);
+        if let (Some(channel), Some(to)) = (&job.channel, &job.to) {
+            ctx = ctx.with_origin(channel, to);
+        }
+        GatewayState::run_agent_turn(self, &mut session, prompt, &ctx).await
+            .map(|outcome| outcome.text)
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[async_trait::async_trait]
+impl HeartbeatExecutor for GatewayState {
+    /// Heartbeats run in the main session; acknowledged runs are dropped from
+    /// it again so they don't crowd out the conversation.
+    async fn run_heartbeat(&self, prompt: &str, settings: &HeartbeatSettings) -> Result<String, String> {
+        let agent = self.agents.default_agent();
+        let mut session = self.open_session(&agent.session_key("main", "main"), &agent.id, "heartbeat").await;
+        let before = session.messages.len();
+        let mut ctx = self.tool_context_for(agent);
+        if let Some((channel, to)) = settings.delivery() {
+            ctx = ctx.with_origin(channel, to);
+        }
+        let text = GatewayState::run_agent_turn(self, &mut session, prompt, &ctx).await
+            .map(|outcome| outcome.text)
+            .map_err(|e| e.to_string())?;
+        if is_ack(&text) {
+            session.messages.truncate(before);
+            self.session_manager.update(&session).await;
+        }
+        Ok(text)
+    }
+}
+
+#[async_trait::async_trait]
+impl SubagentExecutor for GatewayState {
+    /// Subagents run as their agent in their own session, without the tools
+    /// reserved for top-level turns.
+    async fn run_subagent(&self, run: &SubagentRun, cancel: CancellationToken) -> Result<String, String> {
+        let agent = self.agents.get(&run.agent_id).ok_or_else(|| format!("Unknown agent: {}", run.agent_id))?;
+        let provider = self.provider.clone().ok_or_else(|| AgentError::NoProvider.to_string())?;
+        let tools = self.agent_tools(agent).await.into_iter()
+            .filter(|t| run.allows_tool(&t.definition().name))
+            .collect();
+        let runner = AgentRunner::new(provider, &agent.model).with_tools(tools);
+        let mut session = self.open_session(&run.session_key, &agent.id, "subagent").await;
+        let ctx = self.tool_context_for(agent).with_cancel(cancel);
+        self.run_with(&runner, &mut session, &run.task, &ctx).await
+            .map(|outcome| outcome.text)
+            .map_err(|e| e.to_string())
+    }
+
+    async fn announce(&self, run: &SubagentRun) {
+        let text = run.announcement();
+        if let Some(mut parent) = self.session_manager.get(&run.parent_session).await {
+            parent.add_assistant_message(&text);
+            self.session_manager.update(&parent).await;
+        }
+        if let (Some(channel), Some(to)) = (&run.channel, &run.to) {
+            let message = crate::channel::OutgoingMessage::text(channel, to, &text);
+            if let Err(e) = self.channel_manager.read().await.send(&message).await {
+                tracing::warn!("Failed to announce subagent {} to {}:{}: {}", run.id, channel, to, e);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::provider::mock::ScriptedProvider;
 
     #[test]
     fn gateway_state_creation() {
@@ -71,4 +458,192 @@ mod tests {
         let state = GatewayState::new(config);
         assert_eq!(state.auth_token, Some("secret123".into()));
     }
+
+    #[test]
+    fn shutdown_cancels_tool_calls() {
+        let state = GatewayState::new(OpenClawConfig::default());
+        let ctx = state.tool_context_for(state.agents.default_agent());
+        assert!(!ctx.cancel.is_cancelled());
+        state.shutdown.cancel();
+        assert!(ctx.cancel.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn close_session_flushes_memory() {
+        let dir = tempfile::tempdir().unwrap();
+        let config: OpenClawConfig = serde_json::from_value(serde_json::json!({
+            "agents": { "defaults": { "workspace": dir.path().to_str().unwrap(), "model": { "primary": "anthropic/test-model" } } }
+        })).unwrap();
+        let state = GatewayState::new(config).with_provider(Arc::new(ScriptedProvider::text("- [preference] Likes short answers")));
+        assert_eq!(state.model, "test-model");
+
+        let mut session = state.open_session("k", "main", "cli").await;
+        session.add_user_message("keep it brief please");
+        state.session_manager.update(&session).await;
+
+        assert!(state.close_session("k").await.is_some());
+        assert_eq!(state.session_manager.count().await, 0);
+        let note = state.memory_journal.load_today().unwrap();
+        assert!(note.contains("[preference] Likes short answers"));
+    }
+
+    #[tokio::test]
+    async fn sessions_flush_into_their_agent_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().join("ws");
+        let config: OpenClawConfig = serde_json::from_value(serde_json::json!({
+            "agents": {
+                "defaults": { "workspace": workspace.to_str().unwrap() },
+                "list": [{ "id": "home" }, { "id": "work" }]
+            }
+        })).unwrap();
+        let state = GatewayState::new(config).with_provider(Arc::new(ScriptedProvider::text("- [fact] Ships on Fridays")));
+        let work = state.agents.get("work").unwrap();
+
+        let mut session = state.open_session(&work.session_key("slack", "C1"), "work", "slack").await;
+        session.add_user_message("we ship on fridays");
+        state.session_manager.update(&session).await;
+        state.close_session(&session.key).await.unwrap();
+
+        let note = state.tool_context_for(work).memory_journal.unwrap().load_today().unwrap();
+        assert!(note.contains("[fact] Ships on Fridays"));
+        assert!(state.memory_journal.load_today().is_none());
+    }
+
+    #[tokio::test]
+    async fn cron_jobs_run_in_their_target_session() {
+        let state = GatewayState::new(OpenClawConfig::default())
+            .with_provider(Arc::new(ScriptedProvider::text("Morning briefing ready")));
+        let job = CronJob::from_config(&crate::config::CronJobConfig {
+            id: Some("brief".into()),
+            prompt: Some("Prepare my briefing".into()),
+            ..Default::default()
+        });
+
+        let named = SessionTarget::Named("briefings".into());
+        let reply = CronExecutor::run_agent_turn(&state, &job, "Prepare my briefing", &named).await.unwrap();
+        assert_eq!(reply, "Morning briefing ready");
+        let session = state.session_manager.get("cron:briefings").await.unwrap();
+        assert_eq!(session.message_count(), 2);
+
+        CronExecutor::run_agent_turn(&state, &job, "Again", &SessionTarget::Isolated).await.unwrap();
+        assert_eq!(state.session_manager.count().await, 1);
+
+        // Drop any provider picked up from the environment
+        let mut no_provider = GatewayState::new(OpenClawConfig::default());
+        no_provider.provider = None;
+        let err = CronExecutor::run_agent_turn(&no_provider, &job, "x", &named).await.unwrap_err();
+        assert!(err.contains("No model provider"));
+    }
+
+    #[tokio::test]
+    async fn turns_run_as_the_session_agent() {
+        let config: OpenClawConfig = serde_json::from_value(serde_json::json!({
+            "agents": {
+                "defaults": { "workspace": "/tmp/rc-ws" },
+                "list": [{ "id": "home" }, { "id": "work", "model": "anthropic/claude-opus", "tools": { "deny": ["exec"] } }]
+            }
+        })).unwrap();
+        let provider = ScriptedProvider::text("On it");
+        let state = GatewayState::new(config).with_provider(Arc::new(provider.clone()));
+        state.tool_registry.register_builtins().await;
+
+        let work = state.agents.get("work").unwrap();
+        let ctx = state.tool_context_for(work);
+        assert_eq!(ctx.workspace_dir, "/tmp/rc-ws-work");
+        assert!(ctx.memory_index.is_none());
+        assert_eq!(state.tool_context_for(state.agents.default_agent()).workspace_dir, "/tmp/rc-ws");
+
+        let mut session = state.open_session(&work.session_key("slack", "C1"), "work", "slack").await;
+        state.run_agent_turn(&mut session, "Draft the report", &ctx).await.unwrap();
+        let requests = provider.requests.lock().unwrap();
+        assert_eq!(requests[0].model, "claude-opus");
+        assert!(!requests[0].tools.is_empty() && requests[0].tools.iter().all(|t| t.name != "exec"));
+    }
+
+    #[tokio::test]
+    async fn cron_jobs_run_as_their_agent() {
+        let config: OpenClawConfig = serde_json::from_value(serde_json::json!({
+            "agents": {
+                "defaults": { "workspace": "/tmp/rc-ws" },
+                "list": [{ "id": "home" }, { "id": "work", "tools": { "deny": ["exec"] } }]
+            }
+        })).unwrap();
+        let provider = ScriptedProvider::text("Done");
+        let state = GatewayState::new(config).with_provider(Arc::new(provider.clone()));
+        state.tool_registry.register_builtins().await;
+        let mut job = CronJob::from_config(&crate::config::CronJobConfig {
+            id: Some("report".into()),
+            prompt: Some("Draft the report".into()),
+            agent_id: Some("work".into()),
+            ..Default::default()
+        });
+
+        let main = SessionTarget::Named("main".into());
+        CronExecutor::run_agent_turn(&state, &job, "Draft the report", &main).await.unwrap();
+        assert!(state.session_manager.get(&state.agents.get("work").unwrap().session_key("main", "main")).await.is_some());
+        assert!(provider.requests.lock().unwrap()[0].tools.iter().all(|t| t.name != "exec"));
+
+        job.agent_id = Some("gone".into());
+        let err = CronExecutor::run_agent_turn(&state, &job, "x", &main).await.unwrap_err();
+        assert!(err.contains("Unknown agent"));
+    }
+
+    #[tokio::test]
+    async fn subagents_announce_to_the_parent_chat() {
+        use crate::channel::{mock::MockChannel, ChannelCapabilities};
+        use crate::subagents::{SpawnRequest, SubagentStatus};
+
+        let provider = ScriptedProvider::text("Three flights under $400");
+        let state = GatewayState::new(OpenClawConfig::default()).with_provider(Arc::new(provider.clone()));
+        state.tool_registry.register_builtins().await;
+        let channel = MockChannel::new("telegram", ChannelCapabilities::default());
+        state.channel_manager.write().await.register(Box::new(channel.clone()));
+        let subagents = SubagentService::new(2).with_executor(Arc::new(state.clone()));
+        *state.subagents.write().await = Some(subagents.clone());
+
+        let parent = state.open_session("agent:main:telegram:42", "main", "telegram").await;
+        let run = subagents.spawn(SpawnRequest {
+            task: "Find flights to Lisbon".into(),
+            label: Some("flights".into()),
+            agent_id: "main".into(),
+            parent_session: parent.key.clone(),
+            channel: Some("telegram".into()),
+            to: Some("42".into()),
+            ..Default::default()
+        }).await.unwrap();
+        for _ in 0..200 {
+            if subagents.active_count().await == 0 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+
+        assert_eq!(subagents.get(&run.id).await.unwrap().status, SubagentStatus::Ok);
+        let parent = state.session_manager.get(&parent.key).await.unwrap();
+        assert!(parent.last_assistant_text().unwrap().contains("Three flights under $400"));
+        assert!(channel.sent.lock().unwrap()[0].text.starts_with("Subagent \"flights\" finished"));
+        assert_eq!(state.session_manager.get(&run.session_key).await.unwrap().message_count(), 2);
+
+        let requests = provider.requests.lock().unwrap();
+        let tools: Vec<&str> = requests[0].tools.iter().map(|t| t.name.as_str()).collect();
+        assert!(tools.contains(&"Read") && !tools.contains(&"sessions_spawn") && !tools.contains(&"message"));
+    }
+
+    #[tokio::test]
+    async fn acknowledged_heartbeats_leave_no_trace() {
+        let settings = HeartbeatSettings { every: crate::heartbeat::DEFAULT_EVERY, active_hours: None, target: None, to: None };
+        let key = crate::session::build_session_key("main", "main", "main");
+
+        let quiet = GatewayState::new(OpenClawConfig::default())
+            .with_provider(Arc::new(ScriptedProvider::text("HEARTBEAT_OK")));
+        assert_eq!(quiet.run_heartbeat("check", &settings).await.unwrap(), "HEARTBEAT_OK");
+        assert_eq!(quiet.session_manager.get(&key).await.unwrap().message_count(), 0);
+
+        let alert = GatewayState::new(OpenClawConfig::default())
+            .with_provider(Arc::new(ScriptedProvider::text("Your package arrives today")));
+        alert.run_heartbeat("check", &settings).await.unwrap();
+        assert_eq!(alert.session_manager.get(&key).await.unwrap().message_count(), 2);
+        assert_eq!(alert.heartbeat_status().await["enabled"], false);
+    }
 }
@@ -9,6 +9,11 @@ use futures::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tracing::{error, info, warn};
+use crate::approvals::Decision;
+use crate::channel::IncomingMessage;
+use crate::config::CronJobConfig;
+use crate::cron_system::{CronError, JobSource};
+use crate::gateway::inbound::Inbound;
 use crate::gateway::state::GatewayState;
 
 /// WebSocket protocol version.
@@ -54,7 +59,32 @@ async fn handle_ws_connection(socket: WebSocket, state: GatewayState) {
         return;
     }
 
-    while let Some(msg_result) = receiver.next().await {
+    // Approval requests are pushed to every connected client
+    let mut approvals = state.approvals.subscribe();
+
+    loop {
+        let msg_result = tokio::select! {
+            incoming = receiver.next() => match incoming {
+                Some(msg_result) => msg_result,
+                None => break,
+            },
+            event = approvals.recv() => {
+                match event {
+                    Ok(event) => {
+                        let notification = json!({ "method": event.method(), "params": event });
+                        if let Err(e) = sender.send(Message::Text(notification.to_string().into())).await {
+                            error!("Failed to push approval event: {}", e);
+                            break;
+                        }
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(missed)) => {
+                        warn!("WebSocket client missed {} approval events", missed);
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => {}
+                }
+                continue;
+            }
+        };
         let msg = match msg_result {
             Ok(m) => m,
             Err(e) => {
@@ -98,6 +128,31 @@ async fn handle_ws_connection(socket: WebSocket, state: GatewayState) {
     }
 }
 
+/// JSON-RPC error response for a request.
+fn error_response(msg: &WsMessage, code: i64, message: String) -> Option<WsMessage> {
+    Some(WsMessage {
+        id: msg.id.clone(),
+        method: None,
+        params: None,
+        result: None,
+        error: Some(json!({ "code": code, "message": message })),
+    })
+}
+
+/// Read a string parameter from a request.
+fn param_str<'a>(msg: &'a WsMessage, key: &str) -> Option<&'a str> {
+    msg.params.as_ref().and_then(|p| p.get(key)).and_then(|v| v.as_str())
+}
+
+/// Map a cron error to a JSON-RPC error response.
+fn cron_error(msg: &WsMessage, e: CronError) -> Option<WsMessage> {
+    let code = match e {
+        CronError::Invalid(_) => -32602,
+        _ => -32000,
+    };
+    error_response(msg, code, e.to_string())
+}
+
 async fn handle_ws_method(state: &GatewayState, msg: &WsMessage) -> Option<WsMessage> {
     let method = msg.method.as_deref().unwrap_or("");
 
@@ -109,15 +164,122 @@ async fn handle_ws_method(state: &GatewayState, msg: &WsMessage) -> Option<WsMes
                 "version": crate::version::VERSION,
                 "uptime": state.uptime_secs(),
                 "sessions": session_count,
+                "heartbeat": state.heartbeat_status().await,
             })
         }
         "gateway.health" => {
             json!({ "status": "ok" })
         }
         "sessions.list" => {
-            let keys = state.session_manager.list_keys().await;
+            let mut keys = state.session_manager.list_keys().await;
+            if let Some(agent_id) = param_str(msg, "agentId") {
+                let Some(agent) = state.agents.get(agent_id) else {
+                    return error_response(msg, -32602, format!("Unknown agent: {}", agent_id));
+                };
+                let prefix = agent.session_prefix();
+                keys.retain(|k| k.starts_with(&prefix));
+            }
             json!({ "sessions": keys })
         }
+        "sessions.delete" => {
+            // Ends the session; durable facts are flushed to memory first
+            let Some(key) = param_str(msg, "sessionKey") else {
+                return error_response(msg, -32602, "sessionKey is required".into());
+            };
+            if state.close_session(key).await.is_none() {
+                return error_response(msg, -32000, format!("Unknown session: {}", key));
+            }
+            json!({ "deleted": key })
+        }
+        "agents.list" => {
+            json!({ "default": state.agents.default_agent().id, "agents": state.agents.list() })
+        }
+        "agents.get" => {
+            let Some(agent_id) = param_str(msg, "agentId") else {
+                return error_response(msg, -32602, "agentId is required".into());
+            };
+            let Some(agent) = state.agents.get(agent_id) else {
+                return error_response(msg, -32000, format!("Unknown agent: {}", agent_id));
+            };
+            let prefix = agent.session_prefix();
+            let sessions: Vec<String> = state.session_manager.list_keys().await
+                .into_iter().filter(|k| k.starts_with(&prefix)).collect();
+            json!({ "agent": agent, "sessions": sessions })
+        }
+        "agents.route" => {
+            // Which agent a message with these fields would go to
+            let channel = param_str(msg, "channel").unwrap_or("");
+            let chat_id = param_str(msg, "chatId").unwrap_or("");
+            let from = param_str(msg, "from").unwrap_or(chat_id);
+            json!(state.route(channel, chat_id, from))
+        }
+        "channels.inbound" => {
+            // Messages from channel bridges. Acknowledged once routed; the
+            // agent answers in the background through the channel.
+            let incoming: IncomingMessage = match msg.params.clone().map(serde_json::from_value) {
+                Some(Ok(incoming)) => incoming,
+                Some(Err(e)) => return error_response(msg, -32602, format!("Invalid message: {}", e)),
+                None => return error_response(msg, -32602, "message params are required".into()),
+            };
+            match state.accept_incoming(&incoming).await {
+                Inbound::Approval(answer) => match answer {
+                    Ok((request, decision)) => json!({ "action": "approval", "id": request.id, "decision": decision }),
+                    Err(e) => json!({ "action": "approval", "error": e }),
+                },
+                Inbound::Dropped => json!({ "action": "dropped" }),
+                Inbound::Vote(vote) => json!({ "action": "vote", "vote": vote }),
+                Inbound::Buffered(route) => json!({ "action": "buffered", "route": route }),
+                Inbound::Turn { route, prompt } => {
+                    let result = json!({ "action": "turn", "route": route });
+                    let state = state.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = state.answer_incoming(&incoming, &route, &prompt).await {
+                            warn!("Agent turn for {} failed: {}", route.session_key, e);
+                        }
+                    });
+                    result
+                }
+            }
+        }
+        "subagents.list" => {
+            let Some(subagents) = state.subagents.read().await.clone() else {
+                return error_response(msg, -32000, "Subagents are not available".into());
+            };
+            let runs = subagents.list(param_str(msg, "parentSession")).await;
+            let active = runs.iter().filter(|r| r.status.is_active()).count();
+            json!({ "maxConcurrent": subagents.max_children(), "active": active, "runs": runs })
+        }
+        "subagents.cancel" => {
+            let Some(id) = param_str(msg, "id") else {
+                return error_response(msg, -32602, "id is required".into());
+            };
+            let Some(subagents) = state.subagents.read().await.clone() else {
+                return error_response(msg, -32000, "Subagents are not available".into());
+            };
+            match subagents.cancel(id).await {
+                Ok(run) => json!(run),
+                Err(e) => return error_response(msg, -32000, e.to_string()),
+            }
+        }
+        "exec.approvals.list" => {
+            json!({
+                "mode": state.approvals.mode(),
+                "pending": state.approvals.pending().await,
+                "remembered": state.approvals.remembered().await,
+            })
+        }
+        "exec.approvals.resolve" => {
+            let Some(id) = param_str(msg, "id") else {
+                return error_response(msg, -32602, "id is required".into());
+            };
+            let Some(decision) = param_str(msg, "decision").and_then(Decision::parse) else {
+                return error_response(msg, -32602, "decision must be allow-once, allow-always or deny".into());
+            };
+            match state.approvals.resolve(id, decision).await {
+                Ok(request) => json!({ "id": request.id, "command": request.command, "decision": decision }),
+                Err(e) => return error_response(msg, -32000, e.to_string()),
+            }
+        }
         "config.get" => {
             let config = state.config.read().await;
             json!({
@@ -130,6 +292,108 @@ async fn handle_ws_method(state: &GatewayState, msg: &WsMessage) -> Option<WsMes
             let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
             json!({ "tools": names })
         }
+        "polls.list" => {
+            let polls = state.poll_service.list().await;
+            json!({ "polls": polls })
+        }
+        "polls.results" => {
+            let Some(poll_id) = param_str(msg, "pollId") else {
+                return error_response(msg, -32602, "pollId is required".into());
+            };
+            match state.poll_service.results(poll_id).await {
+                Ok(results) => json!(results),
+                Err(e) => return error_response(msg, -32000, e.to_string()),
+            }
+        }
+        "polls.vote" => {
+            // Native poll updates from channel bridges: identify the poll by
+            // pollId or by the channel message id the poll was sent as.
+            let poll_id = match (param_str(msg, "pollId"), param_str(msg, "messageId")) {
+                (Some(id), _) => id.to_string(),
+                (None, Some(message_id)) => match state.poll_service.find_by_message_id(message_id).await {
+                    Some(poll) => poll.id,
+                    None => return error_response(msg, -32000, format!("No poll for message: {}", message_id)),
+                },
+                (None, None) => return error_response(msg, -32602, "pollId or messageId is required".into()),
+            };
+            let Some(voter) = param_str(msg, "voter") else {
+                return error_response(msg, -32602, "voter is required".into());
+            };
+            let selections: Vec<usize> = msg.params.as_ref()
+                .and_then(|p| p.get("selections"))
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter().filter_map(|v| v.as_u64()).map(|v| v as usize).collect())
+                .unwrap_or_default();
+            match state.poll_service.record_vote(&poll_id, voter, selections).await {
+                Ok(outcome) => json!(outcome),
+                Err(e) => return error_response(msg, -32000, e.to_string()),
+            }
+        }
+        "polls.close" => {
+            let Some(poll_id) = param_str(msg, "pollId") else {
+                return error_response(msg, -32602, "pollId is required".into());
+            };
+            match state.poll_service.close(poll_id).await {
+                Ok(poll) => json!(poll.results()),
+                Err(e) => return error_response(msg, -32000, e.to_string()),
+            }
+        }
+        "cron.list" | "cron.add" | "cron.update" | "cron.remove" | "cron.run" | "cron.history" => {
+            let Some(cron) = state.cron().await else {
+                return error_response(msg, -32000, "Cron service is not running".into());
+            };
+            let params = msg.params.clone().unwrap_or_else(|| json!({}));
+            match (method, param_str(msg, "id")) {
+                ("cron.list", _) => json!({ "jobs": cron.list_jobs().await }),
+                ("cron.add", _) => {
+                    let input: CronJobConfig = match serde_json::from_value(params) {
+                        Ok(input) => input,
+                        Err(e) => return error_response(msg, -32602, format!("Invalid job: {}", e)),
+                    };
+                    match cron.create_job(&input, JobSource::Runtime).await {
+                        Ok(job) => json!(job),
+                        Err(e) => return cron_error(msg, e),
+                    }
+                }
+                (_, None) => return error_response(msg, -32602, "id is required".into()),
+                ("cron.update", Some(id)) => {
+                    let patch: CronJobConfig = match serde_json::from_value(params) {
+                        Ok(patch) => patch,
+                        Err(e) => return error_response(msg, -32602, format!("Invalid job: {}", e)),
+                    };
+                    match cron.update_job(id, &patch).await {
+                        Ok(job) => json!(job),
+                        Err(e) => return cron_error(msg, e),
+                    }
+                }
+                ("cron.remove", Some(id)) => {
+                    if !cron.remove_job(id).await {
+                        return cron_error(msg, CronError::NotFound(id.to_string()));
+                    }
+                    json!({ "id": id, "removed": true })
+                }
+                ("cron.run", Some(id)) => {
+                    // Runs can take minutes; only block the socket when asked to
+                    if params.get("wait").and_then(|v| v.as_bool()).unwrap_or(false) {
+                        match cron.run_now(id).await {
+                            Ok(run) => json!(run),
+                            Err(e) => return cron_error(msg, e),
+                        }
+                    } else {
+                        match cron.trigger(id).await {
+                            Ok(()) => json!({ "id": id, "started": true }),
+                            Err(e) => return cron_error(msg, e),
+                        }
+                    }
+                }
+                (_, Some(id)) => {
+                    if cron.get_job(id).await.is_none() {
+                        return cron_error(msg, CronError::NotFound(id.to_string()));
+                    }
+                    json!({ "id": id, "runs": cron.history(id).await })
+                }
+            }
+        }
         _ => {
             return Some(WsMessage {
                 id: msg.id.clone(),
@@ -172,6 +436,50 @@ mod tests {
         assert!(!json.contains("params"));
     }
 
+    fn request(method: &str, params: Value) -> WsMessage {
+        WsMessage {
+            id: Some("1".into()),
+            method: Some(method.into()),
+            params: Some(params),
+            result: None,
+            error: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn polls_methods_round_trip() {
+        use crate::channel::{mock::MockChannel, ChannelCapabilities};
+        use crate::polls::PollInput;
+
+        let state = GatewayState::new(crate::config::OpenClawConfig::default());
+        let caps = ChannelCapabilities { polls: true, ..Default::default() };
+        state.channel_manager.write().await.register(Box::new(MockChannel::new("wa", caps)));
+        let poll = {
+            let channels = state.channel_manager.read().await;
+            state.poll_service.create(&channels, "wa", "+1555", &PollInput {
+                question: "Lunch?".into(),
+                options: vec!["Pizza".into(), "Sushi".into()],
+                max_selections: None,
+                duration_seconds: None,
+                duration_hours: None,
+            }).await.unwrap()
+        };
+
+        let vote = request("polls.vote", json!({
+            "messageId": poll.message_ids[0],
+            "voter": "+1",
+            "selections": [0],
+        }));
+        let resp = handle_ws_method(&state, &vote).await.unwrap();
+        assert!(resp.error.is_none());
+
+        let resp = handle_ws_method(&state, &request("polls.results", json!({ "pollId": poll.id }))).await.unwrap();
+        assert_eq!(resp.result.unwrap()["options"][0]["votes"], 1);
+
+        let resp = handle_ws_method(&state, &request("polls.results", json!({}))).await.unwrap();
+        assert_eq!(resp.error.unwrap()["code"], -32602);
+    }
+
     #[test]
     fn ws_message_deserialization() {
         let json = r#"{"id":"1","method":"gateway.status"}"#;
@@ -179,4 +487,166 @@ mod tests {
         assert_eq!(msg.id, Some("1".into()));
         assert_eq!(msg.method, Some("gateway.status".into()));
     }
+
+    #[tokio::test]
+    async fn agents_methods_list_and_route() {
+        let config: crate::config::OpenClawConfig = serde_json::from_value(json!({
+            "agents": { "list": [{ "id": "home" }, { "id": "work", "model": "anthropic/claude-opus" }] },
+            "bindings": [{ "agentId": "work", "match": { "channel": "slack" } }]
+        })).unwrap();
+        let state = GatewayState::new(config);
+        state.open_session("agent:work:slack:C1", "work", "slack").await;
+        state.open_session("agent:home:telegram:42", "home", "telegram").await;
+
+        let resp = handle_ws_method(&state, &request("agents.list", json!({}))).await.unwrap();
+        let result = resp.result.unwrap();
+        assert_eq!(result["default"], "home");
+        assert_eq!(result["agents"][1]["model"], "claude-opus");
+
+        let resp = handle_ws_method(&state, &request("agents.get", json!({ "agentId": "work" }))).await.unwrap();
+        assert_eq!(resp.result.unwrap()["sessions"], json!(["agent:work:slack:C1"]));
+        let resp = handle_ws_method(&state, &request("agents.get", json!({ "agentId": "nobody" }))).await.unwrap();
+        assert_eq!(resp.error.unwrap()["code"], -32000);
+
+        let resp = handle_ws_method(&state, &request("agents.route", json!({ "channel": "slack", "chatId": "C9" }))).await.unwrap();
+        let route = resp.result.unwrap();
+        assert_eq!((route["agentId"].as_str(), route["sessionKey"].as_str()), (Some("work"), Some("agent:work:slack:C9")));
+
+        let resp = handle_ws_method(&state, &request("sessions.list", json!({ "agentId": "home" }))).await.unwrap();
+        assert_eq!(resp.result.unwrap()["sessions"], json!(["agent:home:telegram:42"]));
+
+        let resp = handle_ws_method(&state, &request("sessions.delete", json!({ "sessionKey": "agent:home:telegram:42" }))).await.unwrap();
+        assert_eq!(resp.result.unwrap()["deleted"], "agent:home:telegram:42");
+        assert!(state.session_manager.get("agent:home:telegram:42").await.is_none());
+        let resp = handle_ws_method(&state, &request("sessions.delete", json!({ "sessionKey": "agent:home:telegram:42" }))).await.unwrap();
+        assert_eq!(resp.error.unwrap()["code"], -32000);
+    }
+
+    #[tokio::test]
+    async fn inbound_group_messages_are_buffered() {
+        let config = serde_json::from_value(json!({ "channels": { "whatsapp": { "groupPolicy": "open" } } })).unwrap();
+        let state = GatewayState::new(config);
+        let mut message = json!({
+            "id": "m1", "channel": "whatsapp", "from": "+1", "chat_id": "g1@g.us",
+            "text": "anyone around?", "timestamp": 0, "is_group": true, "mentions_bot": false,
+        });
+        let resp = handle_ws_method(&state, &request("channels.inbound", message.clone())).await.unwrap();
+        let result = resp.result.unwrap();
+        assert_eq!(result["action"], "buffered");
+        assert_eq!(result["route"]["sessionKey"], "agent:main:whatsapp:g1@g.us");
+        assert_eq!(state.group_history.len("agent:main:whatsapp:g1@g.us").await, 1);
+
+        // Channels without a registered plugin can't vouch for the sender
+        message["channel"] = json!("telegram");
+        let resp = handle_ws_method(&state, &request("channels.inbound", message)).await.unwrap();
+        assert_eq!(resp.result.unwrap()["action"], "dropped");
+
+        let resp = handle_ws_method(&state, &request("channels.inbound", json!({ "text": "hi" }))).await.unwrap();
+        assert_eq!(resp.error.unwrap()["code"], -32602);
+    }
+
+    #[tokio::test]
+    async fn subagent_methods_list_and_cancel() {
+        use crate::subagents::{SpawnRequest, SubagentExecutor, SubagentRun, SubagentService};
+
+        struct Stalled;
+        #[async_trait::async_trait]
+        impl SubagentExecutor for Stalled {
+            async fn run_subagent(&self, _run: &SubagentRun, _cancel: tokio_util::sync::CancellationToken) -> Result<String, String> {
+                std::future::pending().await
+            }
+            async fn announce(&self, _run: &SubagentRun) {}
+        }
+
+        let state = GatewayState::new(crate::config::OpenClawConfig::default());
+        let resp = handle_ws_method(&state, &request("subagents.list", json!({}))).await.unwrap();
+        assert_eq!(resp.error.unwrap()["code"], -32000);
+
+        let subagents = SubagentService::new(1).with_executor(std::sync::Arc::new(Stalled));
+        *state.subagents.write().await = Some(subagents.clone());
+        let run = subagents.spawn(SpawnRequest {
+            task: "Watch the build".into(),
+            agent_id: "main".into(),
+            parent_session: "agent:main:main:main".into(),
+            ..Default::default()
+        }).await.unwrap();
+
+        let resp = handle_ws_method(&state, &request("subagents.list", json!({ "parentSession": "agent:main:main:main" }))).await.unwrap();
+        let result = resp.result.unwrap();
+        assert_eq!((result["active"].as_u64(), result["runs"][0]["id"].as_str()), (Some(1), Some(run.id.as_str())));
+
+        let resp = handle_ws_method(&state, &request("subagents.cancel", json!({ "id": run.id }))).await.unwrap();
+        assert_eq!(resp.result.unwrap()["status"], "cancelled");
+        let resp = handle_ws_method(&state, &request("subagents.cancel", json!({ "id": run.id }))).await.unwrap();
+        assert_eq!(resp.error.unwrap()["code"], -32000);
+    }
+
+    #[tokio::test]
+    async fn approval_methods_resolve_pending_requests() {
+        let config: crate::config::OpenClawConfig = serde_json::from_value(json!({ "approvals": { "mode": "on-miss" } })).unwrap();
+        let state = GatewayState::new(config);
+        let mut events = state.approvals.subscribe();
+        let waiting = tokio::spawn({
+            let approvals = state.approvals.clone();
+            async move { approvals.authorize("cargo publish", "/work", Default::default()).await }
+        });
+        let event = events.recv().await.unwrap();
+        assert_eq!(event.method(), "exec.approval.requested");
+
+        let resp = handle_ws_method(&state, &request("exec.approvals.list", json!({}))).await.unwrap();
+        let result = resp.result.unwrap();
+        assert_eq!(result["mode"], "on-miss");
+        let id = result["pending"][0]["id"].as_str().unwrap().to_string();
+        assert_eq!(result["pending"][0]["command"], "cargo publish");
+
+        let resp = handle_ws_method(&state, &request("exec.approvals.resolve", json!({ "id": id, "decision": "maybe" }))).await.unwrap();
+        assert_eq!(resp.error.unwrap()["code"], -32602);
+        let resp = handle_ws_method(&state, &request("exec.approvals.resolve", json!({ "id": id, "decision": "always" }))).await.unwrap();
+        assert_eq!(resp.result.unwrap()["decision"], "allow-always");
+        assert!(waiting.await.unwrap().is_ok());
+
+        let resp = handle_ws_method(&state, &request("exec.approvals.list", json!({}))).await.unwrap();
+        assert_eq!(resp.result.unwrap()["remembered"], json!(["cargo publish"]));
+        let resp = handle_ws_method(&state, &request("exec.approvals.resolve", json!({ "id": id, "decision": "deny" }))).await.unwrap();
+        assert_eq!(resp.error.unwrap()["code"], -32000);
+    }
+
+    #[tokio::test]
+    async fn cron_methods_manage_jobs() {
+        let state = GatewayState::new(crate::config::OpenClawConfig::default());
+        let resp = handle_ws_method(&state, &request("cron.list", json!({}))).await.unwrap();
+        assert_eq!(resp.error.unwrap()["code"], -32000);
+
+        *state.cron_service.write().await = Some(crate::cron_system::CronService::new());
+        let resp = handle_ws_method(&state, &request("cron.add", json!({
+            "id": "standup",
+            "schedule": "0 0 9 * * Mon-Fri",
+            "prompt": "Post the standup reminder",
+            "timezone": "Europe/Berlin",
+        }))).await.unwrap();
+        let job = resp.result.unwrap();
+        assert_eq!(job["source"], "runtime");
+        assert!(job["nextRun"].is_string());
+
+        let resp = handle_ws_method(&state, &request("cron.add", json!({ "schedule": "sometimes", "prompt": "x" }))).await.unwrap();
+        assert_eq!(resp.error.unwrap()["code"], -32602);
+
+        let resp = handle_ws_method(&state, &request("cron.update", json!({ "id": "standup", "enabled": false }))).await.unwrap();
+        assert_eq!(resp.result.unwrap()["enabled"], false);
+        let resp = handle_ws_method(&state, &request("cron.list", json!({}))).await.unwrap();
+        assert_eq!(resp.result.unwrap()["jobs"][0]["id"], "standup");
+
+        // No executor configured, so the run records an error
+        let resp = handle_ws_method(&state, &request("cron.run", json!({ "id": "standup", "wait": true }))).await.unwrap();
+        assert_eq!(resp.result.unwrap()["status"], "error");
+        let resp = handle_ws_method(&state, &request("cron.history", json!({ "id": "standup" }))).await.unwrap();
+        assert_eq!(resp.result.unwrap()["runs"].as_array().unwrap().len(), 1);
+
+        let resp = handle_ws_method(&state, &request("cron.remove", json!({ "id": "standup" }))).await.unwrap();
+        assert_eq!(resp.result.unwrap()["removed"], true);
+        let resp = handle_ws_method(&state, &request("cron.remove", json!({ "id": "standup" }))).await.unwrap();
+        assert_eq!(resp.error.unwrap()["code"], -32000);
+        let resp = handle_ws_method(&state, &request("cron.run", json!({}))).await.unwrap();
+        assert_eq!(resp.error.unwrap()["code"], -32602);
+    }
 }
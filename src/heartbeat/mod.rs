@@ -0,0 +1,581 @@
+//! Heartbeat: a periodic agent turn that works through `HEARTBEAT.md` during
+//! active hours and only messages the user when something needs attention.
+
+use crate::channel::{ChannelManager, OutgoingMessage};
+use crate::config::HeartbeatConfig;
+use crate::cron_system::schedule::{parse_interval, resolve_local};
+use crate::cron_system::parse_timezone;
+use crate::utils::excerpt;
+use async_trait::async_trait;
+use chrono::{DateTime, Local, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Notify, RwLock};
+use tracing::{info, warn};
+
+/// Reply meaning "nothing needs attention"; such replies are not delivered.
+pub const HEARTBEAT_OK: &str = "HEARTBEAT_OK";
+/// Interval used when `every` is unset.
+pub const DEFAULT_EVERY: Duration = Duration::from_secs(30 * 60);
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(300);
+const MAX_RECENT_RUNS: usize = 20;
+
+const HEARTBEAT_PROMPT: &str = "This is a scheduled heartbeat check. Go through the checklist \
+from HEARTBEAT.md below and act on anything that is due. Don't revisit tasks from earlier \
+conversations that aren't on the list. If nothing needs the user's attention, reply with \
+exactly HEARTBEAT_OK and nothing else; otherwise reply with a short message for the user.";
+
+#[derive(Debug, thiserror::Error)]
+pub enum HeartbeatError {
+    #[error("Invalid heartbeat config: {0}")]
+    Config(String),
+}
+
+/// Daily window in which heartbeats may run. A window whose end is before its
+/// start runs overnight; equal start and end means all day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveHours {
+    /// Minutes after midnight.
+    start: u32,
+    /// Minutes after midnight; 1440 for "24:00".
+    end: u32,
+    /// Host local time when unset.
+    timezone: Option<Tz>,
+}
+
+impl ActiveHours {
+    pub fn parse(start: &str, end: &str, timezone: Option<&str>) -> Result<Self, HeartbeatError> {
+        let timezone = timezone.map(parse_timezone).transpose().map_err(HeartbeatError::Config)?;
+        Ok(Self { start: parse_minutes(start)?, end: parse_minutes(end)?, timezone })
+    }
+
+    fn local(&self, t: DateTime<Utc>) -> NaiveDateTime {
+        match self.timezone {
+            Some(tz) => t.with_timezone(&tz).naive_local(),
+            None => t.with_timezone(&Local).naive_local(),
+        }
+    }
+
+    fn instant(&self, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self.timezone {
+            Some(tz) => resolve_local(tz, naive),
+            None => match Local.from_local_datetime(&naive).earliest() {
+                Some(t) => Some(t.with_timezone(&Utc)),
+                None => Local.from_local_datetime(&(naive - chrono::Duration::hours(1))).earliest()
+                    .map(|t| t.with_timezone(&Utc) + chrono::Duration::hours(1)),
+            },
+        }
+    }
+
+    pub fn contains(&self, t: DateTime<Utc>) -> bool {
+        let local = self.local(t);
+        let minute = local.hour() * 60 + local.minute();
+        match self.start.cmp(&self.end) {
+            std::cmp::Ordering::Less => minute >= self.start && minute < self.end,
+            std::cmp::Ordering::Greater => minute >= self.start || minute < self.end,
+            std::cmp::Ordering::Equal => true,
+        }
+    }
+
+    /// `after` if it falls inside the window, otherwise the next window start.
+    pub fn next_open(&self, after: DateTime<Utc>) -> DateTime<Utc> {
+        if self.contains(after) {
+            return after;
+        }
+        let start = NaiveTime::from_hms_opt(self.start / 60, self.start % 60, 0).unwrap_or_default();
+        let date = self.local(after).date();
+        [date, date + chrono::Duration::days(1), date + chrono::Duration::days(2)].into_iter()
+            .filter_map(|d| self.instant(d.and_time(start)))
+            .find(|t| *t > after)
+            .unwrap_or(after)
+    }
+
+    /// e.g. "08:00-22:00 Europe/Berlin".
+    pub fn describe(&self) -> String {
+        let fmt = |m: u32| format!("{:02}:{:02}", m / 60, m % 60);
+        let zone = self.timezone.map(|tz| tz.name().to_string()).unwrap_or_else(|| "local".into());
+        format!("{}-{} {}", fmt(self.start), fmt(self.end), zone)
+    }
+}
+
+/// "HH:MM" as minutes after midnight; "24:00" is accepted as end of day.
+fn parse_minutes(s: &str) -> Result<u32, HeartbeatError> {
+    let s = s.trim();
+    if s == "24:00" {
+        return Ok(24 * 60);
+    }
+    NaiveTime::parse_from_str(s, "%H:%M")
+        .map(|t| t.hour() * 60 + t.minute())
+        .map_err(|_| HeartbeatError::Config(format!("expected HH:MM, got '{}'", s)))
+}
+
+/// Parsed `agents.defaults.heartbeat`.
+#[derive(Debug, Clone)]
+pub struct HeartbeatSettings {
+    pub every: Duration,
+    pub active_hours: Option<ActiveHours>,
+    /// Channel alerts are delivered on; "none" keeps them in the session.
+    pub target: Option<String>,
+    pub to: Option<String>,
+}
+
+impl HeartbeatSettings {
+    /// `None` when `every` is zero ("0m"), which disables heartbeats.
+    pub fn from_config(config: &HeartbeatConfig) -> Result<Option<Self>, HeartbeatError> {
+        let every = match config.every.as_deref().map(str::trim) {
+            None | Some("") => DEFAULT_EVERY,
+            Some("0") => return Ok(None),
+            Some(raw) => {
+                let every = parse_interval(raw)
+                    .ok_or_else(|| HeartbeatError::Config(format!("invalid every '{}'", raw)))?
+                    .map_err(HeartbeatError::Config)?;
+                if every <= chrono::Duration::zero() {
+                    return Ok(None);
+                }
+                every.to_std().map_err(|e| HeartbeatError::Config(e.to_string()))?
+            }
+        };
+        let active_hours = match &config.active_hours {
+            Some(hours) => Some(ActiveHours::parse(
+                hours.start.as_deref().unwrap_or("00:00"),
+                hours.end.as_deref().unwrap_or("24:00"),
+                hours.timezone.as_deref(),
+            )?),
+            None => None,
+        };
+        Ok(Some(Self {
+            every,
+            active_hours,
+            target: config.target.clone(),
+            to: config.to.clone(),
+        }))
+    }
+
+    /// Channel and recipient for alerts, if delivery is configured.
+    pub fn delivery(&self) -> Option<(&str, &str)> {
+        match (self.target.as_deref(), self.to.as_deref()) {
+            (Some("none"), _) | (None, _) | (_, None) => None,
+            (Some(channel), Some(to)) => Some((channel, to)),
+        }
+    }
+
+    /// When the next heartbeat runs: `every` after `after`, moved to the
+    /// start of the active window when that falls outside it.
+    pub fn next_run_after(&self, after: DateTime<Utc>) -> DateTime<Utc> {
+        let next = after + chrono::Duration::from_std(self.every).unwrap_or(chrono::Duration::minutes(30));
+        match &self.active_hours {
+            Some(hours) => hours.next_open(next),
+            None => next,
+        }
+    }
+
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.active_hours.as_ref().is_none_or(|h| h.contains(now))
+    }
+}
+
+/// Read `HEARTBEAT.md`; `None` when it's missing or has nothing but
+/// headings, comments and blank lines, so no model call is wasted on it.
+pub fn load_checklist(workspace_dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(workspace_dir.join("HEARTBEAT.md")).ok()?;
+    let actionable = content.lines()
+        .map(str::trim)
+        .any(|l| !l.is_empty() && !l.starts_with('#') && !l.starts_with("<!--"));
+    actionable.then(|| content.trim().to_string())
+}
+
+/// Whether a reply is an acknowledgement: `HEARTBEAT_OK` at its start or
+/// end, possibly wrapped in markdown. Any text around it is commentary.
+pub fn is_ack(reply: &str) -> bool {
+    let text = reply.trim_matches(|c: char| c.is_whitespace() || matches!(c, '*' | '`' | '.' | '!' | '_'));
+    text.starts_with(HEARTBEAT_OK) || text.ends_with(HEARTBEAT_OK)
+}
+
+/// Runs the heartbeat agent turn.
+#[async_trait]
+pub trait HeartbeatExecutor: Send + Sync {
+    async fn run_heartbeat(&self, prompt: &str, settings: &HeartbeatSettings) -> Result<String, String>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HeartbeatOutcome {
+    /// The model replied HEARTBEAT_OK; nothing was delivered.
+    Ok,
+    /// The model flagged something for the user.
+    Alert,
+    /// No model call was made (empty checklist).
+    Skipped,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeartbeatRun {
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub outcome: HeartbeatOutcome,
+    pub excerpt: Option<String>,
+    pub error: Option<String>,
+    /// Message ids of a delivered alert.
+    #[serde(default)]
+    pub delivered: Vec<String>,
+}
+
+/// Counters and recent runs for status output.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeartbeatStatus {
+    pub every_seconds: u64,
+    pub active_hours: Option<String>,
+    pub in_active_hours: bool,
+    pub target: Option<String>,
+    pub next_run: Option<DateTime<Utc>>,
+    pub runs: u64,
+    pub alerts: u64,
+    /// Runs answered with HEARTBEAT_OK, whose delivery was suppressed.
+    pub suppressed: u64,
+    pub skipped: u64,
+    pub errors: u64,
+    /// Newest first.
+    pub recent: Vec<HeartbeatRun>,
+}
+
+#[derive(Default)]
+struct Stats {
+    runs: u64,
+    alerts: u64,
+    suppressed: u64,
+    skipped: u64,
+    errors: u64,
+    next_run: Option<DateTime<Utc>>,
+    recent: VecDeque<HeartbeatRun>,
+}
+
+/// Schedules heartbeat turns.
+#[derive(Clone)]
+pub struct HeartbeatService {
+    settings: HeartbeatSettings,
+    workspace_dir: PathBuf,
+    executor: Option<Arc<dyn HeartbeatExecutor>>,
+    channels: Option<Arc<RwLock<ChannelManager>>>,
+    stats: Arc<RwLock<Stats>>,
+    running: Arc<RwLock<bool>>,
+    wake: Arc<Notify>,
+}
+
+impl HeartbeatService {
+    pub fn new(settings: HeartbeatSettings, workspace_dir: &str) -> Self {
+        Self {
+            settings,
+            workspace_dir: crate::utils::resolve_user_path(workspace_dir),
+            executor: None,
+            channels: None,
+            stats: Arc::new(RwLock::new(Stats::default())),
+            running: Arc::new(RwLock::new(false)),
+            wake: Arc::new(Notify::new()),
+        }
+    }
+
+    pub fn with_executor(mut self, executor: Arc<dyn HeartbeatExecutor>) -> Self {
+        self.executor = Some(executor);
+        self
+    }
+
+    /// Delivers alerts when `target` and `to` are set.
+    pub fn with_channels(mut self, channels: Arc<RwLock<ChannelManager>>) -> Self {
+        self.channels = Some(channels);
+        self
+    }
+
+    pub fn settings(&self) -> &HeartbeatSettings {
+        &self.settings
+    }
+
+    /// Run one heartbeat now, regardless of active hours, and record it.
+    pub async fn run_once(&self) -> HeartbeatRun {
+        let started_at = Utc::now();
+        let clock = Instant::now();
+        let mut run = HeartbeatRun {
+            started_at,
+            duration_ms: 0,
+            outcome: HeartbeatOutcome::Ok,
+            excerpt: None,
+            error: None,
+            delivered: Vec::new(),
+        };
+
+        match self.execute().await {
+            Ok(None) => {
+                run.outcome = HeartbeatOutcome::Skipped;
+                run.error = Some("HEARTBEAT.md is missing or empty".into());
+            }
+            Ok(Some(reply)) if is_ack(&reply) => run.outcome = HeartbeatOutcome::Ok,
+            Ok(Some(reply)) => {
+                run.outcome = HeartbeatOutcome::Alert;
+                run.excerpt = Some(excerpt(&reply));
+                match self.deliver(reply.trim()).await {
+                    Ok(ids) => run.delivered = ids,
+                    Err(e) => {
+                        run.outcome = HeartbeatOutcome::Error;
+                        run.error = Some(format!("delivery failed: {}", e));
+                    }
+                }
+            }
+            Err(e) => {
+                run.outcome = HeartbeatOutcome::Error;
+                run.error = Some(e);
+            }
+        }
+        run.duration_ms = clock.elapsed().as_millis() as u64;
+
+        match run.outcome {
+            HeartbeatOutcome::Error => warn!("Heartbeat failed: {}", run.error.as_deref().unwrap_or("")),
+            outcome => info!("Heartbeat finished: {:?}", outcome),
+        }
+        self.record(run.clone()).await;
+        run
+    }
+
+    /// The model's reply, or `None` when there was nothing to check.
+    async fn execute(&self) -> Result<Option<String>, String> {
+        let Some(checklist) = load_checklist(&self.workspace_dir) else {
+            return Ok(None);
+        };
+        let executor = self.executor.as_ref().ok_or("no agent executor configured")?;
+        let prompt = format!("{}\n\n<HEARTBEAT.md>\n{}\n</HEARTBEAT.md>", HEARTBEAT_PROMPT, checklist);
+        match tokio::time::timeout(HEARTBEAT_TIMEOUT, executor.run_heartbeat(&prompt, &self.settings)).await {
+            Ok(result) => result.map(Some),
+            Err(_) => Err(format!("timed out after {}s", HEARTBEAT_TIMEOUT.as_secs())),
+        }
+    }
+
+    async fn deliver(&self, text: &str) -> Result<Vec<String>, String> {
+        let Some((channel, to)) = self.settings.delivery() else {
+            return Ok(Vec::new());
+        };
+        let channels = self.channels.as_ref().ok_or("no channels configured")?;
+        let outcome = channels.read().await
+            .send(&OutgoingMessage::text(channel, to, text))
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(outcome.message_ids)
+    }
+
+    async fn record(&self, run: HeartbeatRun) {
+        let mut stats = self.stats.write().await;
+        stats.runs += 1;
+        match run.outcome {
+            HeartbeatOutcome::Ok => stats.suppressed += 1,
+            HeartbeatOutcome::Alert => stats.alerts += 1,
+            HeartbeatOutcome::Skipped => stats.skipped += 1,
+            HeartbeatOutcome::Error => stats.errors += 1,
+        }
+        stats.recent.push_front(run);
+        stats.recent.truncate(MAX_RECENT_RUNS);
+    }
+
+    pub async fn status(&self) -> HeartbeatStatus {
+        let stats = self.stats.read().await;
+        HeartbeatStatus {
+            every_seconds: self.settings.every.as_secs(),
+            active_hours: self.settings.active_hours.as_ref().map(ActiveHours::describe),
+            in_active_hours: self.settings.is_active(Utc::now()),
+            target: self.settings.delivery().map(|(channel, to)| format!("{}:{}", channel, to)),
+            next_run: stats.next_run,
+            runs: stats.runs,
+            alerts: stats.alerts,
+            suppressed: stats.suppressed,
+            skipped: stats.skipped,
+            errors: stats.errors,
+            recent: stats.recent.iter().cloned().collect(),
+        }
+    }
+
+    /// Run heartbeats every `every`, only inside active hours.
+    pub async fn start(&self) {
+        {
+            let mut running = self.running.write().await;
+            if *running {
+                return;
+            }
+            *running = true;
+        }
+
+        let service = self.clone();
+        tokio::spawn(async move {
+            info!("Heartbeat started (every {:?})", service.settings.every);
+            let mut next = service.settings.next_run_after(Utc::now());
+            loop {
+                service.stats.write().await.next_run = Some(next);
+                let wait = (next - Utc::now()).to_std().unwrap_or(Duration::ZERO);
+                tokio::select! {
+                    _ = tokio::time::sleep(wait) => {}
+                    _ = service.wake.notified() => {}
+                }
+                if !*service.running.read().await {
+                    break;
+                }
+                let now = Utc::now();
+                if now < next {
+                    continue;
+                }
+                // The clock may have jumped past the window while we slept
+                if service.settings.is_active(now) {
+                    service.run_once().await;
+                }
+                next = service.settings.next_run_after(Utc::now());
+            }
+            service.stats.write().await.next_run = None;
+            info!("Heartbeat stopped");
+        });
+    }
+
+    pub async fn stop(&self) {
+        *self.running.write().await = false;
+        self.wake.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ActiveHoursConfig;
+
+    fn at(h: u32, m: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 6, 1, h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn active_hours_windows() {
+        let day = ActiveHours::parse("08:00", "22:00", Some("UTC")).unwrap();
+        assert!(day.contains(at(8, 0)));
+        assert!(!day.contains(at(22, 0)));
+        assert!(!day.contains(at(3, 0)));
+        assert_eq!(day.next_open(at(23, 0)), Utc.with_ymd_and_hms(2026, 6, 2, 8, 0, 0).unwrap());
+        assert_eq!(day.next_open(at(6, 30)), at(8, 0));
+        assert_eq!(day.next_open(at(12, 0)), at(12, 0));
+
+        let night = ActiveHours::parse("22:00", "06:00", Some("UTC")).unwrap();
+        assert!(night.contains(at(23, 30)) && night.contains(at(5, 59)));
+        assert!(!night.contains(at(12, 0)));
+
+        // 08:00 in Berlin (UTC+2 in June) is 06:00 UTC
+        let berlin = ActiveHours::parse("08:00", "24:00", Some("Europe/Berlin")).unwrap();
+        assert!(!berlin.contains(at(5, 30)));
+        assert!(berlin.contains(at(21, 59)));
+        assert_eq!(berlin.next_open(at(5, 0)), at(6, 0));
+        assert_eq!(berlin.describe(), "08:00-24:00 Europe/Berlin");
+
+        assert!(ActiveHours::parse("8am", "22:00", None).is_err());
+        assert!(ActiveHours::parse("08:00", "22:00", Some("Nowhere/City")).is_err());
+    }
+
+    #[test]
+    fn settings_from_config() {
+        let config = HeartbeatConfig {
+            every: Some("1h".into()),
+            active_hours: Some(ActiveHoursConfig { start: Some("09:00".into()), end: Some("18:00".into()), timezone: Some("UTC".into()) }),
+            target: Some("telegram".into()),
+            to: Some("42".into()),
+        };
+        let settings = HeartbeatSettings::from_config(&config).unwrap().unwrap();
+        assert_eq!(settings.every, Duration::from_secs(3600));
+        assert_eq!(settings.delivery(), Some(("telegram", "42")));
+        // 17:30 + 1h is outside the window, so the next run is tomorrow at 09:00
+        assert_eq!(settings.next_run_after(at(17, 30)), Utc.with_ymd_and_hms(2026, 6, 2, 9, 0, 0).unwrap());
+        assert_eq!(settings.next_run_after(at(10, 0)), at(11, 0));
+
+        let defaults = HeartbeatSettings::from_config(&HeartbeatConfig { target: Some("none".into()), to: Some("42".into()), ..Default::default() }).unwrap().unwrap();
+        assert_eq!(defaults.every, DEFAULT_EVERY);
+        assert_eq!(defaults.delivery(), None);
+        assert!(HeartbeatSettings::from_config(&HeartbeatConfig { every: Some("0m".into()), ..Default::default() }).unwrap().is_none());
+        assert!(HeartbeatSettings::from_config(&HeartbeatConfig { every: Some("often".into()), ..Default::default() }).is_err());
+    }
+
+    #[test]
+    fn sentinel_replies() {
+        assert!(is_ack("HEARTBEAT_OK"));
+        assert!(is_ack("  **HEARTBEAT_OK**\n"));
+        assert!(is_ack("All quiet. HEARTBEAT_OK"));
+        assert!(!is_ack("Your flight to Oslo boards in 2 hours."));
+        assert!(!is_ack("Reply HEARTBEAT_OK-style acks are off, but: the server is down."));
+    }
+
+    #[test]
+    fn empty_checklists_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(load_checklist(dir.path()).is_none());
+        std::fs::write(dir.path().join("HEARTBEAT.md"), "# Heartbeat\n\n<!-- add checks here -->\n").unwrap();
+        assert!(load_checklist(dir.path()).is_none());
+        std::fs::write(dir.path().join("HEARTBEAT.md"), "# Heartbeat\n- Check the calendar for the next 2 hours\n").unwrap();
+        assert!(load_checklist(dir.path()).unwrap().contains("calendar"));
+    }
+
+    struct Scripted(String);
+
+    #[async_trait]
+    impl HeartbeatExecutor for Scripted {
+        async fn run_heartbeat(&self, prompt: &str, _settings: &HeartbeatSettings) -> Result<String, String> {
+            assert!(prompt.contains("Check the calendar"));
+            Ok(self.0.clone())
+        }
+    }
+
+    fn service(dir: &tempfile::TempDir, reply: &str, channels: Arc<RwLock<ChannelManager>>) -> HeartbeatService {
+        let settings = HeartbeatSettings {
+            every: DEFAULT_EVERY,
+            active_hours: None,
+            target: Some("whatsapp".into()),
+            to: Some("+15551234567".into()),
+        };
+        HeartbeatService::new(settings, dir.path().to_str().unwrap())
+            .with_executor(Arc::new(Scripted(reply.into())))
+            .with_channels(channels)
+    }
+
+    #[tokio::test]
+    async fn suppresses_ok_and_delivers_alerts() {
+        use crate::channel::mock::MockChannel;
+        use crate::channel::ChannelCapabilities;
+
+        let dir = tempfile::tempdir().unwrap();
+        let channel = MockChannel::new("whatsapp", ChannelCapabilities::default());
+        let mut manager = ChannelManager::new();
+        manager.register(Box::new(channel.clone()));
+        let channels = Arc::new(RwLock::new(manager));
+
+        // No checklist yet: skipped without calling the model
+        let quiet = service(&dir, HEARTBEAT_OK, channels.clone());
+        assert_eq!(quiet.run_once().await.outcome, HeartbeatOutcome::Skipped);
+
+        std::fs::write(dir.path().join("HEARTBEAT.md"), "- Check the calendar\n").unwrap();
+        assert_eq!(quiet.run_once().await.outcome, HeartbeatOutcome::Ok);
+        assert!(channel.sent.lock().unwrap().is_empty());
+
+        let alert = service(&dir, "Dentist at 15:00, leave in 20 minutes.", channels).run_once().await;
+        assert_eq!(alert.outcome, HeartbeatOutcome::Alert);
+        assert_eq!(alert.delivered, vec!["msg-1"]);
+        assert_eq!(channel.sent.lock().unwrap()[0].text, "Dentist at 15:00, leave in 20 minutes.");
+
+        let status = quiet.status().await;
+        assert_eq!((status.runs, status.suppressed, status.skipped, status.alerts), (2, 1, 1, 0));
+        assert_eq!(status.recent[0].outcome, HeartbeatOutcome::Ok);
+        assert_eq!(status.target.as_deref(), Some("whatsapp:+15551234567"));
+    }
+
+    #[tokio::test]
+    async fn missing_executor_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("HEARTBEAT.md"), "- Check the calendar\n").unwrap();
+        let settings = HeartbeatSettings { every: DEFAULT_EVERY, active_hours: None, target: None, to: None };
+        let service = HeartbeatService::new(settings, dir.path().to_str().unwrap());
+        let run = service.run_once().await;
+        assert_eq!(run.outcome, HeartbeatOutcome::Error);
+        assert_eq!(service.status().await.errors, 1);
+    }
+}
@@ -1,7 +1,9 @@
+pub mod approvals;
 pub mod cli;
 pub mod config;
 pub mod markdown;
 pub mod polls;
+pub mod process;
 pub mod security;
 pub mod utils;
 pub mod version;
@@ -11,7 +13,10 @@ pub mod session;
 pub mod tools;
 pub mod channel;
 pub mod cron_system;
+pub mod heartbeat;
+pub mod subagents;
 pub mod memory;
+pub mod agent;
 pub mod logging;
 
 /// Re-export commonly used items
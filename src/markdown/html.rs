@@ -0,0 +1,322 @@
+use regex::{Captures, Regex};
+
+/// Readable content extracted from an HTML page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadablePage {
+    pub title: Option<String>,
+    pub markdown: String,
+}
+
+/// Tags whose content is never readable text.
+const NOISE_TAGS: &[&str] = &["script", "style", "noscript", "svg", "iframe", "template", "head", "canvas"];
+
+/// Page chrome stripped from the selected content region.
+const BOILERPLATE_TAGS: &[&str] = &["nav", "footer", "aside", "form", "button", "select", "dialog"];
+
+fn remove_elements(html: &str, tags: &[&str]) -> String {
+    let mut result = html.to_string();
+    for tag in tags {
+        let re = Regex::new(&format!(r"(?is)<{tag}\b[^>]*>.*?</{tag}\s*>|<{tag}\b[^>]*/>", tag = tag)).unwrap();
+        result = re.replace_all(&result, "").to_string();
+    }
+    result
+}
+
+/// Pick the main content region: <article>, then <main>, then role="main", then <body>.
+fn select_content_region(html: &str) -> (String, bool) {
+    let candidates = [
+        r"(?is)<article\b[^>]*>(.*)</article\s*>",
+        r"(?is)<main\b[^>]*>(.*)</main\s*>",
+        r#"(?is)<div\b[^>]*role\s*=\s*["']main["'][^>]*>(.*)</div\s*>"#,
+    ];
+    for pattern in candidates {
+        let re = Regex::new(pattern).unwrap();
+        if let Some(caps) = re.captures(html) {
+            let inner = caps[1].to_string();
+            if !strip_tags(&inner).trim().is_empty() {
+                return (inner, true);
+            }
+        }
+    }
+    let body_re = Regex::new(r"(?is)<body\b[^>]*>(.*)</body\s*>").unwrap();
+    match body_re.captures(html) {
+        Some(caps) => (caps[1].to_string(), false),
+        None => (html.to_string(), false),
+    }
+}
+
+fn strip_tags(html: &str) -> String {
+    let re = Regex::new(r"(?s)<[^>]*>").unwrap();
+    re.replace_all(html, "").to_string()
+}
+
+/// Collapse runs of whitespace into single spaces.
+fn collapse_inline(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Decode the HTML entities that commonly appear in page text.
+pub fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let re = Regex::new(r"&(#[0-9]{1,7}|#[xX][0-9a-fA-F]{1,6}|[a-zA-Z]{2,8});").unwrap();
+    re.replace_all(text, |caps: &Captures| {
+        let entity = &caps[1];
+        let decoded = if let Some(hex) = entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+            u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+        } else if let Some(dec) = entity.strip_prefix('#') {
+            dec.parse::<u32>().ok().and_then(char::from_u32)
+        } else {
+            match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                "ndash" => Some('–'),
+                "mdash" => Some('—'),
+                "hellip" => Some('…'),
+                "lsquo" => Some('‘'),
+                "rsquo" => Some('’'),
+                "ldquo" => Some('“'),
+                "rdquo" => Some('”'),
+                "copy" => Some('©'),
+                _ => None,
+            }
+        };
+        decoded.map(|c| c.to_string()).unwrap_or_else(|| caps[0].to_string())
+    }).to_string()
+}
+
+/// Extract the readable part of an HTML document as Markdown.
+///
+/// This is a lightweight readability pass: it drops scripts and page chrome,
+/// prefers the <article>/<main> region, and converts headings, links,
+/// emphasis, lists, quotes and code to Markdown. Relative links are resolved
+/// against `base_url` when given.
+pub fn extract_readable(html: &str, base_url: Option<&reqwest::Url>) -> ReadablePage {
+    const PRE_PLACEHOLDER: &str = "\x00PRE";
+
+    let title_re = Regex::new(r"(?is)<title\b[^>]*>(.*?)</title\s*>").unwrap();
+    let title = title_re.captures(html)
+        .map(|caps| collapse_inline(&decode_entities(&strip_tags(&caps[1]))))
+        .filter(|t| !t.is_empty());
+
+    let comment_re = Regex::new(r"(?s)<!--.*?-->").unwrap();
+    let cleaned = comment_re.replace_all(html, "").to_string();
+    let cleaned = remove_elements(&cleaned, NOISE_TAGS);
+
+    let (region, is_article) = select_content_region(&cleaned);
+    let mut region = remove_elements(&region, BOILERPLATE_TAGS);
+    if !is_article {
+        region = remove_elements(&region, &["header"]);
+    }
+
+    // 1. Protect preformatted blocks from whitespace collapsing
+    let mut pres: Vec<String> = Vec::new();
+    let pre_re = Regex::new(r"(?is)<pre\b[^>]*>(.*?)</pre\s*>").unwrap();
+    let result = pre_re.replace_all(&region, |caps: &Captures| {
+        let code = decode_entities(&strip_tags(&caps[1]));
+        pres.push(format!("```\n{}\n```", code.trim_matches('\n')));
+        format!("\n\n{}{}\x00\n\n", PRE_PLACEHOLDER, pres.len() - 1)
+    }).to_string();
+
+    // 2. Headings
+    let mut result = result;
+    for level in 1..=6 {
+        let re = Regex::new(&format!(r"(?is)<h{level}\b[^>]*>(.*?)</h{level}\s*>", level = level)).unwrap();
+        result = re.replace_all(&result, |caps: &Captures| {
+            // Inline markup inside headings is converted by the later steps
+            let text = collapse_inline(&caps[1]);
+            if strip_tags(&text).trim().is_empty() {
+                String::new()
+            } else {
+                format!("\n\n{} {}\n\n", "#".repeat(level), text)
+            }
+        }).to_string();
+    }
+
+    // 3. Links
+    let link_re = Regex::new(r#"(?is)<a\b[^>]*?href\s*=\s*["']([^"']*)["'][^>]*>(.*?)</a\s*>"#).unwrap();
+    let result = link_re.replace_all(&result, |caps: &Captures| {
+        let text = collapse_inline(&strip_tags(&caps[2]));
+        let href = decode_entities(caps[1].trim());
+        if text.is_empty() {
+            return String::new();
+        }
+        if href.is_empty() || href.starts_with('#') || href.to_lowercase().starts_with("javascript:") {
+            return text;
+        }
+        let resolved = base_url
+            .and_then(|base| base.join(&href).ok())
+            .map(|u| u.to_string())
+            .unwrap_or(href);
+        format!("[{}]({})", text, resolved)
+    }).to_string();
+
+    // 4. Inline formatting
+    let strong_re = Regex::new(r"(?is)<(strong|b)\b[^>]*>(.*?)</(?:strong|b)\s*>").unwrap();
+    let result = strong_re.replace_all(&result, |caps: &Captures| {
+        let text = collapse_inline(&caps[2]);
+        if text.is_empty() { String::new() } else { format!("**{}**", text) }
+    }).to_string();
+    let em_re = Regex::new(r"(?is)<(em|i)\b[^>]*>(.*?)</(?:em|i)\s*>").unwrap();
+    let result = em_re.replace_all(&result, |caps: &Captures| {
+        let text = collapse_inline(&caps[2]);
+        if text.is_empty() { String::new() } else { format!("*{}*", text) }
+    }).to_string();
+    let code_re = Regex::new(r"(?is)<code\b[^>]*>(.*?)</code\s*>").unwrap();
+    let result = code_re.replace_all(&result, |caps: &Captures| {
+        format!("`{}`", collapse_inline(&strip_tags(&caps[1])))
+    }).to_string();
+
+    // 5. Block quotes
+    let quote_re = Regex::new(r"(?is)<blockquote\b[^>]*>(.*?)</blockquote\s*>").unwrap();
+    let result = quote_re.replace_all(&result, |caps: &Captures| {
+        let text = collapse_inline(&strip_tags(&caps[1]));
+        format!("\n\n> {}\n\n", text)
+    }).to_string();
+
+    // 6. Lists, line breaks and block boundaries
+    let li_re = Regex::new(r"(?i)<li\b[^>]*>").unwrap();
+    let result = li_re.replace_all(&result, "\n- ").to_string();
+    let br_re = Regex::new(r"(?i)<br\s*/?>").unwrap();
+    let result = br_re.replace_all(&result, "\n").to_string();
+    let block_re = Regex::new(
+        r"(?i)</?(p|div|section|ul|ol|table|tr|figure|figcaption|dl|dt|dd|header|article|main|li)\b[^>]*>",
+    ).unwrap();
+    let result = block_re.replace_all(&result, "\n\n").to_string();
+    let cell_re = Regex::new(r"(?i)</t[dh]\s*>").unwrap();
+    let result = cell_re.replace_all(&result, " ").to_string();
+
+    // 7. Drop remaining tags, decode entities, tidy whitespace
+    let result = decode_entities(&strip_tags(&result));
+    let mut lines: Vec<String> = Vec::new();
+    for line in result.lines() {
+        let line = collapse_inline(line);
+        if line.is_empty() {
+            if lines.last().is_some_and(|l| !l.is_empty()) {
+                lines.push(String::new());
+            }
+        } else if line == "-" {
+            continue;
+        } else {
+            lines.push(line);
+        }
+    }
+    // Keep list items tight
+    let mut markdown = String::new();
+    for (idx, line) in lines.iter().enumerate() {
+        if line.is_empty() {
+            let prev_item = idx > 0 && lines[idx - 1].starts_with("- ");
+            let next_item = lines.get(idx + 1).is_some_and(|l| l.starts_with("- "));
+            if prev_item && next_item {
+                continue;
+            }
+        }
+        markdown.push_str(line);
+        markdown.push('\n');
+    }
+    let mut markdown = markdown.trim().to_string();
+
+    // 8. Restore preformatted blocks
+    for (i, pre) in pres.iter().enumerate() {
+        markdown = markdown.replace(&format!("{}{}\x00", PRE_PLACEHOLDER, i), pre);
+    }
+
+    ReadablePage { title, markdown }
+}
+
+/// Strip Markdown markup, leaving plain text.
+pub fn markdown_to_plain_text(markdown: &str) -> String {
+    let fence_re = Regex::new(r"(?m)^```.*$\n?").unwrap();
+    let result = fence_re.replace_all(markdown, "").to_string();
+    let link_re = Regex::new(r"\[([^\]]*)\]\([^)]*\)").unwrap();
+    let result = link_re.replace_all(&result, "$1").to_string();
+    let header_re = Regex::new(r"(?m)^#{1,6}\s+").unwrap();
+    let result = header_re.replace_all(&result, "").to_string();
+    let quote_re = Regex::new(r"(?m)^>\s?").unwrap();
+    let result = quote_re.replace_all(&result, "").to_string();
+    let bold_re = Regex::new(r"\*\*(.+?)\*\*").unwrap();
+    let result = bold_re.replace_all(&result, "$1").to_string();
+    let em_re = Regex::new(r"\*([^*\n]+)\*").unwrap();
+    let result = em_re.replace_all(&result, "$1").to_string();
+    let code_re = Regex::new(r"`([^`\n]*)`").unwrap();
+    code_re.replace_all(&result, "$1").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTICLE: &str = r#"<!DOCTYPE html>
+<html>
+<head><title>Rust &amp; Safety</title><style>body { color: red; }</style></head>
+<body>
+  <nav><a href="/">Home</a> <a href="/about">About</a></nav>
+  <article>
+    <h1>Why <em>Rust</em>?</h1>
+    <p>Rust is <strong>fast</strong> and   memory-safe.
+       See the <a href="/docs/book">book</a>.</p>
+    <ul><li>No GC</li><li>Zero-cost abstractions</li></ul>
+    <pre><code>fn main() {
+    println!("hi");
+}</code></pre>
+    <blockquote>Fearless concurrency.</blockquote>
+    <script>track();</script>
+  </article>
+  <footer>© 2026 Example</footer>
+</body>
+</html>"#;
+
+    #[test]
+    fn extracts_title_and_article() {
+        let base = reqwest::Url::parse("https://example.com/posts/rust").unwrap();
+        let page = extract_readable(ARTICLE, Some(&base));
+        assert_eq!(page.title.as_deref(), Some("Rust & Safety"));
+        assert!(page.markdown.starts_with("# Why *Rust*?"));
+        assert!(page.markdown.contains("Rust is **fast** and memory-safe."));
+        assert!(page.markdown.contains("[book](https://example.com/docs/book)"));
+        assert!(page.markdown.contains("- No GC\n- Zero-cost abstractions"));
+        assert!(page.markdown.contains("> Fearless concurrency."));
+    }
+
+    #[test]
+    fn preserves_preformatted_code() {
+        let page = extract_readable(ARTICLE, None);
+        assert!(page.markdown.contains("```\nfn main() {\n    println!(\"hi\");\n}\n```"));
+    }
+
+    #[test]
+    fn drops_scripts_and_chrome() {
+        let page = extract_readable(ARTICLE, None);
+        assert!(!page.markdown.contains("track()"));
+        assert!(!page.markdown.contains("color: red"));
+        assert!(!page.markdown.contains("About"));
+        assert!(!page.markdown.contains("2026 Example"));
+    }
+
+    #[test]
+    fn falls_back_to_body() {
+        let html = "<html><body><header>Site</header><p>Hello <b>world</b></p></body></html>";
+        let page = extract_readable(html, None);
+        assert_eq!(page.title, None);
+        assert_eq!(page.markdown, "Hello **world**");
+    }
+
+    #[test]
+    fn decodes_numeric_entities() {
+        assert_eq!(decode_entities("&#65;&#x42;&lt;&unknown;"), "AB<&unknown;");
+    }
+
+    #[test]
+    fn plain_text_strips_markup() {
+        let md = "# Title\n\nSome **bold** and *em* with a [link](https://x.y) and `code`.\n\n> quote";
+        assert_eq!(
+            markdown_to_plain_text(md),
+            "Title\n\nSome bold and em with a link and code.\n\nquote"
+        );
+    }
+}
@@ -1 +1,2 @@
 pub mod whatsapp;
+pub mod html;
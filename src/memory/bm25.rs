@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+/// Term-frequency saturation.
+const K1: f64 = 1.2;
+/// Document-length normalization.
+const B: f64 = 0.75;
+
+/// Words too common to carry signal.
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "did", "do", "for", "from", "had",
+    "has", "have", "he", "her", "his", "i", "if", "in", "into", "is", "it", "its", "me", "my",
+    "of", "on", "or", "our", "she", "so", "that", "the", "their", "them", "then", "there",
+    "they", "this", "to", "was", "we", "were", "what", "when", "where", "which", "who", "will",
+    "with", "you", "your",
+];
+
+/// Lowercase alphanumeric tokens with stopwords removed.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty() && !STOPWORDS.contains(t))
+        .map(|t| t.to_string())
+        .collect()
+}
+
+/// Inverted index with Okapi BM25 scoring.
+#[derive(Debug, Clone, Default)]
+pub struct Bm25Index {
+    /// term → (document, term frequency)
+    postings: HashMap<String, Vec<(usize, u32)>>,
+    doc_lengths: Vec<usize>,
+    avg_doc_length: f64,
+}
+
+impl Bm25Index {
+    /// Index documents; results refer to them by position.
+    pub fn build<S: AsRef<str>>(docs: &[S]) -> Self {
+        let mut postings: HashMap<String, Vec<(usize, u32)>> = HashMap::new();
+        let mut doc_lengths = Vec::with_capacity(docs.len());
+
+        for (doc_id, doc) in docs.iter().enumerate() {
+            let tokens = tokenize(doc.as_ref());
+            doc_lengths.push(tokens.len());
+            let mut counts: HashMap<String, u32> = HashMap::new();
+            for token in tokens {
+                *counts.entry(token).or_default() += 1;
+            }
+            for (term, tf) in counts {
+                postings.entry(term).or_default().push((doc_id, tf));
+            }
+        }
+
+        let total: usize = doc_lengths.iter().sum();
+        let avg_doc_length = if doc_lengths.is_empty() { 0.0 } else { total as f64 / doc_lengths.len() as f64 };
+        Self { postings, doc_lengths, avg_doc_length }
+    }
+
+    pub fn len(&self) -> usize {
+        self.doc_lengths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.doc_lengths.is_empty()
+    }
+
+    /// Score documents against a query, best first.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<(usize, f64)> {
+        let n = self.doc_lengths.len() as f64;
+        let mut terms = tokenize(query);
+        terms.sort();
+        terms.dedup();
+
+        let mut scores: HashMap<usize, f64> = HashMap::new();
+        for term in &terms {
+            let Some(postings) = self.postings.get(term) else {
+                continue;
+            };
+            let df = postings.len() as f64;
+            let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+            for &(doc, tf) in postings {
+                let tf = tf as f64;
+                let len_norm = 1.0 - B + B * self.doc_lengths[doc] as f64 / self.avg_doc_length.max(1.0);
+                *scores.entry(doc).or_default() += idf * tf * (K1 + 1.0) / (tf + K1 * len_norm);
+            }
+        }
+
+        let mut ranked: Vec<(usize, f64)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then(a.0.cmp(&b.0)));
+        ranked.truncate(limit);
+        ranked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenize_drops_stopwords_and_punctuation() {
+        assert_eq!(tokenize("The car's oil-change is due!"), vec!["car", "s", "oil", "change", "due"]);
+    }
+
+    #[test]
+    fn rare_terms_outrank_common_ones() {
+        let docs = [
+            "meeting notes for the project",
+            "meeting with the dentist about a crown",
+            "project meeting agenda",
+        ];
+        let index = Bm25Index::build(&docs);
+        let ranked = index.search("dentist meeting", 10);
+        assert_eq!(ranked[0].0, 1);
+        assert_eq!(ranked.len(), 3);
+    }
+
+    #[test]
+    fn shorter_documents_score_higher_for_same_tf() {
+        let docs = [
+            "passport renewal",
+            "passport renewal form and many other unrelated words about weekend plans",
+        ];
+        let index = Bm25Index::build(&docs);
+        let ranked = index.search("passport", 10);
+        assert_eq!(ranked[0].0, 0);
+        assert!(ranked[0].1 > ranked[1].1);
+    }
+
+    #[test]
+    fn no_matches_or_empty_index() {
+        let index = Bm25Index::build(&["alpha beta"]);
+        assert!(index.search("gamma", 10).is_empty());
+        assert!(index.search("the", 10).is_empty());
+        assert!(Bm25Index::build::<&str>(&[]).search("alpha", 10).is_empty());
+    }
+}
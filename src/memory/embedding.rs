@@ -0,0 +1,334 @@
+use crate::config::types::MemoryConfig;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::debug;
+
+const OPENAI_EMBEDDINGS_URL: &str = "https://api.openai.com/v1";
+const DEFAULT_OPENAI_MODEL: &str = "text-embedding-3-small";
+const DEFAULT_HASH_DIMENSIONS: usize = 256;
+/// Inputs per embeddings request.
+const EMBED_BATCH_SIZE: usize = 64;
+
+#[derive(Debug, thiserror::Error)]
+pub enum EmbeddingError {
+    #[error("Embedding API error: {status} - {message}")]
+    Api { status: u16, message: String },
+    #[error("Embedding request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Invalid embedding response: {0}")]
+    InvalidResponse(String),
+    #[error("Embeddings not configured: {0}")]
+    NotConfigured(String),
+}
+
+/// Turns text into vectors for similarity search.
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    /// Provider name, e.g. "openai".
+    fn name(&self) -> &str;
+
+    /// Model identifier; a change invalidates existing indexes.
+    fn model(&self) -> &str;
+
+    /// Embed a batch of texts, one vector per input in the same order.
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError>;
+}
+
+/// Cosine similarity of two vectors (0.0 for empty or mismatched input).
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let mut dot = 0.0f32;
+    let mut norm_a = 0.0f32;
+    let mut norm_b = 0.0f32;
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+// ── OpenAI-compatible ──
+
+/// Any `/embeddings` endpoint speaking the OpenAI request/response format
+/// (OpenAI, Azure-style proxies, Ollama, LM Studio, vLLM, ...).
+pub struct OpenAiEmbeddings {
+    client: Client,
+    api_key: Option<String>,
+    base_url: String,
+    model: String,
+}
+
+impl OpenAiEmbeddings {
+    pub fn new(api_key: Option<String>, model: String) -> Self {
+        Self {
+            client: Client::new(),
+            api_key,
+            base_url: OPENAI_EMBEDDINGS_URL.to_string(),
+            model,
+        }
+    }
+
+    pub fn with_base_url(mut self, url: String) -> Self {
+        self.base_url = url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Resolve API key from environment.
+    pub fn api_key_from_env() -> Option<String> {
+        std::env::var("OPENAI_API_KEY").ok().filter(|k| !k.is_empty())
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        let mut request = self.client.post(format!("{}/embeddings", self.base_url))
+            .json(&serde_json::json!({ "model": self.model, "input": texts }));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(EmbeddingError::Api {
+                status: status.as_u16(),
+                message: message.chars().take(200).collect(),
+            });
+        }
+
+        let body: Value = response.json().await
+            .map_err(|e| EmbeddingError::InvalidResponse(e.to_string()))?;
+        let data = body.get("data")
+            .and_then(|d| d.as_array())
+            .ok_or_else(|| EmbeddingError::InvalidResponse("missing data array".into()))?;
+
+        let mut vectors: Vec<Option<Vec<f32>>> = vec![None; texts.len()];
+        for (position, item) in data.iter().enumerate() {
+            let index = item.get("index").and_then(|i| i.as_u64()).map(|i| i as usize).unwrap_or(position);
+            let embedding = item.get("embedding")
+                .and_then(|e| e.as_array())
+                .ok_or_else(|| EmbeddingError::InvalidResponse("missing embedding".into()))?
+                .iter()
+                .map(|v| v.as_f64().unwrap_or(0.0) as f32)
+                .collect();
+            if let Some(slot) = vectors.get_mut(index) {
+                *slot = Some(embedding);
+            }
+        }
+        vectors.into_iter()
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| EmbeddingError::InvalidResponse(format!("expected {} embeddings", texts.len())))
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAiEmbeddings {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(EMBED_BATCH_SIZE) {
+            debug!("Embedding {} texts with {}", batch.len(), self.model);
+            vectors.extend(self.embed_batch(batch).await?);
+        }
+        Ok(vectors)
+    }
+}
+
+// ── Local hashing ──
+
+/// Deterministic, offline embedder using signed feature hashing of words and
+/// word bigrams. Captures lexical overlap only, but needs no network, which
+/// makes it suitable for tests and air-gapped setups.
+pub struct HashingEmbedder {
+    dimensions: usize,
+    model: String,
+}
+
+impl HashingEmbedder {
+    pub fn new(dimensions: usize) -> Self {
+        let dimensions = dimensions.max(8);
+        Self { dimensions, model: format!("hash-{}", dimensions) }
+    }
+
+    /// FNV-1a, stable across platforms and runs (unlike `DefaultHasher`).
+    fn hash(token: &str) -> u64 {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in token.as_bytes() {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        hash
+    }
+
+    pub fn embed_one(&self, text: &str) -> Vec<f32> {
+        let lower = text.to_lowercase();
+        let words: Vec<&str> = lower
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        let mut vector = vec![0.0f32; self.dimensions];
+        let mut add = |feature: &str, weight: f32| {
+            let h = Self::hash(feature);
+            let idx = (h % self.dimensions as u64) as usize;
+            let sign = if (h >> 63) == 0 { 1.0 } else { -1.0 };
+            vector[idx] += sign * weight;
+        };
+        for word in &words {
+            add(word, 1.0);
+        }
+        for pair in words.windows(2) {
+            add(&format!("{} {}", pair[0], pair[1]), 0.5);
+        }
+
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            for v in &mut vector {
+                *v /= norm;
+            }
+        }
+        vector
+    }
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self::new(DEFAULT_HASH_DIMENSIONS)
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for HashingEmbedder {
+    fn name(&self) -> &str {
+        "hash"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        Ok(texts.iter().map(|t| self.embed_one(t)).collect())
+    }
+}
+
+/// Build the embedding provider selected by the top-level `memory` config.
+/// Returns `Ok(None)` when no provider is configured.
+pub fn provider_from_config(config: &MemoryConfig) -> Result<Option<Arc<dyn EmbeddingProvider>>, EmbeddingError> {
+    if config.enabled == Some(false) {
+        return Ok(None);
+    }
+    let Some(provider) = config.provider.as_deref() else {
+        return Ok(None);
+    };
+    match provider.to_lowercase().as_str() {
+        "openai" => {
+            let api_key = config.api_key.clone()
+                .filter(|k| !k.is_empty())
+                .or_else(OpenAiEmbeddings::api_key_from_env);
+            if api_key.is_none() && config.base_url.is_none() {
+                return Err(EmbeddingError::NotConfigured(
+                    "set memory.apiKey, OPENAI_API_KEY or memory.baseUrl".into(),
+                ));
+            }
+            let model = config.embedding_model.clone().unwrap_or_else(|| DEFAULT_OPENAI_MODEL.to_string());
+            let mut embeddings = OpenAiEmbeddings::new(api_key, model);
+            if let Some(url) = &config.base_url {
+                embeddings = embeddings.with_base_url(url.clone());
+            }
+            Ok(Some(Arc::new(embeddings)))
+        }
+        "hash" | "local" => Ok(Some(Arc::new(HashingEmbedder::new(
+            config.dimensions.unwrap_or(DEFAULT_HASH_DIMENSIONS),
+        )))),
+        "none" | "" => Ok(None),
+        other => Err(EmbeddingError::NotConfigured(format!("unknown embedding provider '{}'", other))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::post;
+    use axum::{Json, Router};
+
+    #[test]
+    fn cosine_basics() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[1.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 2.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 1.0]), 0.0);
+    }
+
+    #[tokio::test]
+    async fn hashing_is_deterministic_and_lexical() {
+        let embedder = HashingEmbedder::new(128);
+        let texts = vec![
+            "The dentist appointment is on Friday".to_string(),
+            "dentist appointment friday".to_string(),
+            "Quarterly tax filing deadline".to_string(),
+        ];
+        let a = embedder.embed(&texts).await.unwrap();
+        let b = embedder.embed(&texts).await.unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a[0].len(), 128);
+        assert!(cosine_similarity(&a[0], &a[1]) > cosine_similarity(&a[0], &a[2]));
+    }
+
+    #[tokio::test]
+    async fn openai_compatible_endpoint() {
+        let app = Router::new().route("/v1/embeddings", post(|Json(body): Json<Value>| async move {
+            assert_eq!(body["model"], "test-embed");
+            let inputs = body["input"].as_array().unwrap().clone();
+            // Answer out of order to check the index field is honored
+            let data: Vec<Value> = inputs.iter().enumerate().rev().map(|(i, text)| {
+                let len = text.as_str().unwrap().len() as f64;
+                serde_json::json!({ "index": i, "embedding": [len, 1.0] })
+            }).collect();
+            Json(serde_json::json!({ "data": data }))
+        }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let provider = provider_from_config(&MemoryConfig {
+            provider: Some("openai".into()),
+            embedding_model: Some("test-embed".into()),
+            base_url: Some(format!("http://{}/v1/", addr)),
+            ..Default::default()
+        }).unwrap().unwrap();
+        assert_eq!(provider.model(), "test-embed");
+        let vectors = provider.embed(&["a".into(), "abc".into()]).await.unwrap();
+        assert_eq!(vectors, vec![vec![1.0, 1.0], vec![3.0, 1.0]]);
+    }
+
+    #[test]
+    fn provider_selection() {
+        assert!(provider_from_config(&MemoryConfig::default()).unwrap().is_none());
+        let hash = provider_from_config(&MemoryConfig {
+            provider: Some("hash".into()),
+            dimensions: Some(64),
+            ..Default::default()
+        }).unwrap().unwrap();
+        assert_eq!(hash.model(), "hash-64");
+        assert!(provider_from_config(&MemoryConfig {
+            provider: Some("mystery".into()),
+            ..Default::default()
+        }).is_err());
+    }
+}
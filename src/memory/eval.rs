@@ -0,0 +1,188 @@
+//! Retrieval evaluation harness: run fixture queries through a search
+//! function and report recall@k and mean reciprocal rank, so ranking changes
+//! can be compared before and after.
+
+use super::SearchResult;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::Path;
+
+/// A query and the memory files that should answer it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalCase {
+    pub query: String,
+    /// Workspace-relative paths considered relevant.
+    pub expected: Vec<String>,
+}
+
+/// A fixture workspace plus its queries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalFixture {
+    /// Workspace-relative path → file content.
+    pub files: BTreeMap<String, String>,
+    pub cases: Vec<EvalCase>,
+}
+
+impl EvalFixture {
+    pub fn from_json(raw: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(raw)
+    }
+
+    /// Write the fixture files into a workspace directory.
+    pub fn materialize(&self, workspace: &Path) -> std::io::Result<()> {
+        for (rel_path, content) in &self.files {
+            let path = workspace.join(rel_path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, content)?;
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of one query.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaseResult {
+    pub query: String,
+    /// 1-based rank of the first relevant result within the top k.
+    pub first_hit: Option<usize>,
+    /// Fraction of expected files found in the top k.
+    pub recall: f64,
+    pub returned: Vec<String>,
+}
+
+/// Aggregate metrics over all cases.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvalReport {
+    pub k: usize,
+    pub recall_at_k: f64,
+    pub mrr: f64,
+    pub cases: Vec<CaseResult>,
+}
+
+impl EvalReport {
+    /// One line of metrics followed by the misses.
+    pub fn summary(&self) -> String {
+        let mut out = format!(
+            "recall@{}: {:.3}  MRR: {:.3}  ({} queries)",
+            self.k, self.recall_at_k, self.mrr, self.cases.len(),
+        );
+        for case in self.cases.iter().filter(|c| c.first_hit.is_none()) {
+            out.push_str(&format!("\n  miss: \"{}\" → {:?}", case.query, case.returned));
+        }
+        out
+    }
+}
+
+/// Run every case through `search` and score the top `k` results.
+pub async fn evaluate<F, Fut>(cases: &[EvalCase], k: usize, mut search: F) -> EvalReport
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Vec<SearchResult>>,
+{
+    let mut results = Vec::with_capacity(cases.len());
+    for case in cases {
+        let hits = search(case.query.clone()).await;
+        let mut returned: Vec<String> = Vec::new();
+        for hit in hits {
+            if !returned.contains(&hit.file_path) {
+                returned.push(hit.file_path);
+            }
+        }
+        returned.truncate(k);
+
+        let first_hit = returned.iter()
+            .position(|path| case.expected.contains(path))
+            .map(|idx| idx + 1);
+        let found = case.expected.iter().filter(|e| returned.contains(e)).count();
+        let recall = if case.expected.is_empty() { 1.0 } else { found as f64 / case.expected.len() as f64 };
+        results.push(CaseResult { query: case.query.clone(), first_hit, recall, returned });
+    }
+
+    let n = results.len().max(1) as f64;
+    EvalReport {
+        k,
+        recall_at_k: results.iter().map(|c| c.recall).sum::<f64>() / n,
+        mrr: results.iter().map(|c| c.first_hit.map(|r| 1.0 / r as f64).unwrap_or(0.0)).sum::<f64>() / n,
+        cases: results,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::embedding::HashingEmbedder;
+    use crate::memory::{search_memory_with, MemoryIndex, MemorySearchOptions, QueryOptions};
+    use std::sync::Arc;
+
+    const K: usize = 3;
+
+    fn fixture() -> (tempfile::TempDir, EvalFixture) {
+        let fixture = EvalFixture::from_json(include_str!("fixtures/eval.json")).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        fixture.materialize(dir.path()).unwrap();
+        (dir, fixture)
+    }
+
+    #[tokio::test]
+    async fn metrics_are_computed() {
+        let cases = vec![
+            EvalCase { query: "a".into(), expected: vec!["x.md".into()] },
+            EvalCase { query: "b".into(), expected: vec!["y.md".into()] },
+        ];
+        let report = evaluate(&cases, 2, |q| async move {
+            let path = if q == "a" { "z.md" } else { "w.md" };
+            vec![
+                SearchResult { file_path: path.into(), content: String::new(), score: 1.0, line_number: None, end_line: None },
+                SearchResult { file_path: "x.md".into(), content: String::new(), score: 0.5, line_number: None, end_line: None },
+            ]
+        }).await;
+        assert_eq!(report.recall_at_k, 0.5);
+        assert_eq!(report.mrr, 0.25);
+        assert_eq!(report.cases[0].first_hit, Some(2));
+        assert!(report.summary().contains("miss: \"b\""));
+    }
+
+    #[tokio::test]
+    async fn bm25_baseline() {
+        let (dir, fixture) = fixture();
+        let ws = dir.path().to_str().unwrap().to_string();
+        let options = MemorySearchOptions::default();
+        let report = evaluate(&fixture.cases, K, |q| {
+            let hits = search_memory_with(&ws, &q, K * 3, &options);
+            async move { hits }
+        }).await;
+        assert!(report.recall_at_k >= 0.9, "{}", report.summary());
+    }
+
+    #[tokio::test]
+    async fn vector_and_hybrid() {
+        let (dir, fixture) = fixture();
+        let ws = dir.path().to_str().unwrap();
+
+        let vector = MemoryIndex::new(ws, MemorySearchOptions::default(), Arc::new(HashingEmbedder::default()));
+        vector.sync().await.unwrap();
+        let vector_report = evaluate(&fixture.cases, K, |q| {
+            let index = vector.clone();
+            async move { index.query(&q, K * 3).await.unwrap() }
+        }).await;
+
+        let hybrid_options = MemorySearchOptions {
+            query: QueryOptions { hybrid: true, ..Default::default() },
+            ..Default::default()
+        };
+        let hybrid = MemoryIndex::new(ws, hybrid_options, Arc::new(HashingEmbedder::default()));
+        hybrid.sync().await.unwrap();
+        let hybrid_report = evaluate(&fixture.cases, K, |q| {
+            let index = hybrid.clone();
+            async move { index.query(&q, K * 3).await.unwrap() }
+        }).await;
+
+        assert!(hybrid_report.recall_at_k >= 0.9, "{}", hybrid_report.summary());
+        assert!(hybrid_report.recall_at_k >= vector_report.recall_at_k, "{} vs {}", hybrid_report.summary(), vector_report.summary());
+    }
+}
@@ -0,0 +1,182 @@
+//! End-of-session flush: before a session is compacted or evicted, ask the
+//! model to pull out durable facts and append them to today's note.
+
+use super::journal::{AppendOutcome, EntryKind, MemoryEntry, MemoryJournal};
+use super::MemoryError;
+use crate::provider::types::{CompletionRequest, Message, MessageContent, MessageRole, Provider};
+use crate::session::Session;
+
+/// Transcript budget sent to the model; older turns are dropped first.
+const MAX_TRANSCRIPT_CHARS: usize = 24_000;
+const MAX_FLUSH_TOKENS: u32 = 1024;
+
+const FLUSH_PROMPT: &str = "You maintain a long-term memory journal for an assistant. \
+Read the conversation and list only durable information worth remembering in future \
+conversations: facts about the user and their world, preferences, decisions, and open todos. \
+Skip small talk, one-off requests and anything already resolved.\n\n\
+Reply with one entry per line in the form `- [kind] text #tag`, where kind is one of \
+fact, preference, decision, todo or note, and tags are optional. \
+If there is nothing worth keeping, reply with NONE.";
+
+/// Plain-text transcript of the session's user and assistant turns, keeping
+/// the most recent ones within `max_chars`.
+pub fn session_transcript(session: &Session, max_chars: usize) -> String {
+    let mut turns: Vec<String> = Vec::new();
+    let mut total = 0;
+    for msg in session.messages.iter().rev() {
+        let speaker = match msg.role {
+            MessageRole::User => "User",
+            MessageRole::Assistant => "Assistant",
+            _ => continue,
+        };
+        let text = msg.content.to_text();
+        if text.trim().is_empty() {
+            continue;
+        }
+        let turn = format!("{}: {}", speaker, text.trim());
+        total += turn.len() + 2;
+        if total > max_chars && !turns.is_empty() {
+            break;
+        }
+        turns.push(turn);
+    }
+    turns.reverse();
+    turns.join("\n\n")
+}
+
+/// Parse the model's reply into entries; lines that don't look like list
+/// items are ignored.
+pub fn parse_flush_reply(reply: &str) -> Vec<MemoryEntry> {
+    let mut entries = Vec::new();
+    for line in reply.lines() {
+        let line = line.trim();
+        let Some(item) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) else {
+            continue;
+        };
+        let mut item = item.trim();
+        let mut kind = EntryKind::Note;
+        if let Some(rest) = item.strip_prefix('[') {
+            if let Some((label, text)) = rest.split_once(']') {
+                kind = EntryKind::parse(label).unwrap_or(EntryKind::Note);
+                item = text.trim();
+            }
+        }
+
+        let mut words: Vec<&str> = item.split_whitespace().collect();
+        let mut tags = Vec::new();
+        while let Some(last) = words.last() {
+            match last.strip_prefix('#') {
+                Some(tag) if !tag.is_empty() => {
+                    tags.insert(0, tag.to_string());
+                    words.pop();
+                }
+                _ => break,
+            }
+        }
+        let text = words.join(" ");
+        if !text.is_empty() {
+            entries.push(MemoryEntry::new(kind, &text).with_tags(tags));
+        }
+    }
+    entries
+}
+
+/// Summarize the session into today's note. Sessions without user turns are
+/// skipped without calling the model.
+pub async fn flush_session(
+    session: &Session,
+    provider: &dyn Provider,
+    model: &str,
+    journal: &MemoryJournal,
+) -> Result<Option<AppendOutcome>, MemoryError> {
+    if !session.messages.iter().any(|m| m.role == MessageRole::User) {
+        return Ok(None);
+    }
+    let request = CompletionRequest {
+        model: model.to_string(),
+        system: Some(FLUSH_PROMPT.to_string()),
+        messages: vec![Message {
+            role: MessageRole::User,
+            content: MessageContent::Text(format!(
+                "<conversation>\n{}\n</conversation>",
+                session_transcript(session, MAX_TRANSCRIPT_CHARS),
+            )),
+        }],
+        max_tokens: MAX_FLUSH_TOKENS,
+        temperature: Some(0.0),
+        ..Default::default()
+    };
+    let response = provider.complete(&request).await?;
+    let reply = MessageContent::Blocks(response.content).to_text();
+    let entries = parse_flush_reply(&reply);
+    if entries.is_empty() {
+        return Ok(None);
+    }
+    journal.append(&entries).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock::ScriptedProvider;
+
+    #[test]
+    fn parses_reply_lines() {
+        let entries = parse_flush_reply(
+            "Here you go:\n- [fact] Daughter Mia starts school in September #family\n* [pref] Likes window seats\n- plain note\n- [fact] #orphan",
+        );
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].kind, EntryKind::Fact);
+        assert_eq!(entries[0].text, "Daughter Mia starts school in September");
+        assert_eq!(entries[0].tags, vec!["family"]);
+        assert_eq!(entries[1].kind, EntryKind::Preference);
+        assert_eq!(entries[2].kind, EntryKind::Note);
+        assert!(parse_flush_reply("NONE").is_empty());
+    }
+
+    #[test]
+    fn transcript_keeps_latest_turns() {
+        let mut session = Session::new("k", "main", "cli");
+        session.add_user_message(&"old ".repeat(50));
+        session.add_assistant_message("ok");
+        session.add_user_message("latest question");
+        let transcript = session_transcript(&session, 60);
+        assert!(transcript.starts_with("Assistant: ok"));
+        assert!(transcript.ends_with("User: latest question"));
+    }
+
+    #[tokio::test]
+    async fn flush_writes_todays_note() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = MemoryJournal::new(dir.path().to_str().unwrap());
+        let mut session = Session::new("k", "main", "cli");
+        session.add_user_message("I'm allergic to peanuts, remember that");
+        session.add_assistant_message("Noted!");
+
+        let provider = ScriptedProvider::text("- [fact] Allergic to peanuts #health");
+        let outcome = flush_session(&session, &provider, "test-model", &journal).await.unwrap().unwrap();
+        assert_eq!(outcome.written, 1);
+        let note = std::fs::read_to_string(&outcome.path).unwrap();
+        assert!(note.contains("[fact] Allergic to peanuts #health"));
+
+        let request = provider.requests.lock().unwrap()[0].clone();
+        assert_eq!(request.model, "test-model");
+        assert!(request.messages[0].content.to_text().contains("allergic to peanuts"));
+    }
+
+    #[tokio::test]
+    async fn nothing_to_flush() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = MemoryJournal::new(dir.path().to_str().unwrap());
+        let provider = ScriptedProvider::text("NONE");
+
+        let empty = Session::new("k", "main", "cli");
+        assert!(flush_session(&empty, &provider, "m", &journal).await.unwrap().is_none());
+        assert_eq!(provider.request_count(), 0);
+
+        let mut session = Session::new("k", "main", "cli");
+        session.add_user_message("thanks");
+        assert!(flush_session(&session, &provider, "m", &journal).await.unwrap().is_none());
+        assert!(!dir.path().join("memory").exists());
+    }
+}
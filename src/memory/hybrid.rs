@@ -0,0 +1,216 @@
+use super::SearchResult;
+use crate::config::types::MemoryQueryConfig;
+use chrono::NaiveDate;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Ranking settings (`memorySearch.query`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryOptions {
+    /// Fuse BM25 and vector rankings when an embedding index is available.
+    pub hybrid: bool,
+    pub rrf_k: f64,
+    pub vector_weight: f64,
+    pub text_weight: f64,
+    pub recency_weight: f64,
+    pub recency_half_life_days: f64,
+}
+
+impl Default for QueryOptions {
+    fn default() -> Self {
+        Self {
+            hybrid: false,
+            rrf_k: 60.0,
+            vector_weight: 1.0,
+            text_weight: 1.0,
+            recency_weight: 0.2,
+            recency_half_life_days: 30.0,
+        }
+    }
+}
+
+impl QueryOptions {
+    pub fn from_config(config: &MemoryQueryConfig) -> Self {
+        let defaults = Self::default();
+        let hybrid = config.hybrid.clone().unwrap_or_default();
+        Self {
+            hybrid: hybrid.enabled.unwrap_or(false),
+            rrf_k: hybrid.rrf_k.filter(|k| *k > 0.0).unwrap_or(defaults.rrf_k),
+            vector_weight: hybrid.vector_weight.unwrap_or(defaults.vector_weight).max(0.0),
+            text_weight: hybrid.text_weight.unwrap_or(defaults.text_weight).max(0.0),
+            recency_weight: config.recency_weight.unwrap_or(defaults.recency_weight).max(0.0),
+            recency_half_life_days: config.recency_half_life_days
+                .filter(|d| *d > 0.0)
+                .unwrap_or(defaults.recency_half_life_days),
+        }
+    }
+}
+
+fn result_key(r: &SearchResult) -> (String, usize, usize) {
+    (r.file_path.clone(), r.line_number.unwrap_or(0), r.end_line.unwrap_or(0))
+}
+
+/// Merge ranked lists with weighted reciprocal rank fusion. Scores are
+/// normalized so a chunk ranked first in every list scores 1.0.
+pub fn reciprocal_rank_fusion(lists: &[(&[SearchResult], f64)], k: f64) -> Vec<SearchResult> {
+    let mut fused: HashMap<(String, usize, usize), (f64, SearchResult)> = HashMap::new();
+    let max_score: f64 = lists.iter().map(|(_, w)| w / (k + 1.0)).sum();
+
+    for (results, weight) in lists {
+        for (rank, result) in results.iter().enumerate() {
+            let contribution = weight / (k + rank as f64 + 1.0);
+            fused.entry(result_key(result))
+                .or_insert_with(|| (0.0, result.clone()))
+                .0 += contribution;
+        }
+    }
+
+    let mut merged: Vec<SearchResult> = fused.into_values()
+        .map(|(score, mut r)| {
+            r.score = if max_score > 0.0 { score / max_score } else { 0.0 };
+            r
+        })
+        .collect();
+    sort_by_score(&mut merged);
+    merged
+}
+
+/// Date of a daily note (`memory/YYYY-MM-DD.md`, optionally with a suffix).
+pub fn memory_file_date(path: &str) -> Option<NaiveDate> {
+    let re = Regex::new(r"^(\d{4}-\d{2}-\d{2})").unwrap();
+    let file_name = Path::new(path).file_name()?.to_str()?;
+    let in_memory_dir = Path::new(path).parent()
+        .is_some_and(|p| p.components().any(|c| c.as_os_str() == "memory"));
+    if !in_memory_dir {
+        return None;
+    }
+    let caps = re.captures(file_name)?;
+    NaiveDate::parse_from_str(&caps[1], "%Y-%m-%d").ok()
+}
+
+/// Multiply scores of dated notes by `1 + weight * 0.5^(age / half_life)`.
+pub fn apply_recency_boost(results: &mut [SearchResult], today: NaiveDate, options: &QueryOptions) {
+    if options.recency_weight <= 0.0 {
+        return;
+    }
+    for r in results.iter_mut() {
+        if let Some(date) = memory_file_date(&r.file_path) {
+            let age = (today - date).num_days().max(0) as f64;
+            let decay = 0.5f64.powf(age / options.recency_half_life_days);
+            r.score *= 1.0 + options.recency_weight * decay;
+        }
+    }
+}
+
+/// Drop results whose line span overlaps a better-ranked result from the same file.
+pub fn dedupe_overlapping(results: Vec<SearchResult>) -> Vec<SearchResult> {
+    let mut kept: Vec<SearchResult> = Vec::with_capacity(results.len());
+    for r in results {
+        let (start, end) = (r.line_number.unwrap_or(0), r.end_line.or(r.line_number).unwrap_or(0));
+        let overlaps = kept.iter().any(|k| {
+            k.file_path == r.file_path && {
+                let (k_start, k_end) = (k.line_number.unwrap_or(0), k.end_line.or(k.line_number).unwrap_or(0));
+                start <= k_end && k_start <= end
+            }
+        });
+        if !overlaps {
+            kept.push(r);
+        }
+    }
+    kept
+}
+
+fn sort_by_score(results: &mut [SearchResult]) {
+    results.sort_by(|a, b| {
+        b.score.partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.file_path.cmp(&b.file_path))
+            .then_with(|| a.line_number.cmp(&b.line_number))
+    });
+}
+
+/// Recency boost, re-sort, de-duplicate and cut to `limit`.
+pub fn finalize(mut results: Vec<SearchResult>, today: NaiveDate, options: &QueryOptions, limit: usize) -> Vec<SearchResult> {
+    apply_recency_boost(&mut results, today, options);
+    sort_by_score(&mut results);
+    let mut results = dedupe_overlapping(results);
+    results.truncate(limit);
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(path: &str, start: usize, end: usize, score: f64) -> SearchResult {
+        SearchResult {
+            file_path: path.into(),
+            content: format!("[{}]\n…", path),
+            score,
+            line_number: Some(start),
+            end_line: Some(end),
+        }
+    }
+
+    #[test]
+    fn rrf_rewards_agreement() {
+        let vector = vec![hit("a.md", 1, 3, 0.9), hit("b.md", 1, 2, 0.8)];
+        let text = vec![hit("b.md", 1, 2, 7.0), hit("c.md", 4, 4, 3.0)];
+        let fused = reciprocal_rank_fusion(&[(&vector, 1.0), (&text, 1.0)], 60.0);
+        assert_eq!(fused[0].file_path, "b.md");
+        assert_eq!(fused.len(), 3);
+        assert!(fused[0].score <= 1.0);
+
+        let first_everywhere = reciprocal_rank_fusion(&[(&vector, 1.0), (&vector, 1.0)], 60.0);
+        assert!((first_everywhere[0].score - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn dated_notes_get_recency_boost() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 31).unwrap();
+        assert_eq!(memory_file_date("memory/2026-03-01.md"), NaiveDate::from_ymd_opt(2026, 3, 1));
+        assert_eq!(memory_file_date("memory/2026-03-01-standup.md"), NaiveDate::from_ymd_opt(2026, 3, 1));
+        assert_eq!(memory_file_date("knowledge/2026-03-01.md"), None);
+
+        let results = vec![
+            hit("memory/2025-01-01.md", 1, 1, 1.0),
+            hit("memory/2026-03-30.md", 1, 1, 0.95),
+            hit("MEMORY.md", 1, 1, 0.97),
+        ];
+        let ranked = finalize(results, today, &QueryOptions::default(), 10);
+        assert_eq!(ranked[0].file_path, "memory/2026-03-30.md");
+        assert_eq!(ranked[1].file_path, "memory/2025-01-01.md");
+
+        let flat = QueryOptions { recency_weight: 0.0, ..Default::default() };
+        let ranked = finalize(vec![hit("memory/2026-03-30.md", 1, 1, 0.5)], today, &flat, 10);
+        assert_eq!(ranked[0].score, 0.5);
+    }
+
+    #[test]
+    fn overlapping_chunks_are_deduped() {
+        let results = vec![
+            hit("a.md", 1, 10, 0.9),
+            hit("a.md", 9, 18, 0.8),
+            hit("a.md", 19, 25, 0.7),
+            hit("b.md", 1, 10, 0.6),
+        ];
+        let kept = dedupe_overlapping(results);
+        let spans: Vec<_> = kept.iter().map(|r| (r.file_path.as_str(), r.line_number.unwrap())).collect();
+        assert_eq!(spans, vec![("a.md", 1), ("a.md", 19), ("b.md", 1)]);
+    }
+
+    #[test]
+    fn options_from_config() {
+        let config: MemoryQueryConfig = serde_json::from_value(serde_json::json!({
+            "hybrid": { "enabled": true, "rrfK": 10, "textWeight": 0.5 },
+            "recencyWeight": 0
+        })).unwrap();
+        let options = QueryOptions::from_config(&config);
+        assert!(options.hybrid);
+        assert_eq!(options.rrf_k, 10.0);
+        assert_eq!(options.text_weight, 0.5);
+        assert_eq!(options.vector_weight, 1.0);
+        assert_eq!(options.recency_weight, 0.0);
+    }
+}
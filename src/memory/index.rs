@@ -0,0 +1,615 @@
+use super::bm25::Bm25Index;
+use super::embedding::{cosine_similarity, EmbeddingProvider};
+use super::hybrid::{finalize, reciprocal_rank_fusion};
+use super::{collect_memory_files, display_path, MemoryError, MemorySearchOptions, SearchResult};
+use crate::utils::resolve_user_path;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{debug, warn};
+
+const INDEX_VERSION: u32 = 1;
+
+/// How files are split into chunks before embedding.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChunkOptions {
+    /// Soft cap on characters per chunk (a single longer line is kept whole).
+    pub max_chars: usize,
+    /// Lines repeated at the start of the next chunk for context.
+    pub overlap_lines: usize,
+}
+
+impl Default for ChunkOptions {
+    fn default() -> Self {
+        Self { max_chars: 1200, overlap_lines: 2 }
+    }
+}
+
+/// A chunk of a file with its 1-based, inclusive line span.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Chunk {
+    pub start_line: usize,
+    pub end_line: usize,
+    pub text: String,
+}
+
+/// Split text into overlapping line windows of roughly `max_chars`.
+pub fn chunk_with_overlap(content: &str, options: ChunkOptions) -> Vec<Chunk> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < lines.len() {
+        while start < lines.len() && lines[start].trim().is_empty() {
+            start += 1;
+        }
+        if start >= lines.len() {
+            break;
+        }
+
+        let mut end = start;
+        let mut chars = 0;
+        while end < lines.len() && (end == start || chars + lines[end].len() < options.max_chars) {
+            chars += lines[end].len() + 1;
+            end += 1;
+        }
+        let mut last = end;
+        while last > start && lines[last - 1].trim().is_empty() {
+            last -= 1;
+        }
+        chunks.push(Chunk {
+            start_line: start + 1,
+            end_line: last,
+            text: lines[start..last].join("\n"),
+        });
+
+        if end >= lines.len() {
+            break;
+        }
+        start = end.saturating_sub(options.overlap_lines).max(start + 1);
+    }
+    chunks
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexedChunk {
+    #[serde(flatten)]
+    chunk: Chunk,
+    embedding: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexedFile {
+    hash: String,
+    chunks: Vec<IndexedChunk>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexData {
+    version: u32,
+    /// "provider:model" the vectors were produced with.
+    embedder: String,
+    files: HashMap<String, IndexedFile>,
+    #[serde(default)]
+    last_sync: Option<DateTime<Utc>>,
+}
+
+/// What a sync pass changed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStats {
+    pub indexed: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+    pub chunks: usize,
+}
+
+/// Index health, as reported by `/v1/status` and `rustyclaw memory status`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexStatus {
+    pub mode: String,
+    pub embedder: String,
+    pub files: usize,
+    pub chunks: usize,
+    pub last_sync: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub syncing: bool,
+    /// Syncs completed since startup.
+    pub syncs: u64,
+    /// Active file watcher ("native" or "poll"), if any.
+    pub watcher: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct SyncState {
+    last_error: Option<String>,
+    syncing: bool,
+    syncs: u64,
+    watcher: Option<String>,
+}
+
+/// On-disk vector index over the memory files, re-embedding only files whose
+/// content hash changed.
+#[derive(Clone)]
+pub struct MemoryIndex {
+    workspace_dir: String,
+    options: MemorySearchOptions,
+    provider: Arc<dyn EmbeddingProvider>,
+    chunking: ChunkOptions,
+    data: Arc<RwLock<IndexData>>,
+    store_path: Option<PathBuf>,
+    sync_lock: Arc<Mutex<()>>,
+    /// BM25 over the indexed chunks, rebuilt lazily after a sync changes them.
+    bm25: Arc<RwLock<Option<Arc<TextIndex>>>>,
+    state: Arc<std::sync::Mutex<SyncState>>,
+}
+
+/// BM25 index plus the (file, chunk) each document refers to.
+struct TextIndex {
+    index: Bm25Index,
+    docs: Vec<(String, usize)>,
+}
+
+impl MemoryIndex {
+    pub fn new(workspace_dir: &str, options: MemorySearchOptions, provider: Arc<dyn EmbeddingProvider>) -> Self {
+        let embedder = format!("{}:{}", provider.name(), provider.model());
+        Self {
+            workspace_dir: workspace_dir.to_string(),
+            options,
+            provider,
+            chunking: ChunkOptions::default(),
+            data: Arc::new(RwLock::new(IndexData {
+                version: INDEX_VERSION,
+                embedder,
+                files: HashMap::new(),
+                last_sync: None,
+            })),
+            store_path: None,
+            sync_lock: Arc::new(Mutex::new(())),
+            bm25: Arc::new(RwLock::new(None)),
+            state: Arc::new(std::sync::Mutex::new(SyncState::default())),
+        }
+    }
+
+    pub fn with_chunking(mut self, chunking: ChunkOptions) -> Self {
+        self.chunking = chunking;
+        self
+    }
+
+    /// Persist the index to a JSON file, loading any existing index built
+    /// with the same embedder.
+    pub fn with_store_path(mut self, path: PathBuf) -> Self {
+        if let Ok(raw) = std::fs::read_to_string(&path) {
+            match serde_json::from_str::<IndexData>(&raw) {
+                Ok(data) if data.version == INDEX_VERSION && data.embedder == self.embedder() => {
+                    self.data = Arc::new(RwLock::new(data));
+                }
+                Ok(_) => debug!("Discarding memory index built with a different embedder"),
+                Err(e) => warn!("Failed to load memory index {}: {}", path.display(), e),
+            }
+        }
+        self.store_path = Some(path);
+        self
+    }
+
+    fn embedder(&self) -> String {
+        format!("{}:{}", self.provider.name(), self.provider.model())
+    }
+
+    pub fn options(&self) -> &MemorySearchOptions {
+        &self.options
+    }
+
+    pub fn workspace_dir(&self) -> &str {
+        &self.workspace_dir
+    }
+
+    /// Current index health.
+    pub async fn status(&self) -> IndexStatus {
+        let (files, chunks) = self.counts().await;
+        let last_sync = self.data.read().await.last_sync;
+        let state = self.state.lock().unwrap();
+        IndexStatus {
+            mode: self.mode().to_string(),
+            embedder: self.embedder(),
+            files,
+            chunks,
+            last_sync,
+            last_error: state.last_error.clone(),
+            syncing: state.syncing,
+            syncs: state.syncs,
+            watcher: state.watcher.clone(),
+        }
+    }
+
+    pub(crate) fn set_watcher(&self, watcher: Option<&str>) {
+        self.state.lock().unwrap().watcher = watcher.map(String::from);
+    }
+
+    /// Sync in the background when a session starts, if that trigger is enabled.
+    pub fn on_session_start(&self) {
+        if !self.options.sync.on_session_start {
+            return;
+        }
+        let index = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = index.sync().await {
+                warn!("Memory sync on session start failed: {}", e);
+            }
+        });
+    }
+
+    /// Sync before a search, if that trigger is enabled or the index has
+    /// never been built.
+    pub async fn sync_for_search(&self) -> Result<(), MemoryError> {
+        let never_synced = self.data.read().await.last_sync.is_none();
+        if self.options.sync.on_search || never_synced {
+            self.sync().await?;
+        }
+        Ok(())
+    }
+
+    /// Number of indexed files and chunks.
+    pub async fn counts(&self) -> (usize, usize) {
+        let data = self.data.read().await;
+        let chunks = data.files.values().map(|f| f.chunks.len()).sum();
+        (data.files.len(), chunks)
+    }
+
+    async fn persist(&self) -> Result<(), MemoryError> {
+        let Some(path) = &self.store_path else {
+            return Ok(());
+        };
+        let raw = {
+            let data = self.data.read().await;
+            serde_json::to_string(&*data).map_err(|e| MemoryError::Index(e.to_string()))?
+        };
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, raw).await?;
+        tokio::fs::rename(&tmp, path).await?;
+        Ok(())
+    }
+
+    /// Bring the index up to date with the memory files on disk.
+    pub async fn sync(&self) -> Result<SyncStats, MemoryError> {
+        let _guard = self.sync_lock.lock().await;
+        self.state.lock().unwrap().syncing = true;
+        let result = self.sync_files().await;
+        let mut state = self.state.lock().unwrap();
+        state.syncing = false;
+        match &result {
+            Ok(_) => {
+                state.syncs += 1;
+                state.last_error = None;
+            }
+            Err(e) => state.last_error = Some(e.to_string()),
+        }
+        result
+    }
+
+    async fn sync_files(&self) -> Result<SyncStats, MemoryError> {
+        let workspace_dir = self.workspace_dir.clone();
+        let options = self.options.clone();
+        let files = tokio::task::spawn_blocking(move || {
+            let workspace = resolve_user_path(&workspace_dir);
+            collect_memory_files(&workspace_dir, &options).into_iter()
+                .filter_map(|path| {
+                    let content = std::fs::read_to_string(&path).ok()?;
+                    let hash = hex::encode(Sha256::digest(content.as_bytes()));
+                    Some((display_path(&path, &workspace), hash, content))
+                })
+                .collect::<Vec<_>>()
+        }).await.map_err(|e| MemoryError::Index(e.to_string()))?;
+
+        let known: HashMap<String, String> = {
+            let data = self.data.read().await;
+            data.files.iter().map(|(k, v)| (k.clone(), v.hash.clone())).collect()
+        };
+
+        let mut stats = SyncStats::default();
+        let mut updated: Vec<(String, IndexedFile)> = Vec::new();
+        for (rel_path, hash, content) in &files {
+            if known.get(rel_path) == Some(hash) {
+                stats.unchanged += 1;
+                continue;
+            }
+            let chunks = chunk_with_overlap(content, self.chunking);
+            let texts: Vec<String> = chunks.iter()
+                .map(|c| format!("{}\n{}", rel_path, c.text))
+                .collect();
+            let embeddings = if texts.is_empty() {
+                Vec::new()
+            } else {
+                self.provider.embed(&texts).await?
+            };
+            let chunks = chunks.into_iter().zip(embeddings)
+                .map(|(chunk, embedding)| IndexedChunk { chunk, embedding })
+                .collect();
+            updated.push((rel_path.clone(), IndexedFile { hash: hash.clone(), chunks }));
+            stats.indexed += 1;
+        }
+
+        let changed = {
+            let mut data = self.data.write().await;
+            let before = data.files.len();
+            data.files.retain(|path, _| files.iter().any(|(p, _, _)| p == path));
+            stats.removed = before - data.files.len();
+            for (path, file) in updated {
+                data.files.insert(path, file);
+            }
+            stats.chunks = data.files.values().map(|f| f.chunks.len()).sum();
+            data.last_sync = Some(Utc::now());
+            stats.indexed > 0 || stats.removed > 0
+        };
+
+        if changed {
+            debug!("Memory index sync: {:?}", stats);
+            *self.bm25.write().await = None;
+            self.persist().await?;
+        }
+        Ok(stats)
+    }
+
+    /// Ranked memory search: vector similarity, fused with BM25 when hybrid
+    /// retrieval is enabled, then recency-boosted and de-duplicated.
+    pub async fn query(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>, MemoryError> {
+        let query_options = &self.options.query;
+        // Over-fetch so fusion and de-duplication have candidates to work with
+        let candidates = (limit * 4).max(20);
+        let vector = self.search(query, candidates).await?;
+        let ranked = if query_options.hybrid {
+            let text = self.text_search(query, candidates).await;
+            reciprocal_rank_fusion(
+                &[(&vector, query_options.vector_weight), (&text, query_options.text_weight)],
+                query_options.rrf_k,
+            )
+        } else {
+            vector
+        };
+        let today = chrono::Local::now().date_naive();
+        Ok(finalize(ranked, today, query_options, limit))
+    }
+
+    /// "hybrid" or "vector", depending on configuration.
+    pub fn mode(&self) -> &'static str {
+        if self.options.query.hybrid { "hybrid" } else { "vector" }
+    }
+
+    async fn text_index(&self) -> Arc<TextIndex> {
+        if let Some(existing) = self.bm25.read().await.as_ref() {
+            return existing.clone();
+        }
+        let data = self.data.read().await;
+        let mut docs = Vec::new();
+        let mut texts = Vec::new();
+        for (path, file) in &data.files {
+            for (i, chunk) in file.chunks.iter().enumerate() {
+                docs.push((path.clone(), i));
+                texts.push(chunk.chunk.text.as_str());
+            }
+        }
+        let built = Arc::new(TextIndex { index: Bm25Index::build(&texts), docs });
+        *self.bm25.write().await = Some(built.clone());
+        built
+    }
+
+    /// Top-k chunks by BM25.
+    pub async fn text_search(&self, query: &str, limit: usize) -> Vec<SearchResult> {
+        let text_index = self.text_index().await;
+        let data = self.data.read().await;
+        text_index.index.search(query, limit).into_iter()
+            .filter_map(|(doc, score)| {
+                let (path, i) = &text_index.docs[doc];
+                let chunk = &data.files.get(path)?.chunks.get(*i)?.chunk;
+                Some(SearchResult {
+                    file_path: path.clone(),
+                    content: format!("[{}]\n{}", path, chunk.text),
+                    score,
+                    line_number: Some(chunk.start_line),
+                    end_line: Some(chunk.end_line),
+                })
+            })
+            .collect()
+    }
+
+    /// Top-k chunks by cosine similarity to the query.
+    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>, MemoryError> {
+        if query.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        let query_vec = self.provider.embed(&[query.to_string()]).await?
+            .into_iter().next()
+            .ok_or_else(|| MemoryError::Index("embedder returned no vector".into()))?;
+
+        let data = self.data.read().await;
+        let mut scored: Vec<(f32, &String, &IndexedChunk)> = data.files.iter()
+            .flat_map(|(path, file)| file.chunks.iter().map(move |c| (path, c)))
+            .map(|(path, c)| (cosine_similarity(&query_vec, &c.embedding), path, c))
+            .filter(|(score, _, _)| *score > 0.0)
+            .collect();
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(limit);
+
+        Ok(scored.into_iter().map(|(score, path, c)| SearchResult {
+            file_path: path.clone(),
+            content: format!("[{}]\n{}", path, c.chunk.text),
+            score: score as f64,
+            line_number: Some(c.chunk.start_line),
+            end_line: Some(c.chunk.end_line),
+        }).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::embedding::HashingEmbedder;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Hashing embedder that counts how many texts it embedded.
+    struct CountingEmbedder {
+        inner: HashingEmbedder,
+        embedded: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for CountingEmbedder {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        fn model(&self) -> &str {
+            "hash"
+        }
+
+        async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, crate::memory::embedding::EmbeddingError> {
+            self.embedded.fetch_add(texts.len(), Ordering::SeqCst);
+            self.inner.embed(texts).await
+        }
+    }
+
+    fn workspace() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("memory")).unwrap();
+        std::fs::write(
+            dir.path().join("memory/2026-02-01.md"),
+            "# Feb 1\n\nRenewed the passport at the consulate.\n\nCar needs an oil change next week.",
+        ).unwrap();
+        std::fs::write(dir.path().join("MEMORY.md"), "Allergic to peanuts.\nFavorite color is green.").unwrap();
+        dir
+    }
+
+    fn index(dir: &tempfile::TempDir) -> (MemoryIndex, Arc<AtomicUsize>) {
+        let embedded = Arc::new(AtomicUsize::new(0));
+        let provider = Arc::new(CountingEmbedder { inner: HashingEmbedder::new(256), embedded: embedded.clone() });
+        let index = MemoryIndex::new(dir.path().to_str().unwrap(), MemorySearchOptions::default(), provider);
+        (index, embedded)
+    }
+
+    #[test]
+    fn chunks_overlap_and_track_lines() {
+        let content = (1..=10).map(|i| format!("line number {:02}", i)).collect::<Vec<_>>().join("\n");
+        let chunks = chunk_with_overlap(&content, ChunkOptions { max_chars: 60, overlap_lines: 1 });
+        assert_eq!(chunks[0].start_line, 1);
+        assert_eq!(chunks[0].end_line, 4);
+        assert_eq!(chunks[1].start_line, 4);
+        assert_eq!(chunks.last().unwrap().end_line, 10);
+        assert!(chunks[0].text.starts_with("line number 01"));
+    }
+
+    #[test]
+    fn chunking_skips_blank_runs() {
+        let chunks = chunk_with_overlap("\n\nonly line\n\n", ChunkOptions::default());
+        assert_eq!(chunks, vec![Chunk { start_line: 3, end_line: 3, text: "only line".into() }]);
+        assert!(chunk_with_overlap("", ChunkOptions::default()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn vector_search_returns_line_numbers() {
+        let dir = workspace();
+        let (index, _) = index(&dir);
+        let stats = index.sync().await.unwrap();
+        assert_eq!(stats.indexed, 2);
+
+        let results = index.search("when is the oil change for the car", 3).await.unwrap();
+        assert_eq!(results[0].file_path, "memory/2026-02-01.md");
+        assert_eq!(results[0].line_number, Some(1));
+        assert_eq!(results[0].end_line, Some(5));
+
+        let results = index.search("peanuts allergy", 1).await.unwrap();
+        assert_eq!(results[0].file_path, "MEMORY.md");
+    }
+
+    #[tokio::test]
+    async fn hybrid_query_fuses_rankings() {
+        let dir = workspace();
+        let embedded = Arc::new(AtomicUsize::new(0));
+        let provider = Arc::new(CountingEmbedder { inner: HashingEmbedder::new(256), embedded });
+        let options = MemorySearchOptions {
+            query: crate::memory::QueryOptions { hybrid: true, ..Default::default() },
+            ..Default::default()
+        };
+        let index = MemoryIndex::new(dir.path().to_str().unwrap(), options, provider);
+        assert_eq!(index.mode(), "hybrid");
+        index.sync().await.unwrap();
+
+        let text = index.text_search("passport", 5).await;
+        assert_eq!(text.len(), 1);
+        assert_eq!(text[0].file_path, "memory/2026-02-01.md");
+
+        let results = index.query("passport consulate", 5).await.unwrap();
+        assert_eq!(results[0].file_path, "memory/2026-02-01.md");
+
+        // BM25 sees new content after a sync
+        std::fs::write(dir.path().join("MEMORY.md"), "Passport number is in the safe.").unwrap();
+        index.sync().await.unwrap();
+        assert_eq!(index.text_search("safe", 5).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn incremental_sync_by_hash() {
+        let dir = workspace();
+        let (index, embedded) = index(&dir);
+        index.sync().await.unwrap();
+        let first = embedded.load(Ordering::SeqCst);
+
+        let again = index.sync().await.unwrap();
+        assert_eq!(again.unchanged, 2);
+        assert_eq!(embedded.load(Ordering::SeqCst), first);
+
+        std::fs::write(dir.path().join("MEMORY.md"), "Allergic to shellfish.").unwrap();
+        std::fs::remove_file(dir.path().join("memory/2026-02-01.md")).unwrap();
+        let stats = index.sync().await.unwrap();
+        assert_eq!(stats, SyncStats { indexed: 1, unchanged: 0, removed: 1, chunks: 1 });
+        assert_eq!(embedded.load(Ordering::SeqCst), first + 1);
+    }
+
+    #[tokio::test]
+    async fn status_tracks_syncs_and_errors() {
+        let dir = workspace();
+        let (index, _) = index(&dir);
+        assert_eq!(index.status().await.last_sync, None);
+        index.sync().await.unwrap();
+        let status = index.status().await;
+        assert_eq!((status.files, status.chunks, status.syncs), (2, 2, 1));
+        assert_eq!(status.mode, "vector");
+        assert!(status.last_sync.is_some());
+        assert!(status.last_error.is_none());
+
+        // A store path that can't be written surfaces as last_error
+        std::fs::write(dir.path().join("blocker"), "").unwrap();
+        let broken = index.with_store_path(dir.path().join("blocker/index.json"));
+        std::fs::write(dir.path().join("MEMORY.md"), "changed").unwrap();
+        assert!(broken.sync().await.is_err());
+        assert!(broken.status().await.last_error.is_some());
+    }
+
+    #[tokio::test]
+    async fn index_persists_across_instances() {
+        let dir = workspace();
+        let store = dir.path().join("state/index.json");
+        let (index, _) = index(&dir);
+        let index = index.with_store_path(store.clone());
+        index.sync().await.unwrap();
+        assert!(store.exists());
+
+        let (reloaded, embedded) = self::index(&dir);
+        let reloaded = reloaded.with_store_path(store);
+        assert_eq!(reloaded.counts().await, (2, 2));
+        reloaded.sync().await.unwrap();
+        assert_eq!(embedded.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,283 @@
+//! Writing memory: structured entries appended to the daily note
+//! (`memory/YYYY-MM-DD.md`), with the day decided in the user's timezone.
+
+use super::MemoryError;
+use crate::config::OpenClawConfig;
+use crate::cron_system::parse_timezone;
+use crate::utils::resolve_user_path;
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use chrono_tz::Tz;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// What kind of thing an entry records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    Note,
+    Fact,
+    Decision,
+    Preference,
+    Todo,
+}
+
+impl EntryKind {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "note" => Some(Self::Note),
+            "fact" => Some(Self::Fact),
+            "decision" => Some(Self::Decision),
+            "preference" | "pref" => Some(Self::Preference),
+            "todo" | "task" => Some(Self::Todo),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Note => "note",
+            Self::Fact => "fact",
+            Self::Decision => "decision",
+            Self::Preference => "preference",
+            Self::Todo => "todo",
+        }
+    }
+}
+
+/// One line in a daily note.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryEntry {
+    pub kind: EntryKind,
+    pub text: String,
+    pub tags: Vec<String>,
+}
+
+impl MemoryEntry {
+    pub fn new(kind: EntryKind, text: &str) -> Self {
+        Self { kind, text: text.trim().to_string(), tags: Vec::new() }
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags.into_iter()
+            .map(|t| t.trim().trim_start_matches('#').replace(char::is_whitespace, "-"))
+            .filter(|t| !t.is_empty())
+            .collect();
+        self
+    }
+
+    /// `- HH:MM [kind] text #tag`; continuation lines are indented.
+    pub fn render(&self, time: &str) -> String {
+        let mut lines = self.text.lines().map(str::trim_end).filter(|l| !l.trim().is_empty());
+        let mut out = format!("- {} [{}] {}", time, self.kind.as_str(), lines.next().unwrap_or("").trim());
+        for line in lines {
+            out.push_str("\n  ");
+            out.push_str(line.trim());
+        }
+        for tag in &self.tags {
+            out.push_str(" #");
+            out.push_str(tag);
+        }
+        out
+    }
+}
+
+/// Appends entries to today's daily note.
+#[derive(Debug, Clone)]
+pub struct MemoryJournal {
+    workspace_dir: PathBuf,
+    /// Day boundaries follow this zone; the host's local time when unset.
+    timezone: Option<Tz>,
+}
+
+impl MemoryJournal {
+    pub fn new(workspace_dir: &str) -> Self {
+        Self { workspace_dir: resolve_user_path(workspace_dir), timezone: None }
+    }
+
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
+    /// Uses `agents.defaults.heartbeat.activeHours.timezone` for date rollover.
+    pub fn from_config(workspace_dir: &str, config: &OpenClawConfig) -> Self {
+        let journal = Self::new(workspace_dir);
+        let timezone = config.agents.as_ref()
+            .and_then(|a| a.defaults.as_ref())
+            .and_then(|d| d.heartbeat.as_ref())
+            .and_then(|h| h.active_hours.as_ref())
+            .and_then(|a| a.timezone.as_deref());
+        match timezone.map(parse_timezone) {
+            Some(Ok(tz)) => journal.with_timezone(tz),
+            Some(Err(e)) => {
+                tracing::warn!("{}; daily notes use local time", e);
+                journal
+            }
+            None => journal,
+        }
+    }
+
+    pub fn timezone(&self) -> Option<Tz> {
+        self.timezone
+    }
+
+    /// Calendar date of `now` in the journal's timezone.
+    pub fn date_at(&self, now: DateTime<Utc>) -> NaiveDate {
+        match self.timezone {
+            Some(tz) => now.with_timezone(&tz).date_naive(),
+            None => now.with_timezone(&Local).date_naive(),
+        }
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.date_at(Utc::now())
+    }
+
+    pub fn path_for(&self, date: NaiveDate) -> PathBuf {
+        self.workspace_dir.join("memory").join(format!("{}.md", date.format("%Y-%m-%d")))
+    }
+
+    /// Today's note, if it exists.
+    pub fn load_today(&self) -> Option<String> {
+        std::fs::read_to_string(self.path_for(self.today())).ok()
+    }
+
+    pub fn append(&self, entries: &[MemoryEntry]) -> Result<AppendOutcome, MemoryError> {
+        self.append_at(entries, Utc::now())
+    }
+
+    /// Append entries to the note for `now`'s date. Entries whose text is
+    /// already in that note are skipped.
+    pub fn append_at(&self, entries: &[MemoryEntry], now: DateTime<Utc>) -> Result<AppendOutcome, MemoryError> {
+        let date = self.date_at(now);
+        let path = self.path_for(date);
+        let existing = std::fs::read_to_string(&path).unwrap_or_default();
+        let time = match self.timezone {
+            Some(tz) => now.with_timezone(&tz).format("%H:%M").to_string(),
+            None => now.with_timezone(&Local).format("%H:%M").to_string(),
+        };
+
+        let mut seen: Vec<&str> = Vec::new();
+        let fresh: Vec<&MemoryEntry> = entries.iter()
+            .filter(|e| !e.text.is_empty())
+            .filter(|e| {
+                let first_line = e.text.lines().next().unwrap_or("").trim();
+                let dup = contains_entry(&existing, first_line) || seen.contains(&first_line);
+                seen.push(first_line);
+                !dup
+            })
+            .collect();
+        let outcome = AppendOutcome {
+            path: path.clone(),
+            date,
+            written: fresh.len(),
+            skipped: entries.len() - fresh.len(),
+        };
+        if fresh.is_empty() {
+            return Ok(outcome);
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut block = String::new();
+        if existing.is_empty() {
+            block.push_str(&format!("# {}\n\n", date.format("%Y-%m-%d")));
+        } else if !existing.ends_with('\n') {
+            block.push('\n');
+        }
+        for entry in fresh {
+            block.push_str(&entry.render(&time));
+            block.push('\n');
+        }
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&path)?;
+        file.write_all(block.as_bytes())?;
+        Ok(outcome)
+    }
+
+    pub fn workspace_dir(&self) -> &Path {
+        &self.workspace_dir
+    }
+}
+
+/// Result of an append.
+#[derive(Debug, Clone)]
+pub struct AppendOutcome {
+    pub path: PathBuf,
+    pub date: NaiveDate,
+    pub written: usize,
+    /// Entries dropped as duplicates or empty.
+    pub skipped: usize,
+}
+
+fn contains_entry(note: &str, first_line: &str) -> bool {
+    note.lines().any(|line| {
+        line.strip_prefix("- ")
+            .and_then(|rest| rest.split_once("] "))
+            .is_some_and(|(_, text)| text.trim_end() == first_line || text.starts_with(&format!("{} #", first_line)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn journal(dir: &tempfile::TempDir, tz: &str) -> MemoryJournal {
+        MemoryJournal::new(dir.path().to_str().unwrap()).with_timezone(parse_timezone(tz).unwrap())
+    }
+
+    #[test]
+    fn entry_rendering() {
+        let entry = MemoryEntry::new(EntryKind::Fact, "Moved to Lisbon\nstarts new job in May")
+            .with_tags(vec!["#life".into(), "big move".into(), "".into()]);
+        assert_eq!(entry.render("09:30"), "- 09:30 [fact] Moved to Lisbon\n  starts new job in May #life #big-move");
+        assert_eq!(EntryKind::parse("Pref"), Some(EntryKind::Preference));
+        assert_eq!(EntryKind::parse("idea"), None);
+    }
+
+    #[test]
+    fn date_rolls_over_in_configured_timezone() {
+        let dir = tempfile::tempdir().unwrap();
+        // 23:30 UTC on March 1st is already March 2nd in Tokyo, still March 1st in New York
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 23, 30, 0).unwrap();
+        assert_eq!(journal(&dir, "Asia/Tokyo").date_at(now), NaiveDate::from_ymd_opt(2026, 3, 2).unwrap());
+        assert_eq!(journal(&dir, "America/New_York").date_at(now), NaiveDate::from_ymd_opt(2026, 3, 1).unwrap());
+
+        let outcome = journal(&dir, "Asia/Tokyo")
+            .append_at(&[MemoryEntry::new(EntryKind::Note, "Late call with Ana")], now)
+            .unwrap();
+        assert!(outcome.path.ends_with("memory/2026-03-02.md"));
+        let content = std::fs::read_to_string(&outcome.path).unwrap();
+        assert_eq!(content, "# 2026-03-02\n\n- 08:30 [note] Late call with Ana\n");
+    }
+
+    #[test]
+    fn appends_and_skips_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = journal(&dir, "UTC");
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 10, 0, 0).unwrap();
+        journal.append_at(&[MemoryEntry::new(EntryKind::Preference, "Prefers tea").with_tags(vec!["food".into()])], now).unwrap();
+
+        let later = Utc.with_ymd_and_hms(2026, 3, 1, 18, 0, 0).unwrap();
+        let outcome = journal.append_at(&[
+            MemoryEntry::new(EntryKind::Preference, "Prefers tea"),
+            MemoryEntry::new(EntryKind::Todo, "Book dentist"),
+            MemoryEntry::new(EntryKind::Todo, "Book dentist"),
+        ], later).unwrap();
+        assert_eq!((outcome.written, outcome.skipped), (1, 2));
+
+        let content = std::fs::read_to_string(&outcome.path).unwrap();
+        assert_eq!(content.lines().filter(|l| l.starts_with("- ")).count(), 2);
+        assert!(content.ends_with("- 18:00 [todo] Book dentist\n"));
+    }
+
+    #[test]
+    fn timezone_from_config() {
+        let config: OpenClawConfig = serde_json::from_value(serde_json::json!({
+            "agents": { "defaults": { "heartbeat": { "activeHours": { "timezone": "Europe/Berlin" } } } }
+        })).unwrap();
+        let journal = MemoryJournal::from_config("/tmp/ws", &config);
+        assert_eq!(journal.timezone(), Some(chrono_tz::Europe::Berlin));
+        assert!(parse_timezone("Mars/Olympus").is_err());
+    }
+}
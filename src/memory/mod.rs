@@ -1,85 +1,344 @@
-use std::path::Path;
+pub mod bm25;
+pub mod embedding;
+pub mod eval;
+pub mod flush;
+pub mod hybrid;
+pub mod index;
+pub mod journal;
+pub mod watcher;
+
+pub use hybrid::QueryOptions;
+pub use index::{IndexStatus, MemoryIndex, SyncStats};
+pub use journal::{EntryKind, MemoryEntry, MemoryJournal};
+pub use watcher::MemoryWatcher;
+
+use crate::config::types::{MemorySearchConfig, MemorySyncConfig};
+use crate::config::OpenClawConfig;
+use crate::utils::resolve_user_path;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+/// File extensions indexed as memory.
+const MEMORY_EXTENSIONS: &[&str] = &["md", "txt", "json", "yaml", "yml"];
+
 /// A memory search result.
 #[derive(Debug, Clone)]
 pub struct SearchResult {
     pub file_path: String,
     pub content: String,
     pub score: f64,
+    /// First line of the matching chunk (1-based).
     pub line_number: Option<usize>,
+    /// Last line of the matching chunk (1-based, inclusive).
+    pub end_line: Option<usize>,
 }
 
-/// Simple text-based memory search (grep-style).
-/// In a full implementation, this would use vector embeddings.
-pub fn search_memory(
-    workspace_dir: &str,
-    query: &str,
-    limit: usize,
-) -> Vec<SearchResult> {
-    let mut results = Vec::new();
-    let query_lower = query.to_lowercase();
-    let query_terms: Vec<&str> = query_lower.split_whitespace().collect();
+#[derive(Debug, thiserror::Error)]
+pub enum MemoryError {
+    #[error("Memory file not found: {0}")]
+    NotFound(String),
+    #[error("Path is outside the configured memory sources: {0}")]
+    OutsideSources(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Embedding(#[from] embedding::EmbeddingError),
+    #[error("Memory index error: {0}")]
+    Index(String),
+    #[error("Model call failed: {0}")]
+    Provider(#[from] crate::provider::types::ProviderError),
+}
 
-    if query_terms.is_empty() {
-        return results;
+/// A named group of workspace files searched as memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemorySource {
+    /// MEMORY.md and the daily notes under memory/.
+    Memory,
+    /// Reference notes under knowledge/.
+    Knowledge,
+}
+
+impl MemorySource {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "memory" => Some(Self::Memory),
+            "knowledge" => Some(Self::Knowledge),
+            _ => None,
+        }
     }
 
-    let search_dirs = vec![
-        Path::new(workspace_dir).join("memory"),
-        Path::new(workspace_dir).join("knowledge"),
-    ];
+    fn roots(&self, workspace: &Path) -> Vec<PathBuf> {
+        match self {
+            Self::Memory => vec![workspace.join("MEMORY.md"), workspace.join("memory")],
+            Self::Knowledge => vec![workspace.join("knowledge")],
+        }
+    }
+}
 
-    for search_dir in &search_dirs {
-        if !search_dir.exists() {
-            continue;
+/// When the embedding index re-syncs (`memorySearch.sync`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncTriggers {
+    pub on_session_start: bool,
+    pub on_search: bool,
+    /// Watch memory files and re-index on change.
+    pub watch: bool,
+}
+
+impl Default for SyncTriggers {
+    fn default() -> Self {
+        Self { on_session_start: true, on_search: true, watch: false }
+    }
+}
+
+impl SyncTriggers {
+    pub fn from_config(config: &MemorySyncConfig) -> Self {
+        let defaults = Self::default();
+        Self {
+            on_session_start: config.on_session_start.unwrap_or(defaults.on_session_start),
+            on_search: config.on_search.unwrap_or(defaults.on_search),
+            watch: config.watch.unwrap_or(defaults.watch),
         }
+    }
+}
+
+/// Which files memory search covers (`agents.defaults.memorySearch`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemorySearchOptions {
+    pub enabled: bool,
+    pub sources: Vec<MemorySource>,
+    /// Extra files or directories; relative paths resolve against the workspace.
+    pub extra_paths: Vec<String>,
+    pub query: QueryOptions,
+    pub sync: SyncTriggers,
+}
 
-        for entry in WalkDir::new(search_dir)
+impl Default for MemorySearchOptions {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sources: vec![MemorySource::Memory, MemorySource::Knowledge],
+            extra_paths: Vec::new(),
+            query: QueryOptions::default(),
+            sync: SyncTriggers::default(),
+        }
+    }
+}
+
+impl MemorySearchOptions {
+    pub fn from_config(config: &OpenClawConfig) -> Self {
+        config.agents.as_ref()
+            .and_then(|a| a.defaults.as_ref())
+            .and_then(|d| d.memory_search.as_ref())
+            .map(Self::from_search_config)
+            .unwrap_or_default()
+    }
+
+    pub fn from_search_config(config: &MemorySearchConfig) -> Self {
+        let defaults = Self::default();
+        let sources = match &config.sources {
+            Some(names) => names.iter().filter_map(|name| {
+                let source = MemorySource::parse(name);
+                if source.is_none() {
+                    tracing::warn!("Ignoring unknown memory source: {}", name);
+                }
+                source
+            }).collect(),
+            None => defaults.sources,
+        };
+        Self {
+            enabled: config.enabled.unwrap_or(true),
+            sources,
+            extra_paths: config.extra_paths.clone().unwrap_or_default(),
+            query: config.query.as_ref().map(QueryOptions::from_config).unwrap_or_default(),
+            sync: config.sync.as_ref().map(SyncTriggers::from_config).unwrap_or_default(),
+        }
+    }
+
+    /// Files and directories searched for a workspace.
+    pub fn roots(&self, workspace_dir: &str) -> Vec<PathBuf> {
+        let workspace = resolve_user_path(workspace_dir);
+        let mut roots: Vec<PathBuf> = self.sources.iter()
+            .flat_map(|s| s.roots(&workspace))
+            .collect();
+        for extra in &self.extra_paths {
+            let path = if extra.starts_with('~') || Path::new(extra).is_absolute() {
+                resolve_user_path(extra)
+            } else {
+                workspace.join(extra)
+            };
+            if !roots.contains(&path) {
+                roots.push(path);
+            }
+        }
+        roots
+    }
+}
+
+/// All memory files under the configured roots.
+pub fn collect_memory_files(workspace_dir: &str, options: &MemorySearchOptions) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for root in options.roots(workspace_dir) {
+        if root.is_file() {
+            files.push(root);
+            continue;
+        }
+        if !root.is_dir() {
+            continue;
+        }
+        for entry in WalkDir::new(&root)
+            .sort_by_file_name()
             .into_iter()
             .filter_map(|e| e.ok())
             .filter(|e| e.file_type().is_file())
         {
-            let path = entry.path();
-            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-            if !matches!(ext, "md" | "txt" | "json" | "yaml" | "yml") {
-                continue;
+            let ext = entry.path().extension().and_then(|e| e.to_str()).unwrap_or("");
+            if MEMORY_EXTENSIONS.contains(&ext) {
+                files.push(entry.into_path());
             }
+        }
+    }
+    files.dedup();
+    files
+}
 
-            if let Ok(content) = std::fs::read_to_string(path) {
-                let rel_path = path.strip_prefix(workspace_dir)
-                    .unwrap_or(path)
-                    .to_string_lossy()
-                    .to_string();
-
-                // Search by chunks (paragraphs)
-                for (chunk_idx, chunk) in content.split("\n\n").enumerate() {
-                    let chunk_lower = chunk.to_lowercase();
-                    let matching_terms = query_terms.iter()
-                        .filter(|term| chunk_lower.contains(*term))
-                        .count();
-
-                    if matching_terms > 0 {
-                        let score = matching_terms as f64 / query_terms.len() as f64;
-                        // Prepend file path to chunk for context (matching frankclaw behavior)
-                        let content_with_path = format!("[{}]\n{}", rel_path, chunk.trim());
-
-                        results.push(SearchResult {
-                            file_path: rel_path.clone(),
-                            content: content_with_path,
-                            score,
-                            line_number: Some(chunk_idx + 1),
-                        });
-                    }
-                }
+/// Path shown to the model: relative to the workspace when possible.
+pub(crate) fn display_path(path: &Path, workspace: &Path) -> String {
+    path.strip_prefix(workspace)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Split text into blank-line separated chunks with their 1-based line spans.
+pub fn chunk_lines(content: &str) -> Vec<(usize, usize, String)> {
+    let mut chunks = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut start = 0;
+    for (idx, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                chunks.push((start + 1, idx, current.join("\n")));
+                current.clear();
             }
+            continue;
         }
+        if current.is_empty() {
+            start = idx;
+        }
+        current.push(line);
+    }
+    if !current.is_empty() {
+        chunks.push((start + 1, start + current.len(), current.join("\n")));
     }
+    chunks
+}
 
-    // Sort by score descending
-    results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
-    results.truncate(limit);
-    results
+/// BM25 memory search over the default sources.
+/// See [`MemoryIndex`] for embedding-based search.
+pub fn search_memory(
+    workspace_dir: &str,
+    query: &str,
+    limit: usize,
+) -> Vec<SearchResult> {
+    search_memory_with(workspace_dir, query, limit, &MemorySearchOptions::default())
+}
+
+/// BM25 memory search over the configured sources and extra paths.
+/// Chunks are paragraphs; scores are relative to the best hit (1.0), then
+/// boosted for recent daily notes.
+pub fn search_memory_with(
+    workspace_dir: &str,
+    query: &str,
+    limit: usize,
+    options: &MemorySearchOptions,
+) -> Vec<SearchResult> {
+    if bm25::tokenize(query).is_empty() {
+        return Vec::new();
+    }
+
+    let workspace = resolve_user_path(workspace_dir);
+    let mut chunks: Vec<(String, usize, usize, String)> = Vec::new();
+    for path in collect_memory_files(workspace_dir, options) {
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let rel_path = display_path(&path, &workspace);
+        for (start_line, end_line, chunk) in chunk_lines(&content) {
+            chunks.push((rel_path.clone(), start_line, end_line, chunk));
+        }
+    }
+
+    let index = bm25::Bm25Index::build(&chunks.iter().map(|c| c.3.as_str()).collect::<Vec<_>>());
+    let ranked = index.search(query, chunks.len());
+    let top = ranked.first().map(|(_, score)| *score).unwrap_or(1.0);
+
+    let results = ranked.into_iter().map(|(doc, score)| {
+        let (rel_path, start_line, end_line, chunk) = &chunks[doc];
+        SearchResult {
+            file_path: rel_path.clone(),
+            // Prepend file path to chunk for context (matching frankclaw behavior)
+            content: format!("[{}]\n{}", rel_path, chunk.trim()),
+            score: score / top,
+            line_number: Some(*start_line),
+            end_line: Some(*end_line),
+        }
+    }).collect();
+
+    let today = chrono::Local::now().date_naive();
+    hybrid::finalize(results, today, &options.query, limit)
+}
+
+/// A span of lines read from a memory file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemorySpan {
+    pub file_path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub total_lines: usize,
+    pub text: String,
+}
+
+/// Read `lines` lines starting at `from` (1-based) from a memory file.
+/// The path must fall under one of the configured memory roots.
+pub fn read_memory_span(
+    workspace_dir: &str,
+    path: &str,
+    from: usize,
+    lines: usize,
+    options: &MemorySearchOptions,
+) -> Result<MemorySpan, MemoryError> {
+    let workspace = resolve_user_path(workspace_dir);
+    let requested = if path.starts_with('~') || Path::new(path).is_absolute() {
+        resolve_user_path(path)
+    } else {
+        workspace.join(path)
+    };
+    let resolved = requested.canonicalize()
+        .map_err(|_| MemoryError::NotFound(path.to_string()))?;
+
+    let allowed = options.roots(workspace_dir).iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .any(|root| resolved.starts_with(&root));
+    if !allowed {
+        return Err(MemoryError::OutsideSources(path.to_string()));
+    }
+    if !resolved.is_file() {
+        return Err(MemoryError::NotFound(path.to_string()));
+    }
+
+    let content = std::fs::read_to_string(&resolved)?;
+    let all: Vec<&str> = content.lines().collect();
+    let start = from.max(1).min(all.len().max(1));
+    let end = (start - 1 + lines.max(1)).min(all.len());
+    let text = if all.is_empty() { String::new() } else { all[start - 1..end].join("\n") };
+
+    Ok(MemorySpan {
+        file_path: display_path(&requested, &workspace),
+        start_line: start,
+        end_line: end,
+        total_lines: all.len(),
+        text,
+    })
 }
 
 /// List workspace context files (AGENTS.md, SOUL.md, etc.).
@@ -108,9 +367,7 @@ pub fn list_workspace_context_files(workspace_dir: &str) -> Vec<(String, String)
 
 /// Load today's memory file.
 pub fn load_today_memory(workspace_dir: &str) -> Option<String> {
-    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-    let path = Path::new(workspace_dir).join("memory").join(format!("{}.md", today));
-    std::fs::read_to_string(&path).ok()
+    MemoryJournal::new(workspace_dir).load_today()
 }
 
 #[cfg(test)]
@@ -186,6 +443,66 @@ mod tests {
         assert_eq!(files[0].0, "AGENTS.md");
     }
 
+    #[test]
+    fn search_reports_line_spans() {
+        let dir = setup_workspace();
+        let results = search_memory(dir.path().to_str().unwrap(), "apples", 10);
+        assert_eq!(results[0].line_number, Some(5));
+        assert_eq!(results[0].end_line, Some(5));
+    }
+
+    #[test]
+    fn sources_and_extra_paths() {
+        let dir = setup_workspace();
+        let ws = dir.path().to_str().unwrap();
+        std::fs::create_dir_all(dir.path().join("notes")).unwrap();
+        std::fs::write(dir.path().join("notes/ideas.md"), "Build a telescope").unwrap();
+
+        let memory_only = MemorySearchOptions::from_search_config(&MemorySearchConfig {
+            sources: Some(vec!["memory".into(), "bogus".into()]),
+            extra_paths: Some(vec!["notes".into()]),
+            ..Default::default()
+        });
+        assert_eq!(memory_only.sources, vec![MemorySource::Memory]);
+        assert!(search_memory_with(ws, "Rust programming", 10, &memory_only).is_empty());
+        let hits = search_memory_with(ws, "telescope", 10, &memory_only);
+        assert_eq!(hits[0].file_path, "notes/ideas.md");
+    }
+
+    #[test]
+    fn read_span_within_sources() {
+        let dir = setup_workspace();
+        let ws = dir.path().to_str().unwrap();
+        let options = MemorySearchOptions::default();
+        let span = read_memory_span(ws, "memory/2024-01-01.md", 3, 3, &options).unwrap();
+        assert_eq!(span.start_line, 3);
+        assert_eq!(span.end_line, 5);
+        assert_eq!(span.total_lines, 7);
+        assert_eq!(span.text, "Frank went to the store.\n\nHe bought apples and oranges.");
+
+        let tail = read_memory_span(ws, "memory/2024-01-01.md", 7, 50, &options).unwrap();
+        assert_eq!(tail.text, "The weather was cold.");
+    }
+
+    #[test]
+    fn read_span_rejects_outside_paths() {
+        let dir = setup_workspace();
+        let ws = dir.path().to_str().unwrap();
+        let options = MemorySearchOptions::default();
+        assert!(matches!(
+            read_memory_span(ws, "AGENTS.md", 1, 10, &options),
+            Err(MemoryError::OutsideSources(_))
+        ));
+        assert!(matches!(
+            read_memory_span(ws, "memory/../AGENTS.md", 1, 10, &options),
+            Err(MemoryError::OutsideSources(_))
+        ));
+        assert!(matches!(
+            read_memory_span(ws, "memory/missing.md", 1, 10, &options),
+            Err(MemoryError::NotFound(_))
+        ));
+    }
+
     #[test]
     fn search_knowledge_dir() {
         let dir = setup_workspace();
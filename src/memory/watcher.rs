@@ -0,0 +1,271 @@
+use super::{MemoryError, MemoryIndex};
+use notify::{Config, Event, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Quiet period after the last change before re-indexing.
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_secs(2);
+/// Scan interval for the polling fallback.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Background re-indexer that watches the memory sources for changes.
+pub struct MemoryWatcher {
+    task: JoinHandle<()>,
+    mode: &'static str,
+}
+
+impl MemoryWatcher {
+    /// Watch with the platform's native notifications (inotify, FSEvents, ...),
+    /// falling back to polling when they are unavailable.
+    pub fn start(index: MemoryIndex, debounce: Duration) -> Result<Self, MemoryError> {
+        let roots = watch_roots(&index);
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        let native = RecommendedWatcher::new(event_sender(tx.clone()), Config::default())
+            .map_err(|e| e.to_string())
+            .and_then(|mut watcher| {
+                let missing = register(&mut watcher, &roots).map_err(|e| e.to_string())?;
+                Ok((watcher, missing))
+            });
+        match native {
+            Ok((watcher, missing)) => Ok(Self::spawn(index, Box::new(watcher), "native", roots, missing, rx, debounce)),
+            Err(e) => {
+                warn!("Native file watching unavailable ({}), polling memory files instead", e);
+                Self::start_polling_with(index, debounce, DEFAULT_POLL_INTERVAL, roots, tx, rx)
+            }
+        }
+    }
+
+    /// Watch by periodically scanning the memory sources.
+    pub fn start_polling(index: MemoryIndex, debounce: Duration, interval: Duration) -> Result<Self, MemoryError> {
+        let roots = watch_roots(&index);
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self::start_polling_with(index, debounce, interval, roots, tx, rx)
+    }
+
+    fn start_polling_with(
+        index: MemoryIndex,
+        debounce: Duration,
+        interval: Duration,
+        roots: Vec<PathBuf>,
+        tx: mpsc::UnboundedSender<PathBuf>,
+        rx: mpsc::UnboundedReceiver<PathBuf>,
+    ) -> Result<Self, MemoryError> {
+        let mut watcher = PollWatcher::new(event_sender(tx), Config::default().with_poll_interval(interval))
+            .map_err(|e| MemoryError::Index(e.to_string()))?;
+        let missing = register(&mut watcher, &roots).map_err(|e| MemoryError::Index(e.to_string()))?;
+        Ok(Self::spawn(index, Box::new(watcher), "poll", roots, missing, rx, debounce))
+    }
+
+    fn spawn(
+        index: MemoryIndex,
+        mut watcher: Box<dyn Watcher + Send>,
+        mode: &'static str,
+        roots: Vec<PathBuf>,
+        mut missing: Vec<PathBuf>,
+        mut rx: mpsc::UnboundedReceiver<PathBuf>,
+        debounce: Duration,
+    ) -> Self {
+        index.set_watcher(Some(mode));
+        info!("Watching memory files ({}): {} roots", mode, roots.len());
+
+        let task = tokio::spawn(async move {
+            if let Err(e) = index.sync().await {
+                warn!("Initial memory sync failed: {}", e);
+            }
+
+            while let Some(path) = rx.recv().await {
+                if !is_relevant(&path, &roots) {
+                    continue;
+                }
+                debug!("Memory change: {}", path.display());
+                // Roots missing at startup are only watched through their parent;
+                // watch them properly once they appear
+                missing.retain(|root| {
+                    if !root.is_dir() {
+                        return true;
+                    }
+                    match watcher.watch(root, RecursiveMode::Recursive) {
+                        Ok(()) => false,
+                        Err(e) => {
+                            warn!("Can't watch {}: {}", root.display(), e);
+                            true
+                        }
+                    }
+                });
+                // Debounce: wait until changes stop arriving
+                loop {
+                    match tokio::time::timeout(debounce, rx.recv()).await {
+                        Ok(Some(_)) => continue,
+                        Ok(None) => return,
+                        Err(_) => break,
+                    }
+                }
+                match index.sync().await {
+                    Ok(stats) => debug!("Memory re-indexed after change: {:?}", stats),
+                    Err(e) => warn!("Memory re-index failed: {}", e),
+                }
+            }
+        });
+
+        Self { task, mode }
+    }
+
+    /// "native" or "poll".
+    pub fn mode(&self) -> &'static str {
+        self.mode
+    }
+
+    pub fn stop(&self) {
+        self.task.abort();
+    }
+}
+
+impl Drop for MemoryWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn event_sender(tx: mpsc::UnboundedSender<PathBuf>) -> impl Fn(notify::Result<Event>) + Send + 'static {
+    move |event: notify::Result<Event>| match event {
+        Ok(event) if !event.kind.is_access() => {
+            for path in event.paths {
+                let _ = tx.send(path);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Memory watcher error: {}", e),
+    }
+}
+
+/// Memory roots, canonicalized (through their parent when they don't exist
+/// yet) so event paths compare equal.
+fn watch_roots(index: &MemoryIndex) -> Vec<PathBuf> {
+    index.options().roots(index.workspace_dir()).into_iter()
+        .map(|root| root.canonicalize().unwrap_or_else(|_| {
+            match (root.parent().and_then(|p| p.canonicalize().ok()), root.file_name()) {
+                (Some(parent), Some(name)) => parent.join(name),
+                _ => root,
+            }
+        }))
+        .collect()
+}
+
+/// Watch each root; a root that doesn't exist yet is covered by watching its
+/// parent so its creation is noticed. Returns the roots watched that way.
+fn register(watcher: &mut dyn Watcher, roots: &[PathBuf]) -> notify::Result<Vec<PathBuf>> {
+    let mut watched: Vec<PathBuf> = Vec::new();
+    let mut missing = Vec::new();
+    for root in roots {
+        if !root.exists() {
+            missing.push(root.clone());
+        }
+        let (target, mode) = if root.is_dir() {
+            (root.clone(), RecursiveMode::Recursive)
+        } else if root.exists() {
+            (root.clone(), RecursiveMode::NonRecursive)
+        } else {
+            match root.parent().filter(|p| p.is_dir()) {
+                Some(parent) => (parent.canonicalize().unwrap_or_else(|_| parent.to_path_buf()), RecursiveMode::NonRecursive),
+                None => continue,
+            }
+        };
+        if watched.contains(&target) {
+            continue;
+        }
+        watcher.watch(&target, mode)?;
+        watched.push(target);
+    }
+    Ok(missing)
+}
+
+fn is_relevant(path: &Path, roots: &[PathBuf]) -> bool {
+    roots.iter().any(|root| path.starts_with(root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::embedding::HashingEmbedder;
+    use crate::memory::MemorySearchOptions;
+    use std::sync::Arc;
+
+    fn index(dir: &tempfile::TempDir) -> MemoryIndex {
+        std::fs::create_dir_all(dir.path().join("memory")).unwrap();
+        std::fs::write(dir.path().join("memory/2026-04-01.md"), "First note").unwrap();
+        MemoryIndex::new(
+            dir.path().to_str().unwrap(),
+            MemorySearchOptions::default(),
+            Arc::new(HashingEmbedder::default()),
+        )
+    }
+
+    async fn wait_for_files(index: &MemoryIndex, expected: usize) -> bool {
+        for _ in 0..100 {
+            if index.status().await.files == expected {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        false
+    }
+
+    #[test]
+    fn relevance_filter() {
+        let roots = vec![PathBuf::from("/ws/memory"), PathBuf::from("/ws/MEMORY.md")];
+        assert!(is_relevant(Path::new("/ws/memory/2026-01-01.md"), &roots));
+        assert!(is_relevant(Path::new("/ws/MEMORY.md"), &roots));
+        assert!(!is_relevant(Path::new("/ws/AGENTS.md"), &roots));
+    }
+
+    #[tokio::test]
+    async fn native_watcher_reindexes_on_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = index(&dir);
+        let watcher = MemoryWatcher::start(index.clone(), Duration::from_millis(100)).unwrap();
+        assert!(wait_for_files(&index, 1).await);
+        assert_eq!(index.status().await.watcher.as_deref(), Some(watcher.mode()));
+
+        // MEMORY.md doesn't exist yet; its creation is picked up via the parent
+        std::fs::write(dir.path().join("MEMORY.md"), "Likes hiking").unwrap();
+        assert!(wait_for_files(&index, 2).await);
+        std::fs::write(dir.path().join("memory/2026-04-02.md"), "Second note").unwrap();
+        assert!(wait_for_files(&index, 3).await);
+    }
+
+    #[tokio::test]
+    async fn memory_dir_created_later_is_watched() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = MemoryIndex::new(
+            dir.path().to_str().unwrap(),
+            MemorySearchOptions::default(),
+            Arc::new(HashingEmbedder::default()),
+        );
+        let _watcher = MemoryWatcher::start(index.clone(), Duration::from_millis(100)).unwrap();
+        assert!(wait_for_files(&index, 0).await);
+
+        std::fs::create_dir(dir.path().join("memory")).unwrap();
+        std::fs::write(dir.path().join("memory/2026-04-01.md"), "First note").unwrap();
+        assert!(wait_for_files(&index, 1).await);
+        // Only seen with a recursive watch on the new directory
+        std::fs::write(dir.path().join("memory/2026-04-02.md"), "Second note").unwrap();
+        assert!(wait_for_files(&index, 2).await);
+    }
+
+    #[tokio::test]
+    async fn polling_watcher_reindexes_on_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = index(&dir);
+        let watcher = MemoryWatcher::start_polling(index.clone(), Duration::from_millis(50), Duration::from_millis(100)).unwrap();
+        assert_eq!(watcher.mode(), "poll");
+        assert!(wait_for_files(&index, 1).await);
+
+        std::fs::remove_file(dir.path().join("memory/2026-04-01.md")).unwrap();
+        assert!(wait_for_files(&index, 0).await);
+        assert_eq!(index.status().await.watcher.as_deref(), Some("poll"));
+    }
+}
@@ -1,5 +1,11 @@
+pub mod service;
+
+use crate::channel::ChannelCapabilities;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+pub use service::{PollRecord, PollResults, PollService, VoteOutcome};
+
 #[derive(Error, Debug)]
 pub enum PollError {
     #[error("Poll question is required")]
@@ -18,9 +24,20 @@ pub enum PollError {
     MinDurationHours,
     #[error("durationSeconds and durationHours are mutually exclusive")]
     MutuallyExclusiveDuration,
+    #[error("Poll not found: {0}")]
+    NotFound(String),
+    #[error("Poll is closed")]
+    Closed,
+    #[error("Invalid vote: {0}")]
+    InvalidVote(String),
+    #[error("Poll delivery failed: {0}")]
+    Delivery(String),
+    #[error("Poll store error: {0}")]
+    Store(String),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct PollInput {
     pub question: String,
     pub options: Vec<String>,
@@ -29,7 +46,8 @@ pub struct PollInput {
     pub duration_hours: Option<u32>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct NormalizedPollInput {
     pub question: String,
     pub options: Vec<String>,
@@ -38,13 +56,18 @@ pub struct NormalizedPollInput {
     pub duration_hours: Option<u32>,
 }
 
+#[derive(Default)]
 pub struct NormalizePollOptions {
     pub max_options: Option<usize>,
 }
 
-impl Default for NormalizePollOptions {
-    fn default() -> Self {
-        Self { max_options: None }
+impl NormalizePollOptions {
+    /// Limits for a poll sent to a channel. Native polls use the channel's
+    /// option cap; text fallbacks are unbounded.
+    pub fn for_channel(caps: &ChannelCapabilities) -> Self {
+        Self {
+            max_options: if caps.polls { caps.max_poll_options } else { None },
+        }
     }
 }
 
@@ -154,6 +177,22 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("at most 2"));
     }
 
+    #[test]
+    fn channel_limits_apply_only_to_native_polls() {
+        let input = PollInput {
+            question: "Q".to_string(),
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            max_selections: None,
+            duration_seconds: None,
+            duration_hours: None,
+        };
+        let native = ChannelCapabilities { polls: true, max_poll_options: Some(2), ..Default::default() };
+        assert!(normalize_poll_input(&input, &NormalizePollOptions::for_channel(&native)).is_err());
+
+        let text_only = ChannelCapabilities { max_poll_options: Some(2), ..Default::default() };
+        assert!(normalize_poll_input(&input, &NormalizePollOptions::for_channel(&text_only)).is_ok());
+    }
+
     #[test]
     fn clamps_poll_duration_with_defaults() {
         assert_eq!(normalize_poll_duration_hours(None, 24, 48), 24);
@@ -0,0 +1,540 @@
+use super::{normalize_poll_input, NormalizePollOptions, PollError, PollInput};
+use crate::channel::{ChannelManager, IncomingMessage, OutboundAction, OutgoingMessage};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+/// A poll sent through a channel, with the votes collected so far.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PollRecord {
+    pub id: String,
+    pub channel: String,
+    pub chat_id: String,
+    pub message_ids: Vec<String>,
+    pub question: String,
+    pub options: Vec<String>,
+    pub max_selections: u32,
+    /// False when the channel has no native polls and a numbered list was sent instead.
+    pub native: bool,
+    pub created_at: DateTime<Utc>,
+    pub closes_at: Option<DateTime<Utc>>,
+    pub closed: bool,
+    /// Voter → selected option indexes (0-based). A new vote replaces the previous one.
+    #[serde(default)]
+    pub votes: HashMap<String, Vec<usize>>,
+}
+
+impl PollRecord {
+    /// Check if the poll's duration has elapsed.
+    pub fn is_due(&self, now: &DateTime<Utc>) -> bool {
+        !self.closed && self.closes_at.is_some_and(|closes_at| *now >= closes_at)
+    }
+
+    /// Tally the votes per option.
+    pub fn results(&self) -> PollResults {
+        let mut counts = vec![0usize; self.options.len()];
+        for selections in self.votes.values() {
+            for idx in selections {
+                if let Some(count) = counts.get_mut(*idx) {
+                    *count += 1;
+                }
+            }
+        }
+        PollResults {
+            poll_id: self.id.clone(),
+            question: self.question.clone(),
+            options: self.options.iter().zip(counts)
+                .map(|(option, votes)| OptionTally { option: option.clone(), votes })
+                .collect(),
+            voters: self.votes.len(),
+            closed: self.closed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionTally {
+    pub option: String,
+    pub votes: usize,
+}
+
+/// Vote counts for a poll.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PollResults {
+    pub poll_id: String,
+    pub question: String,
+    pub options: Vec<OptionTally>,
+    pub voters: usize,
+    pub closed: bool,
+}
+
+impl PollResults {
+    /// Human/model readable summary.
+    pub fn to_text(&self) -> String {
+        let status = if self.closed { "closed" } else { "open" };
+        let mut lines = vec![format!("📊 {} ({}, {} voters)", self.question, status, self.voters)];
+        for (idx, tally) in self.options.iter().enumerate() {
+            lines.push(format!("{}. {} — {}", idx + 1, tally.option, tally.votes));
+        }
+        lines.join("\n")
+    }
+}
+
+/// A vote that was accepted.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VoteOutcome {
+    pub poll_id: String,
+    pub voter: String,
+    pub selections: Vec<usize>,
+}
+
+/// Parse a reply like "2", "1, 3" or "1 3" into 0-based option indexes.
+pub fn parse_vote_reply(text: &str) -> Option<Vec<usize>> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    let mut selections = Vec::new();
+    for part in trimmed.split(|c: char| c == ',' || c.is_whitespace()).filter(|p| !p.is_empty()) {
+        let n: usize = part.trim_end_matches('.').parse().ok()?;
+        if n == 0 {
+            return None;
+        }
+        if !selections.contains(&(n - 1)) {
+            selections.push(n - 1);
+        }
+    }
+    if selections.is_empty() { None } else { Some(selections) }
+}
+
+/// The poll service sends polls, collects votes and closes expired polls.
+#[derive(Clone)]
+pub struct PollService {
+    polls: Arc<RwLock<HashMap<String, PollRecord>>>,
+    store_path: Option<PathBuf>,
+    running: Arc<RwLock<bool>>,
+}
+
+impl PollService {
+    pub fn new() -> Self {
+        Self {
+            polls: Arc::new(RwLock::new(HashMap::new())),
+            store_path: None,
+            running: Arc::new(RwLock::new(false)),
+        }
+    }
+
+    /// Persist polls to a JSON file, loading any polls already stored there.
+    pub fn with_store_path(mut self, path: PathBuf) -> Self {
+        match std::fs::read_to_string(&path) {
+            Ok(raw) => match serde_json::from_str::<Vec<PollRecord>>(&raw) {
+                Ok(records) => {
+                    self.polls = Arc::new(RwLock::new(
+                        records.into_iter().map(|p| (p.id.clone(), p)).collect(),
+                    ));
+                }
+                Err(e) => warn!("Ignoring unreadable poll store {}: {}", path.display(), e),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Failed to read poll store {}: {}", path.display(), e),
+        }
+        self.store_path = Some(path);
+        self
+    }
+
+    fn persist(&self, polls: &HashMap<String, PollRecord>) {
+        let Some(path) = &self.store_path else { return };
+        let records: Vec<&PollRecord> = polls.values().collect();
+        let result = serde_json::to_string_pretty(&records)
+            .map_err(|e| PollError::Store(e.to_string()))
+            .and_then(|json| {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent).map_err(|e| PollError::Store(e.to_string()))?;
+                }
+                std::fs::write(path, json).map_err(|e| PollError::Store(e.to_string()))
+            });
+        if let Err(e) = result {
+            warn!("{}", e);
+        }
+    }
+
+    /// Send a poll through a channel. Channels without native polls get a
+    /// numbered list and collect votes from replies.
+    pub async fn create(
+        &self,
+        channels: &ChannelManager,
+        channel: &str,
+        to: &str,
+        input: &PollInput,
+    ) -> Result<PollRecord, PollError> {
+        let caps = channels.capabilities(channel)
+            .ok_or_else(|| PollError::Delivery(format!("No plugin for channel: {}", channel)))?;
+        let normalized = normalize_poll_input(input, &NormalizePollOptions::for_channel(&caps))?;
+
+        let outcome = channels.dispatch(&OutboundAction::Poll {
+            channel: channel.to_string(),
+            to: to.to_string(),
+            poll: input.clone(),
+        }).await.map_err(|e| PollError::Delivery(e.to_string()))?;
+
+        let now = Utc::now();
+        let duration = normalized.duration_seconds.map(|s| chrono::Duration::seconds(s as i64))
+            .or_else(|| normalized.duration_hours.map(|h| chrono::Duration::hours(h as i64)));
+
+        let record = PollRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            channel: channel.to_string(),
+            chat_id: to.to_string(),
+            message_ids: outcome.message_ids,
+            question: normalized.question,
+            options: normalized.options,
+            max_selections: normalized.max_selections,
+            native: outcome.fallback.is_none(),
+            created_at: now,
+            closes_at: duration.map(|d| now + d),
+            closed: false,
+            votes: HashMap::new(),
+        };
+
+        let mut polls = self.polls.write().await;
+        polls.insert(record.id.clone(), record.clone());
+        self.persist(&polls);
+        info!("Poll {} sent to {}:{} (native: {})", record.id, channel, to, record.native);
+        Ok(record)
+    }
+
+    /// Get a poll by ID.
+    pub async fn get(&self, id: &str) -> Option<PollRecord> {
+        let polls = self.polls.read().await;
+        polls.get(id).cloned()
+    }
+
+    /// List all polls, newest first.
+    pub async fn list(&self) -> Vec<PollRecord> {
+        let polls = self.polls.read().await;
+        let mut list: Vec<PollRecord> = polls.values().cloned().collect();
+        list.sort_by_key(|p| std::cmp::Reverse(p.created_at));
+        list
+    }
+
+    /// Find the poll a channel message id belongs to (for native poll updates).
+    pub async fn find_by_message_id(&self, message_id: &str) -> Option<PollRecord> {
+        let polls = self.polls.read().await;
+        polls.values().find(|p| p.message_ids.iter().any(|m| m == message_id)).cloned()
+    }
+
+    /// Record a voter's selections (0-based). Empty selections retract the vote.
+    pub async fn record_vote(
+        &self,
+        poll_id: &str,
+        voter: &str,
+        selections: Vec<usize>,
+    ) -> Result<VoteOutcome, PollError> {
+        let mut polls = self.polls.write().await;
+        let poll = polls.get_mut(poll_id).ok_or_else(|| PollError::NotFound(poll_id.to_string()))?;
+        if poll.closed {
+            return Err(PollError::Closed);
+        }
+
+        let mut unique: Vec<usize> = Vec::new();
+        for idx in selections {
+            if idx >= poll.options.len() {
+                return Err(PollError::InvalidVote(format!(
+                    "option {} does not exist (poll has {} options)",
+                    idx + 1,
+                    poll.options.len()
+                )));
+            }
+            if !unique.contains(&idx) {
+                unique.push(idx);
+            }
+        }
+        if unique.len() > poll.max_selections as usize {
+            return Err(PollError::InvalidVote(format!(
+                "at most {} selections allowed",
+                poll.max_selections
+            )));
+        }
+
+        if unique.is_empty() {
+            poll.votes.remove(voter);
+        } else {
+            poll.votes.insert(voter.to_string(), unique.clone());
+        }
+        self.persist(&polls);
+
+        Ok(VoteOutcome {
+            poll_id: poll_id.to_string(),
+            voter: voter.to_string(),
+            selections: unique,
+        })
+    }
+
+    /// Treat an incoming message as a vote if it answers an open text-fallback
+    /// poll in the same chat: either it quotes the poll message, or it is
+    /// unquoted and the newest open poll in the chat still awaits the
+    /// sender's answer. Other bare numbers are ordinary chat.
+    pub async fn handle_incoming(&self, msg: &IncomingMessage) -> Option<VoteOutcome> {
+        let selections = parse_vote_reply(&msg.text)?;
+
+        let poll_id = {
+            let polls = self.polls.read().await;
+            let mut candidates: Vec<&PollRecord> = polls.values()
+                .filter(|p| !p.closed && !p.native && p.channel == msg.channel && p.chat_id == msg.chat_id)
+                .collect();
+            candidates.sort_by_key(|p| std::cmp::Reverse(p.created_at));
+            let poll = match &msg.reply_to {
+                Some(reply_to) => candidates.into_iter().find(|p| p.message_ids.contains(reply_to))?,
+                None => candidates.into_iter().next().filter(|p| !p.votes.contains_key(&msg.from))?,
+            };
+            poll.id.clone()
+        };
+
+        match self.record_vote(&poll_id, &msg.from, selections).await {
+            Ok(outcome) => Some(outcome),
+            Err(e) => {
+                warn!("Ignoring vote from {} on poll {}: {}", msg.from, poll_id, e);
+                None
+            }
+        }
+    }
+
+    /// Current results for a poll.
+    pub async fn results(&self, poll_id: &str) -> Result<PollResults, PollError> {
+        let polls = self.polls.read().await;
+        polls.get(poll_id)
+            .map(|p| p.results())
+            .ok_or_else(|| PollError::NotFound(poll_id.to_string()))
+    }
+
+    /// Close a poll so no further votes are accepted.
+    pub async fn close(&self, poll_id: &str) -> Result<PollRecord, PollError> {
+        let mut polls = self.polls.write().await;
+        let poll = polls.get_mut(poll_id).ok_or_else(|| PollError::NotFound(poll_id.to_string()))?;
+        poll.closed = true;
+        let closed = poll.clone();
+        self.persist(&polls);
+        Ok(closed)
+    }
+
+    /// Close every poll whose duration has elapsed and return them.
+    pub async fn close_due(&self, now: &DateTime<Utc>) -> Vec<PollRecord> {
+        let mut polls = self.polls.write().await;
+        let mut closed = Vec::new();
+        for poll in polls.values_mut() {
+            if poll.is_due(now) {
+                poll.closed = true;
+                closed.push(poll.clone());
+            }
+        }
+        if !closed.is_empty() {
+            self.persist(&polls);
+        }
+        closed
+    }
+
+    /// Start the loop that closes expired polls. Text-fallback polls get
+    /// their final tally posted back to the chat.
+    pub async fn start(&self, channels: Arc<RwLock<ChannelManager>>) {
+        {
+            let mut running = self.running.write().await;
+            if *running {
+                return;
+            }
+            *running = true;
+        }
+
+        let service = self.clone();
+        tokio::spawn(async move {
+            info!("Poll service started");
+            loop {
+                if !*service.running.read().await {
+                    break;
+                }
+
+                tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+
+                for poll in service.close_due(&Utc::now()).await {
+                    info!("Poll closed: {} ({})", poll.question, poll.id);
+                    if poll.native {
+                        continue;
+                    }
+                    let summary = format!("Poll closed.\n{}", poll.results().to_text());
+                    let msg = OutgoingMessage::text(&poll.channel, &poll.chat_id, &summary);
+                    if let Err(e) = channels.read().await.send(&msg).await {
+                        warn!("Failed to post results for poll {}: {}", poll.id, e);
+                    }
+                }
+            }
+            info!("Poll service stopped");
+        });
+    }
+
+    /// Stop the poll service.
+    pub async fn stop(&self) {
+        let mut running = self.running.write().await;
+        *running = false;
+    }
+}
+
+impl Default for PollService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::mock::MockChannel;
+    use crate::channel::ChannelCapabilities;
+
+    fn lunch_poll() -> PollInput {
+        PollInput {
+            question: "Lunch?".into(),
+            options: vec!["Pizza".into(), "Sushi".into(), "Tacos".into()],
+            max_selections: Some(2),
+            duration_seconds: Some(60),
+            duration_hours: None,
+        }
+    }
+
+    fn manager_with(name: &str, caps: ChannelCapabilities) -> (ChannelManager, MockChannel) {
+        let channel = MockChannel::new(name, caps);
+        let mut mgr = ChannelManager::new();
+        mgr.register(Box::new(channel.clone()));
+        (mgr, channel)
+    }
+
+    fn reply(text: &str, from: &str, reply_to: Option<&str>) -> IncomingMessage {
+        IncomingMessage {
+            id: "in-1".into(),
+            channel: "plain".into(),
+            from: from.into(),
+            sender_name: None,
+            chat_id: "group@g.us".into(),
+            text: text.into(),
+            timestamp: 0,
+            is_group: true,
+            mentions_bot: false,
+            reply_to: reply_to.map(String::from),
+            media: None,
+        }
+    }
+
+    #[test]
+    fn parses_vote_replies() {
+        assert_eq!(parse_vote_reply("2"), Some(vec![1]));
+        assert_eq!(parse_vote_reply(" 1, 3 "), Some(vec![0, 2]));
+        assert_eq!(parse_vote_reply("1 1"), Some(vec![0]));
+        assert_eq!(parse_vote_reply("0"), None);
+        assert_eq!(parse_vote_reply("pizza please"), None);
+    }
+
+    #[tokio::test]
+    async fn native_poll_records_votes() {
+        let caps = ChannelCapabilities { polls: true, max_poll_options: Some(12), ..Default::default() };
+        let (mgr, channel) = manager_with("wa", caps);
+        let svc = PollService::new();
+
+        let poll = svc.create(&mgr, "wa", "group@g.us", &lunch_poll()).await.unwrap();
+        assert!(poll.native);
+        assert_eq!(channel.polls.lock().unwrap().len(), 1);
+        assert!(poll.closes_at.is_some());
+
+        svc.record_vote(&poll.id, "+1", vec![0, 1]).await.unwrap();
+        svc.record_vote(&poll.id, "+2", vec![1]).await.unwrap();
+        // Re-voting replaces the earlier vote
+        svc.record_vote(&poll.id, "+1", vec![2]).await.unwrap();
+
+        let results = svc.results(&poll.id).await.unwrap();
+        assert_eq!(results.voters, 2);
+        let counts: Vec<usize> = results.options.iter().map(|o| o.votes).collect();
+        assert_eq!(counts, vec![0, 1, 1]);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_votes() {
+        let (mgr, _) = manager_with("plain", ChannelCapabilities::default());
+        let svc = PollService::new();
+        let poll = svc.create(&mgr, "plain", "group@g.us", &lunch_poll()).await.unwrap();
+
+        assert!(matches!(svc.record_vote(&poll.id, "+1", vec![5]).await, Err(PollError::InvalidVote(_))));
+        assert!(matches!(svc.record_vote(&poll.id, "+1", vec![0, 1, 2]).await, Err(PollError::InvalidVote(_))));
+        assert!(matches!(svc.record_vote("missing", "+1", vec![0]).await, Err(PollError::NotFound(_))));
+
+        svc.close(&poll.id).await.unwrap();
+        assert!(matches!(svc.record_vote(&poll.id, "+1", vec![0]).await, Err(PollError::Closed)));
+    }
+
+    #[tokio::test]
+    async fn text_fallback_collects_reply_votes() {
+        let (mgr, channel) = manager_with("plain", ChannelCapabilities::default());
+        let svc = PollService::new();
+        let poll = svc.create(&mgr, "plain", "group@g.us", &lunch_poll()).await.unwrap();
+        assert!(!poll.native);
+        assert!(channel.sent.lock().unwrap()[0].text.contains("1. Pizza"));
+
+        let vote = svc.handle_incoming(&reply("1, 3", "+1", None)).await.unwrap();
+        assert_eq!(vote.selections, vec![0, 2]);
+        assert!(svc.handle_incoming(&reply("not a vote", "+2", None)).await.is_none());
+        // Once answered, bare numbers are chat; changing a vote means quoting the poll
+        assert!(svc.handle_incoming(&reply("2", "+1", None)).await.is_none());
+        assert!(svc.handle_incoming(&reply("2", "+1", Some("other-message"))).await.is_none());
+
+        let results = svc.results(&poll.id).await.unwrap();
+        assert_eq!(results.options[0].votes, 1);
+        assert_eq!(results.options[2].votes, 1);
+
+        let changed = svc.handle_incoming(&reply("2", "+1", Some(&poll.message_ids[0]))).await.unwrap();
+        assert_eq!(changed.selections, vec![1]);
+    }
+
+    #[tokio::test]
+    async fn reply_to_selects_the_right_poll() {
+        let (mgr, _) = manager_with("plain", ChannelCapabilities::default());
+        let svc = PollService::new();
+        let first = svc.create(&mgr, "plain", "group@g.us", &lunch_poll()).await.unwrap();
+        let second = svc.create(&mgr, "plain", "group@g.us", &lunch_poll()).await.unwrap();
+
+        let vote = svc.handle_incoming(&reply("2", "+1", Some(&first.message_ids[0]))).await.unwrap();
+        assert_eq!(vote.poll_id, first.id);
+        assert_eq!(svc.results(&second.id).await.unwrap().voters, 0);
+    }
+
+    #[tokio::test]
+    async fn close_due_closes_expired_polls() {
+        let (mgr, _) = manager_with("plain", ChannelCapabilities::default());
+        let svc = PollService::new();
+        let poll = svc.create(&mgr, "plain", "group@g.us", &lunch_poll()).await.unwrap();
+
+        assert!(svc.close_due(&Utc::now()).await.is_empty());
+        let later = Utc::now() + chrono::Duration::seconds(61);
+        let closed = svc.close_due(&later).await;
+        assert_eq!(closed.len(), 1);
+        assert!(svc.get(&poll.id).await.unwrap().closed);
+    }
+
+    #[tokio::test]
+    async fn store_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("polls.json");
+        let (mgr, _) = manager_with("plain", ChannelCapabilities::default());
+
+        let svc = PollService::new().with_store_path(path.clone());
+        let poll = svc.create(&mgr, "plain", "group@g.us", &lunch_poll()).await.unwrap();
+        svc.record_vote(&poll.id, "+1", vec![1]).await.unwrap();
+
+        let reloaded = PollService::new().with_store_path(path);
+        let results = reloaded.results(&poll.id).await.unwrap();
+        assert_eq!(results.options[1].votes, 1);
+    }
+}
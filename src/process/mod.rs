@@ -0,0 +1,434 @@
+//! Background processes started by `exec`: output kept in a bounded buffer
+//! the agent polls, stdin it can write to, and signals to stop them.
+
+use crate::config::OpenClawConfig;
+use crate::tools::sandbox::{signal_group, SandboxPolicy};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::ChildStdin;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{info, warn};
+
+/// Running processes allowed at once when `tools.exec.maxProcesses` is unset.
+pub const DEFAULT_MAX_PROCESSES: usize = 8;
+/// Output kept per process when `tools.exec.processBufferBytes` is unset.
+pub const DEFAULT_BUFFER_BYTES: usize = 256 * 1024;
+/// Finished processes kept for polling.
+const MAX_FINISHED: usize = 32;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProcessError {
+    #[error("Process not found: {0}")]
+    NotFound(String),
+    #[error("Process {0} is not running")]
+    NotRunning(String),
+    #[error("Process {0} is still running")]
+    StillRunning(String),
+    #[error("Too many background processes (max {0}); kill one first")]
+    LimitReached(usize),
+    #[error("Unknown signal: {0}")]
+    UnknownSignal(String),
+    #[error("Process {0} has no stdin (closed)")]
+    StdinClosed(String),
+    #[error("Process I/O failed: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessStatus {
+    Running,
+    /// Exited on its own; see `exitCode`.
+    Exited,
+    /// Ended by a signal.
+    Killed,
+}
+
+/// A background process as reported to callers.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessInfo {
+    pub id: String,
+    pub command: String,
+    pub workdir: String,
+    pub pid: Option<u32>,
+    /// Session whose turn started the process.
+    pub session_key: Option<String>,
+    pub status: ProcessStatus,
+    pub exit_code: Option<i32>,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Bytes of output written so far, including any no longer buffered.
+    pub output_bytes: u64,
+}
+
+/// Output read by a poll.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessOutput {
+    pub output: String,
+    /// Offset to poll from next.
+    pub offset: u64,
+    /// Output between the requested offset and the buffer start was dropped.
+    pub dropped: u64,
+    pub process: ProcessInfo,
+}
+
+/// Output of one process, keeping the last `cap` bytes. Offsets count every
+/// byte ever written, so readers can tell what they missed.
+#[derive(Debug)]
+struct RingBuffer {
+    data: VecDeque<u8>,
+    cap: usize,
+    total: u64,
+}
+
+impl RingBuffer {
+    fn new(cap: usize) -> Self {
+        Self { data: VecDeque::new(), cap, total: 0 }
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        self.total += bytes.len() as u64;
+        self.data.extend(bytes);
+        let excess = self.data.len().saturating_sub(self.cap);
+        self.data.drain(..excess);
+    }
+
+    fn start(&self) -> u64 {
+        self.total - self.data.len() as u64
+    }
+
+    /// Bytes from `offset` on, and how many before them were dropped.
+    fn read_from(&self, offset: u64) -> (Vec<u8>, u64) {
+        let start = self.start();
+        let from = offset.clamp(start, self.total);
+        let skip = (from - start) as usize;
+        (self.data.iter().skip(skip).copied().collect(), start.saturating_sub(offset))
+    }
+}
+
+struct Entry {
+    info: ProcessInfo,
+    output: RingBuffer,
+    /// Where the last poll without an offset stopped.
+    cursor: u64,
+    stdin: Arc<Mutex<Option<ChildStdin>>>,
+}
+
+/// Starts and tracks background processes.
+#[derive(Clone)]
+pub struct ProcessManager {
+    processes: Arc<RwLock<HashMap<String, Entry>>>,
+    max_running: usize,
+    buffer_bytes: usize,
+}
+
+impl Default for ProcessManager {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_PROCESSES, DEFAULT_BUFFER_BYTES)
+    }
+}
+
+impl ProcessManager {
+    pub fn new(max_running: usize, buffer_bytes: usize) -> Self {
+        Self {
+            processes: Arc::new(RwLock::new(HashMap::new())),
+            max_running: max_running.max(1),
+            buffer_bytes: buffer_bytes.max(1),
+        }
+    }
+
+    /// Limits from `tools.exec`.
+    pub fn from_config(config: &OpenClawConfig) -> Self {
+        let exec = config.tools.as_ref().and_then(|t| t.exec.as_ref());
+        Self::new(
+            exec.and_then(|e| e.max_processes).unwrap_or(DEFAULT_MAX_PROCESSES),
+            exec.and_then(|e| e.process_buffer_bytes).unwrap_or(DEFAULT_BUFFER_BYTES),
+        )
+    }
+
+    /// Start `command` in the background under `sandbox`.
+    pub async fn start(
+        &self,
+        command: &str,
+        workdir: &str,
+        workspace: &str,
+        sandbox: &SandboxPolicy,
+        session_key: Option<&str>,
+    ) -> Result<ProcessInfo, ProcessError> {
+        let mut processes = self.processes.write().await;
+        if processes.values().filter(|e| e.info.status == ProcessStatus::Running).count() >= self.max_running {
+            return Err(ProcessError::LimitReached(self.max_running));
+        }
+        prune(&mut processes);
+
+        let mut child = sandbox.spawn(command, workdir, workspace)?;
+        let id = uuid::Uuid::new_v4().to_string()[..8].to_string();
+        let info = ProcessInfo {
+            id: id.clone(),
+            command: command.to_string(),
+            workdir: workdir.to_string(),
+            pid: child.id(),
+            session_key: session_key.map(str::to_string),
+            status: ProcessStatus::Running,
+            exit_code: None,
+            started_at: Utc::now(),
+            ended_at: None,
+            output_bytes: 0,
+        };
+        let readers = [
+            child.stdout.take().map(|s| tokio::spawn(self.clone().pump(id.clone(), s))),
+            child.stderr.take().map(|s| tokio::spawn(self.clone().pump(id.clone(), s))),
+        ];
+        processes.insert(id.clone(), Entry {
+            info: info.clone(),
+            output: RingBuffer::new(self.buffer_bytes),
+            cursor: 0,
+            stdin: Arc::new(Mutex::new(child.stdin.take())),
+        });
+        drop(processes);
+        info!("Started background process {} (pid {:?}): {}", id, info.pid, command);
+
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let status = child.wait().await;
+            // Let the readers drain what the process wrote before it exited
+            for reader in readers.into_iter().flatten() {
+                let _ = reader.await;
+            }
+            let mut processes = manager.processes.write().await;
+            let Some(entry) = processes.get_mut(&id) else { return };
+            entry.info.ended_at = Some(Utc::now());
+            match status {
+                Ok(status) if status.code().is_some() => {
+                    entry.info.status = ProcessStatus::Exited;
+                    entry.info.exit_code = status.code();
+                }
+                Ok(_) => entry.info.status = ProcessStatus::Killed,
+                Err(e) => {
+                    warn!("Waiting for process {} failed: {}", id, e);
+                    entry.info.status = ProcessStatus::Killed;
+                }
+            }
+            let stdin = entry.stdin.clone();
+            drop(processes);
+            stdin.lock().await.take();
+        });
+        Ok(info)
+    }
+
+    /// Copy one output stream into the process's buffer until it closes.
+    async fn pump(self, id: String, mut stream: impl AsyncRead + Unpin) {
+        let mut buf = [0u8; 8192];
+        loop {
+            match stream.read(&mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    let mut processes = self.processes.write().await;
+                    let Some(entry) = processes.get_mut(&id) else { break };
+                    entry.output.push(&buf[..n]);
+                    entry.info.output_bytes = entry.output.total;
+                }
+            }
+        }
+    }
+
+    pub async fn get(&self, id: &str) -> Option<ProcessInfo> {
+        self.processes.read().await.get(id).map(|e| e.info.clone())
+    }
+
+    /// All processes, oldest first.
+    pub async fn list(&self) -> Vec<ProcessInfo> {
+        let mut list: Vec<ProcessInfo> = self.processes.read().await.values().map(|e| e.info.clone()).collect();
+        list.sort_by_key(|p| p.started_at);
+        list
+    }
+
+    /// Output from `offset`, or from where the last such poll stopped.
+    pub async fn poll(&self, id: &str, offset: Option<u64>) -> Result<ProcessOutput, ProcessError> {
+        let mut processes = self.processes.write().await;
+        let entry = processes.get_mut(id).ok_or_else(|| ProcessError::NotFound(id.to_string()))?;
+        let (bytes, dropped) = entry.output.read_from(offset.unwrap_or(entry.cursor));
+        if offset.is_none() {
+            entry.cursor = entry.output.total;
+        }
+        Ok(ProcessOutput {
+            output: String::from_utf8_lossy(&bytes).into_owned(),
+            offset: entry.output.total,
+            dropped,
+            process: entry.info.clone(),
+        })
+    }
+
+    /// Write `data` to the process's stdin, closing it afterwards if `eof`.
+    pub async fn write(&self, id: &str, data: &str, eof: bool) -> Result<(), ProcessError> {
+        let stdin = {
+            let processes = self.processes.read().await;
+            let entry = processes.get(id).ok_or_else(|| ProcessError::NotFound(id.to_string()))?;
+            if entry.info.status != ProcessStatus::Running {
+                return Err(ProcessError::NotRunning(id.to_string()));
+            }
+            entry.stdin.clone()
+        };
+        let mut stdin = stdin.lock().await;
+        let pipe = stdin.as_mut().ok_or_else(|| ProcessError::StdinClosed(id.to_string()))?;
+        pipe.write_all(data.as_bytes()).await?;
+        pipe.flush().await?;
+        if eof {
+            stdin.take();
+        }
+        Ok(())
+    }
+
+    /// Send a signal ("TERM", "SIGINT", "9", ...) to the process and its children.
+    pub async fn signal(&self, id: &str, signal: &str) -> Result<(), ProcessError> {
+        let signo = parse_signal(signal).ok_or_else(|| ProcessError::UnknownSignal(signal.to_string()))?;
+        let processes = self.processes.read().await;
+        let entry = processes.get(id).ok_or_else(|| ProcessError::NotFound(id.to_string()))?;
+        match (entry.info.status, entry.info.pid) {
+            (ProcessStatus::Running, Some(pid)) => Ok(signal_group(pid, signo)?),
+            _ => Err(ProcessError::NotRunning(id.to_string())),
+        }
+    }
+
+    pub async fn kill(&self, id: &str) -> Result<(), ProcessError> {
+        self.signal(id, "KILL").await
+    }
+
+    /// Forget a finished process and its output.
+    pub async fn remove(&self, id: &str) -> Result<ProcessInfo, ProcessError> {
+        let mut processes = self.processes.write().await;
+        match processes.get(id).map(|e| e.info.status) {
+            None => Err(ProcessError::NotFound(id.to_string())),
+            Some(ProcessStatus::Running) => Err(ProcessError::StillRunning(id.to_string())),
+            Some(_) => Ok(processes.remove(id).map(|e| e.info).expect("checked above")),
+        }
+    }
+
+    /// Kill every running process; called when the gateway stops.
+    pub async fn shutdown(&self) {
+        let processes = self.processes.read().await;
+        for entry in processes.values().filter(|e| e.info.status == ProcessStatus::Running) {
+            if let Some(pid) = entry.info.pid {
+                info!("Killing background process {} on shutdown", entry.info.id);
+                let _ = signal_group(pid, libc::SIGKILL);
+            }
+        }
+    }
+}
+
+/// Drop the oldest finished processes beyond `MAX_FINISHED`.
+fn prune(processes: &mut HashMap<String, Entry>) {
+    let mut finished: Vec<(DateTime<Utc>, String)> = processes.values()
+        .filter(|e| e.info.status != ProcessStatus::Running)
+        .map(|e| (e.info.started_at, e.info.id.clone()))
+        .collect();
+    if finished.len() <= MAX_FINISHED {
+        return;
+    }
+    finished.sort();
+    for (_, id) in &finished[..finished.len() - MAX_FINISHED] {
+        processes.remove(id);
+    }
+}
+
+/// Signal number for a name ("TERM", "SIGTERM", "term") or number.
+pub fn parse_signal(signal: &str) -> Option<libc::c_int> {
+    let name = signal.trim().to_ascii_uppercase();
+    if let Ok(n) = name.parse::<libc::c_int>() {
+        return (n > 0 && n < 65).then_some(n);
+    }
+    Some(match name.strip_prefix("SIG").unwrap_or(&name) {
+        "HUP" => libc::SIGHUP,
+        "INT" => libc::SIGINT,
+        "QUIT" => libc::SIGQUIT,
+        "KILL" => libc::SIGKILL,
+        "USR1" => libc::SIGUSR1,
+        "USR2" => libc::SIGUSR2,
+        "TERM" => libc::SIGTERM,
+        "CONT" => libc::SIGCONT,
+        "STOP" => libc::SIGSTOP,
+        "TSTP" => libc::SIGTSTP,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    async fn wait_for(manager: &ProcessManager, id: &str, status: ProcessStatus) -> ProcessInfo {
+        for _ in 0..100 {
+            let info = manager.get(id).await.unwrap();
+            if info.status == status {
+                return info;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("process {} never became {:?}", id, status);
+    }
+
+    #[test]
+    fn ring_buffer_keeps_the_tail() {
+        let mut buf = RingBuffer::new(4);
+        buf.push(b"abc");
+        buf.push(b"defg");
+        assert_eq!((buf.start(), buf.total), (3, 7));
+        assert_eq!(buf.read_from(0), (b"defg".to_vec(), 3));
+        assert_eq!(buf.read_from(5), (b"fg".to_vec(), 0));
+        assert_eq!(buf.read_from(99), (Vec::new(), 0));
+        assert_eq!(parse_signal("sigterm"), Some(libc::SIGTERM));
+        assert_eq!(parse_signal("9"), Some(9));
+        assert_eq!(parse_signal("BOOM"), None);
+    }
+
+    #[tokio::test]
+    async fn polls_output_and_writes_stdin() {
+        let dir = tempfile::tempdir().unwrap();
+        let ws = dir.path().to_str().unwrap();
+        let manager = ProcessManager::default();
+        let info = manager.start("echo ready; read line; echo got $line", ws, ws, &SandboxPolicy::default(), Some("agent:main:main")).await.unwrap();
+        assert_eq!(info.status, ProcessStatus::Running);
+
+        manager.write(&info.id, "ping\n", true).await.unwrap();
+        let done = wait_for(&manager, &info.id, ProcessStatus::Exited).await;
+        assert_eq!((done.exit_code, done.output_bytes), (Some(0), 15));
+
+        let first = manager.poll(&info.id, None).await.unwrap();
+        assert_eq!((first.output.as_str(), first.offset), ("ready\ngot ping\n", 15));
+        assert_eq!(manager.poll(&info.id, None).await.unwrap().output, "", "polls pick up where they stopped");
+        assert_eq!(manager.poll(&info.id, Some(6)).await.unwrap().output, "got ping\n");
+        assert!(matches!(manager.write(&info.id, "x", false).await, Err(ProcessError::NotRunning(_))));
+
+        manager.remove(&info.id).await.unwrap();
+        assert!(manager.list().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn signals_and_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let ws = dir.path().to_str().unwrap();
+        let manager = ProcessManager::new(1, 16);
+        let server = manager.start("sleep 30 & wait", ws, ws, &SandboxPolicy::default(), None).await.unwrap();
+        assert!(matches!(
+            manager.start("true", ws, ws, &SandboxPolicy::default(), None).await,
+            Err(ProcessError::LimitReached(1))
+        ));
+        assert!(matches!(manager.signal(&server.id, "NOPE").await, Err(ProcessError::UnknownSignal(_))));
+        assert!(manager.remove(&server.id).await.is_err(), "running processes can't be removed");
+
+        manager.signal(&server.id, "TERM").await.unwrap();
+        let ended = wait_for(&manager, &server.id, ProcessStatus::Killed).await;
+        assert!(ended.ended_at.is_some());
+        assert!(matches!(manager.kill(&server.id).await, Err(ProcessError::NotRunning(_))));
+
+        let again = manager.start("sleep 30", ws, ws, &SandboxPolicy::default(), None).await.unwrap();
+        manager.shutdown().await;
+        wait_for(&manager, &again.id, ProcessStatus::Killed).await;
+    }
+}
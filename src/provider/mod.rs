@@ -3,3 +3,65 @@ pub mod types;
 
 pub use types::*;
 pub use anthropic::AnthropicProvider;
+
+/// Scripted provider used by tests across the crate.
+#[cfg(test)]
+pub(crate) mod mock {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+
+    /// Replies with queued responses in order, repeating the last one, and
+    /// records every request.
+    #[derive(Clone, Default)]
+    pub struct ScriptedProvider {
+        pub replies: Arc<Mutex<VecDeque<Vec<ContentBlock>>>>,
+        pub requests: Arc<Mutex<Vec<CompletionRequest>>>,
+    }
+
+    impl ScriptedProvider {
+        pub fn new(replies: Vec<Vec<ContentBlock>>) -> Self {
+            Self { replies: Arc::new(Mutex::new(replies.into())), ..Default::default() }
+        }
+
+        /// Always answer with the same text.
+        pub fn text(reply: &str) -> Self {
+            Self::new(vec![vec![ContentBlock::Text { text: reply.to_string() }]])
+        }
+
+        pub fn request_count(&self) -> usize {
+            self.requests.lock().unwrap().len()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for ScriptedProvider {
+        async fn complete(&self, request: &CompletionRequest) -> Result<CompletionResponse, ProviderError> {
+            self.requests.lock().unwrap().push(request.clone());
+            let content = {
+                let mut replies = self.replies.lock().unwrap();
+                if replies.len() > 1 { replies.pop_front() } else { replies.front().cloned() }
+            }.unwrap_or_default();
+            let stop_reason = if content.iter().any(|b| matches!(b, ContentBlock::ToolUse { .. })) {
+                "tool_use"
+            } else {
+                "end_turn"
+            };
+            Ok(CompletionResponse {
+                id: format!("msg_{}", self.request_count()),
+                model: request.model.clone(),
+                content,
+                stop_reason: Some(stop_reason.into()),
+                usage: Usage { input_tokens: 10, output_tokens: 5, ..Default::default() },
+            })
+        }
+
+        async fn stream(&self, _request: &CompletionRequest) -> Result<tokio::sync::mpsc::Receiver<StreamEvent>, ProviderError> {
+            Err(ProviderError::Other("streaming not scripted".into()))
+        }
+
+        fn name(&self) -> &str {
+            "scripted"
+        }
+    }
+}
@@ -231,8 +231,6 @@ pub fn is_external_hook_session(session_key: &str) -> bool {
 pub fn get_hook_type(session_key: &str) -> ExternalContentSource {
     if session_key.starts_with("hook:gmail:") {
         ExternalContentSource::Email
-    } else if session_key.starts_with("hook:webhook:") {
-        ExternalContentSource::Webhook
     } else if session_key.starts_with("hook:") {
         ExternalContentSource::Webhook
     } else {
@@ -0,0 +1,236 @@
+//! Which files the Read/Write/Edit tools may touch: the workspace and any
+//! extra roots, minus deny patterns, judged on the path after symlinks.
+
+use crate::config::OpenClawConfig;
+use crate::utils::{resolve_config_dir, resolve_user_path};
+use glob::{MatchOptions, Pattern};
+use std::path::{Component, Path, PathBuf};
+use tracing::warn;
+
+/// Denied whatever the config says: credentials and the gateway's own config
+/// (which holds tokens).
+pub const DEFAULT_DENY: &[&str] = &["~/.ssh", "~/.gnupg", "~/.aws", "~/.netrc"];
+const CONFIG_FILES: &[&str] = &["openclaw.json", "openclaw.yaml", "openclaw.yml"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum FileAccessError {
+    #[error("Access denied: {path} is outside the workspace ({workspace}); allowed roots can be added under tools.files.roots")]
+    Outside { path: String, workspace: String },
+    #[error("Access denied: {path} resolves to {target} through a symlink, outside the workspace ({workspace})")]
+    SymlinkEscape { path: String, target: String, workspace: String },
+    #[error("Access denied: {path} matches deny pattern '{pattern}'")]
+    Denied { path: String, pattern: String },
+}
+
+#[derive(Debug, Clone)]
+struct DenyRule {
+    source: String,
+    pattern: Pattern,
+}
+
+/// Roots and deny patterns for file tools (`tools.files`).
+#[derive(Debug, Clone)]
+pub struct FileAccessPolicy {
+    /// Limit access to the workspace and `roots`.
+    confine: bool,
+    roots: Vec<PathBuf>,
+    deny: Vec<DenyRule>,
+}
+
+impl Default for FileAccessPolicy {
+    fn default() -> Self {
+        Self::new(true, &[], &[])
+    }
+}
+
+impl FileAccessPolicy {
+    /// `roots` and `deny` may use `~`; deny entries are globs, and a pattern
+    /// naming a directory covers everything below it.
+    pub fn new(confine: bool, roots: &[String], deny: &[String]) -> Self {
+        let config_files = CONFIG_FILES.iter()
+            .map(|name| resolve_config_dir().join(name).to_string_lossy().into_owned());
+        let deny = DEFAULT_DENY.iter().map(|s| s.to_string())
+            .chain(config_files)
+            .chain(deny.iter().cloned())
+            .filter(|s| !s.trim().is_empty())
+            .filter_map(|source| {
+                match Pattern::new(&expand_home(&source)) {
+                    Ok(pattern) => Some(DenyRule { source, pattern }),
+                    Err(e) => {
+                        warn!("Ignoring invalid file deny pattern '{}': {}", source, e);
+                        None
+                    }
+                }
+            })
+            .collect();
+        let roots = roots.iter()
+            .filter(|r| !r.trim().is_empty())
+            .map(|r| canonical(&normalize(&resolve_user_path(r))))
+            .collect();
+        Self { confine, roots, deny }
+    }
+
+    pub fn from_config(config: &OpenClawConfig) -> Self {
+        let files = config.tools.as_ref().and_then(|t| t.files.clone()).unwrap_or_default();
+        Self::new(
+            files.confine.unwrap_or(true),
+            &files.roots.unwrap_or_default(),
+            &files.deny.unwrap_or_default(),
+        )
+    }
+
+    /// Check `path` (already joined to the workspace if relative) and return
+    /// it with symlinks resolved, ready to open.
+    pub fn check(&self, path: &Path, workspace: &str) -> Result<PathBuf, FileAccessError> {
+        let lexical = normalize(path);
+        let target = canonical(&lexical);
+        for candidate in [&lexical, &target] {
+            if let Some(rule) = self.deny.iter().find(|rule| denies(&rule.pattern, candidate)) {
+                return Err(FileAccessError::Denied { path: display(path), pattern: rule.source.clone() });
+            }
+        }
+        if !self.confine {
+            return Ok(target);
+        }
+
+        let workspace_path = normalize(&resolve_user_path(workspace));
+        let workspace_root = canonical(&workspace_path);
+        let inside = |p: &Path| p.starts_with(&workspace_root) || self.roots.iter().any(|root| p.starts_with(root));
+        if inside(&target) {
+            return Ok(target);
+        }
+        let workspace = display(&workspace_root);
+        // Inside by name but not once links are followed
+        if inside(&lexical) || lexical.starts_with(&workspace_path) {
+            return Err(FileAccessError::SymlinkEscape { path: display(path), target: display(&target), workspace });
+        }
+        Err(FileAccessError::Outside { path: display(path), workspace })
+    }
+}
+
+/// Expand a leading `~`; other patterns are used as written.
+fn expand_home(pattern: &str) -> String {
+    let pattern = pattern.trim();
+    if pattern.starts_with('~') {
+        display(&resolve_user_path(pattern))
+    } else {
+        pattern.to_string()
+    }
+}
+
+fn display(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+/// Whether `pattern` matches `path` or one of its parent directories.
+fn denies(pattern: &Pattern, path: &Path) -> bool {
+    let options = MatchOptions { require_literal_separator: true, ..Default::default() };
+    path.ancestors().any(|p| pattern.matches_path_with(p, options))
+}
+
+/// Resolve `.` and `..` without touching the filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                out.pop();
+            }
+            Component::CurDir => {}
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// `path` with symlinks resolved. Parts that don't exist yet (a file about to
+/// be written) are appended to their deepest existing ancestor; a dangling
+/// symlink is followed to where it points, since writing through it would
+/// create the target.
+fn canonical(path: &Path) -> PathBuf {
+    resolve_links(path, 0)
+}
+
+/// Symlink hops followed before giving up, as the kernel does with `ELOOP`.
+const MAX_LINK_HOPS: usize = 40;
+
+fn resolve_links(path: &Path, hops: usize) -> PathBuf {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    loop {
+        if let Ok(resolved) = existing.canonicalize() {
+            return rest.iter().rev().fold(resolved, |acc, part| acc.join(part));
+        }
+        if let Ok(link) = std::fs::read_link(existing) {
+            if hops >= MAX_LINK_HOPS {
+                return path.to_path_buf();
+            }
+            let base = existing.parent().unwrap_or(Path::new("/"));
+            let target = rest.iter().rev().fold(normalize(&base.join(link)), |acc, part| acc.join(part));
+            return resolve_links(&target, hops + 1);
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace() -> (tempfile::TempDir, String) {
+        let dir = tempfile::tempdir().unwrap();
+        let ws = dir.path().join("ws");
+        std::fs::create_dir(&ws).unwrap();
+        let ws = ws.to_string_lossy().into_owned();
+        (dir, ws)
+    }
+
+    #[test]
+    fn confines_to_the_workspace_and_roots() {
+        let (dir, ws) = workspace();
+        let shared = dir.path().join("shared");
+        std::fs::create_dir(&shared).unwrap();
+        let policy = FileAccessPolicy::new(true, &[shared.to_string_lossy().into_owned()], &["**/*.pem".into()]);
+
+        let inside = policy.check(&Path::new(&ws).join("notes/new.md"), &ws).unwrap();
+        assert!(inside.ends_with("ws/notes/new.md"));
+        assert!(policy.check(&shared.join("a.txt"), &ws).is_ok());
+
+        let escape = policy.check(&Path::new(&ws).join("../secret.txt"), &ws).unwrap_err();
+        assert!(matches!(escape, FileAccessError::Outside { .. }), "{}", escape);
+        assert!(policy.check(Path::new("/etc/passwd"), &ws).is_err());
+        let pem = policy.check(&Path::new(&ws).join("keys/server.pem"), &ws).unwrap_err();
+        assert!(pem.to_string().contains("'**/*.pem'"));
+
+        let open = FileAccessPolicy::new(false, &[], &[]);
+        assert!(open.check(Path::new("/etc/passwd"), &ws).is_ok());
+        let ssh = open.check(&resolve_user_path("~/.ssh/id_ed25519"), &ws).unwrap_err();
+        assert!(matches!(ssh, FileAccessError::Denied { .. }));
+    }
+
+    #[test]
+    fn detects_symlink_escapes() {
+        let (dir, ws) = workspace();
+        let outside = dir.path().join("outside");
+        std::fs::create_dir(&outside).unwrap();
+        std::fs::write(outside.join("secret.txt"), "s3cret").unwrap();
+        std::os::unix::fs::symlink(&outside, Path::new(&ws).join("link")).unwrap();
+        std::os::unix::fs::symlink(outside.join("secret.txt"), Path::new(&ws).join("file-link")).unwrap();
+        std::os::unix::fs::symlink(outside.join("created.txt"), Path::new(&ws).join("dangling")).unwrap();
+        std::os::unix::fs::symlink("../outside/missing/x", Path::new(&ws).join("relative")).unwrap();
+
+        let policy = FileAccessPolicy::default();
+        for path in ["link/secret.txt", "file-link", "link/new.txt", "dangling", "relative"] {
+            let err = policy.check(&Path::new(&ws).join(path), &ws).unwrap_err();
+            assert!(matches!(err, FileAccessError::SymlinkEscape { .. }), "{}: {}", path, err);
+        }
+        std::os::unix::fs::symlink("inside.txt", Path::new(&ws).join("inside-link")).unwrap();
+        assert!(policy.check(&Path::new(&ws).join("inside-link"), &ws).unwrap().ends_with("ws/inside.txt"));
+    }
+}
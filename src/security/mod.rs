@@ -1,2 +1,3 @@
 pub mod external_content;
+pub mod file_access;
 pub mod secret_equal;
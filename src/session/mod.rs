@@ -132,28 +132,38 @@ impl SessionManager {
 
     /// Get or create a session for the given key.
     pub async fn get_or_create(&self, key: &str, agent_id: &str, channel: &str) -> Session {
+        self.get_or_create_evicting(key, agent_id, channel).await.0
+    }
+
+    /// Like `get_or_create`, also returning the session evicted to make room,
+    /// so callers can flush it before it's gone.
+    pub async fn get_or_create_evicting(&self, key: &str, agent_id: &str, channel: &str) -> (Session, Option<Session>) {
         {
             let sessions = self.sessions.read().await;
             if let Some(session) = sessions.get(key) {
-                return session.clone();
+                return (session.clone(), None);
             }
         }
 
         let session = Session::new(key, agent_id, channel);
         let mut sessions = self.sessions.write().await;
+        if let Some(existing) = sessions.get(key) {
+            return (existing.clone(), None);
+        }
 
         // Evict oldest if at capacity
+        let mut evicted = None;
         if sessions.len() >= self.max_sessions {
             if let Some(oldest_key) = sessions.iter()
                 .min_by_key(|(_, s)| s.updated_at)
                 .map(|(k, _)| k.clone())
             {
-                sessions.remove(&oldest_key);
+                evicted = sessions.remove(&oldest_key);
             }
         }
 
         sessions.insert(key.to_string(), session.clone());
-        session
+        (session, evicted)
     }
 
     /// Update a session.
@@ -267,4 +277,19 @@ mod tests {
         mgr.remove("k1").await;
         assert_eq!(mgr.count().await, 0);
     }
+
+    #[tokio::test]
+    async fn eviction_returns_oldest_session() {
+        let manager = SessionManager::new(2);
+        let (_, evicted) = manager.get_or_create_evicting("a", "main", "cli").await;
+        assert!(evicted.is_none());
+        manager.get_or_create("b", "main", "cli").await;
+        let (_, evicted) = manager.get_or_create_evicting("a", "main", "cli").await;
+        assert!(evicted.is_none());
+
+        let (session, evicted) = manager.get_or_create_evicting("c", "main", "cli").await;
+        assert_eq!(session.key, "c");
+        assert_eq!(evicted.unwrap().key, "a");
+        assert_eq!(manager.count().await, 2);
+    }
 }
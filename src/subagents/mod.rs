@@ -0,0 +1,490 @@
+//! Background subagent runs: an agent hands a task to a child session, which
+//! runs under its own concurrency limit and reports back when done.
+
+use crate::config::OpenClawConfig;
+use crate::session::build_session_key;
+use crate::utils::excerpt;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{RwLock, Semaphore};
+use tokio::task::AbortHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+/// Top-level agent turns run at once when `agents.defaults.maxConcurrent` is unset.
+pub const DEFAULT_MAX_CONCURRENT: usize = 4;
+/// Subagent runs at once when `agents.defaults.subagents.maxConcurrent` is unset.
+pub const DEFAULT_MAX_CHILDREN: usize = 8;
+/// Used when a spawn sets no `timeoutSeconds`.
+pub const DEFAULT_RUN_TIMEOUT: Duration = Duration::from_secs(600);
+/// Tools a subagent never gets: no nested spawning, scheduling or messaging
+/// chats directly (its result is announced instead).
+pub const SUBAGENT_DENIED_TOOLS: &[&str] = &["sessions_spawn", "cron", "message"];
+/// Finished runs kept for listing.
+const MAX_FINISHED_RUNS: usize = 50;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SubagentError {
+    #[error("Subagent run not found: {0}")]
+    NotFound(String),
+    #[error("Subagent run {0} has already finished")]
+    Finished(String),
+    #[error("Invalid subagent request: {0}")]
+    Invalid(String),
+    #[error("Subagents are not available")]
+    Unavailable,
+}
+
+/// Read `maxConcurrent` limits from `agents.defaults`: (top-level turns, subagent runs).
+pub fn concurrency_limits(config: &OpenClawConfig) -> (usize, usize) {
+    let defaults = config.agents.as_ref().and_then(|a| a.defaults.as_ref());
+    let top = defaults.and_then(|d| d.max_concurrent).map(|n| n as usize).unwrap_or(DEFAULT_MAX_CONCURRENT);
+    let children = defaults
+        .and_then(|d| d.subagents.as_ref())
+        .and_then(|s| s.max_concurrent)
+        .map(|n| n as usize)
+        .unwrap_or(DEFAULT_MAX_CHILDREN);
+    (top.max(1), children.max(1))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubagentStatus {
+    /// Waiting for a free subagent slot.
+    Queued,
+    Running,
+    Ok,
+    Error,
+    Timeout,
+    Cancelled,
+}
+
+impl SubagentStatus {
+    pub fn is_active(self) -> bool {
+        matches!(self, Self::Queued | Self::Running)
+    }
+}
+
+/// What an agent asked a subagent to do.
+#[derive(Debug, Clone, Default)]
+pub struct SpawnRequest {
+    pub task: String,
+    pub label: Option<String>,
+    pub agent_id: String,
+    /// Session that spawned the run and receives its result.
+    pub parent_session: String,
+    /// Chat the parent conversation came from, for delivering the result.
+    pub channel: Option<String>,
+    pub to: Option<String>,
+    /// Narrow the subagent's tools further, when set.
+    pub tools: Option<Vec<String>>,
+    pub timeout: Option<Duration>,
+}
+
+/// One subagent run.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubagentRun {
+    pub id: String,
+    pub label: String,
+    pub task: String,
+    pub agent_id: String,
+    pub parent_session: String,
+    pub session_key: String,
+    pub channel: Option<String>,
+    pub to: Option<String>,
+    pub tools: Option<Vec<String>>,
+    pub status: SubagentStatus,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// The subagent's final reply.
+    pub result: Option<String>,
+    pub error: Option<String>,
+}
+
+impl SubagentRun {
+    /// Whether the subagent may use `tool`.
+    pub fn allows_tool(&self, tool: &str) -> bool {
+        !SUBAGENT_DENIED_TOOLS.contains(&tool)
+            && self.tools.as_ref().is_none_or(|allow| allow.iter().any(|t| t == tool))
+    }
+
+    /// Message reporting the outcome to the parent.
+    pub fn announcement(&self) -> String {
+        match self.status {
+            SubagentStatus::Ok => format!(
+                "Subagent \"{}\" finished:\n\n{}",
+                self.label,
+                self.result.as_deref().unwrap_or("").trim(),
+            ),
+            SubagentStatus::Timeout => format!("Subagent \"{}\" timed out.", self.label),
+            _ => format!(
+                "Subagent \"{}\" failed: {}",
+                self.label,
+                self.error.as_deref().unwrap_or("unknown error"),
+            ),
+        }
+    }
+}
+
+/// Runs subagent tasks and reports their results.
+#[async_trait]
+pub trait SubagentExecutor: Send + Sync {
+    /// Run the task in the run's own session and return the final reply.
+    /// `cancel` fires when the run is cancelled; tool calls should stop.
+    async fn run_subagent(&self, run: &SubagentRun, cancel: CancellationToken) -> Result<String, String>;
+    /// Tell the parent session (and its chat) how the run ended.
+    async fn announce(&self, run: &SubagentRun);
+}
+
+struct Entry {
+    run: SubagentRun,
+    handle: Option<AbortHandle>,
+    cancel: CancellationToken,
+}
+
+/// Spawns, tracks and cancels subagent runs.
+#[derive(Clone)]
+pub struct SubagentService {
+    runs: Arc<RwLock<HashMap<String, Entry>>>,
+    slots: Arc<Semaphore>,
+    max_children: usize,
+    executor: Option<Arc<dyn SubagentExecutor>>,
+    default_timeout: Duration,
+    /// Parent of every run's token.
+    cancel: CancellationToken,
+}
+
+impl SubagentService {
+    pub fn new(max_children: usize) -> Self {
+        let max_children = max_children.max(1);
+        Self {
+            runs: Arc::new(RwLock::new(HashMap::new())),
+            slots: Arc::new(Semaphore::new(max_children)),
+            max_children,
+            executor: None,
+            default_timeout: DEFAULT_RUN_TIMEOUT,
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    pub fn from_config(config: &OpenClawConfig) -> Self {
+        Self::new(concurrency_limits(config).1)
+    }
+
+    pub fn with_executor(mut self, executor: Arc<dyn SubagentExecutor>) -> Self {
+        self.executor = Some(executor);
+        self
+    }
+
+    pub fn with_default_timeout(mut self, timeout: Duration) -> Self {
+        self.default_timeout = timeout;
+        self
+    }
+
+    /// Cancel every run when `cancel` fires (e.g. on gateway shutdown).
+    pub fn with_cancel(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    pub fn max_children(&self) -> usize {
+        self.max_children
+    }
+
+    /// Start a run in the background. It queues until a subagent slot is free.
+    pub async fn spawn(&self, request: SpawnRequest) -> Result<SubagentRun, SubagentError> {
+        let executor = self.executor.clone().ok_or(SubagentError::Unavailable)?;
+        let task = request.task.trim().to_string();
+        if task.is_empty() {
+            return Err(SubagentError::Invalid("task is required".into()));
+        }
+        if request.parent_session.contains(":subagent:") {
+            return Err(SubagentError::Invalid("subagents can't spawn subagents".into()));
+        }
+
+        let id = uuid::Uuid::new_v4().simple().to_string()[..12].to_string();
+        let run = SubagentRun {
+            label: request.label.map(|l| l.trim().to_string()).filter(|l| !l.is_empty())
+                .unwrap_or_else(|| excerpt(&task).chars().take(40).collect()),
+            session_key: build_session_key(&request.agent_id, "subagent", &id),
+            id: id.clone(),
+            task,
+            agent_id: request.agent_id,
+            parent_session: request.parent_session,
+            channel: request.channel,
+            to: request.to,
+            tools: request.tools,
+            status: SubagentStatus::Queued,
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+            result: None,
+            error: None,
+        };
+        let timeout = request.timeout.unwrap_or(self.default_timeout);
+
+        // Hold the lock until the handle is stored so the task can't update
+        // its entry first
+        let mut runs = self.runs.write().await;
+        let service = self.clone();
+        let queued = run.clone();
+        let cancel = self.cancel.child_token();
+        let run_cancel = cancel.clone();
+        let handle = tokio::spawn(async move {
+            let Ok(_permit) = service.slots.clone().acquire_owned().await else { return };
+            let Some(run) = service.update(&id, |run| {
+                run.status = SubagentStatus::Running;
+                run.started_at = Some(Utc::now());
+            }).await else { return };
+
+            let outcome = tokio::time::timeout(timeout, executor.run_subagent(&run, run_cancel)).await;
+            let Some(run) = service.update(&id, |run| {
+                match outcome {
+                    Ok(Ok(reply)) => {
+                        run.status = SubagentStatus::Ok;
+                        run.result = Some(reply);
+                    }
+                    Ok(Err(e)) => {
+                        run.status = SubagentStatus::Error;
+                        run.error = Some(e);
+                    }
+                    Err(_) => {
+                        run.status = SubagentStatus::Timeout;
+                        run.error = Some(format!("timed out after {}s", timeout.as_secs()));
+                    }
+                }
+                run.finished_at = Some(Utc::now());
+            }).await else { return };
+            info!("Subagent {} ({}) finished: {:?}", run.id, run.label, run.status);
+            executor.announce(&run).await;
+        });
+        runs.insert(queued.id.clone(), Entry { run: queued.clone(), handle: Some(handle.abort_handle()), cancel });
+        prune(&mut runs);
+        Ok(queued)
+    }
+
+    /// Stop a queued or running subagent.
+    pub async fn cancel(&self, id: &str) -> Result<SubagentRun, SubagentError> {
+        let mut runs = self.runs.write().await;
+        let entry = runs.get_mut(id).ok_or_else(|| SubagentError::NotFound(id.to_string()))?;
+        if !entry.run.status.is_active() {
+            return Err(SubagentError::Finished(id.to_string()));
+        }
+        entry.cancel.cancel();
+        if let Some(handle) = entry.handle.take() {
+            handle.abort();
+        }
+        entry.run.status = SubagentStatus::Cancelled;
+        entry.run.finished_at = Some(Utc::now());
+        Ok(entry.run.clone())
+    }
+
+    pub async fn get(&self, id: &str) -> Option<SubagentRun> {
+        self.runs.read().await.get(id).map(|e| e.run.clone())
+    }
+
+    /// Runs, newest first; only those spawned by `parent` when given.
+    pub async fn list(&self, parent: Option<&str>) -> Vec<SubagentRun> {
+        let mut runs: Vec<SubagentRun> = self.runs.read().await.values()
+            .map(|e| e.run.clone())
+            .filter(|run| parent.is_none_or(|p| run.parent_session == p))
+            .collect();
+        runs.sort_by_key(|run| std::cmp::Reverse(run.created_at));
+        runs
+    }
+
+    /// Runs still queued or running.
+    pub async fn active_count(&self) -> usize {
+        self.runs.read().await.values().filter(|e| e.run.status.is_active()).count()
+    }
+
+    /// Apply `change` to an active run, returning the result. `None` once the
+    /// run was cancelled.
+    async fn update(&self, id: &str, change: impl FnOnce(&mut SubagentRun)) -> Option<SubagentRun> {
+        let mut runs = self.runs.write().await;
+        let entry = runs.get_mut(id).filter(|e| e.run.status.is_active())?;
+        change(&mut entry.run);
+        if !entry.run.status.is_active() {
+            entry.handle = None;
+        }
+        Some(entry.run.clone())
+    }
+}
+
+/// Drop the oldest finished runs beyond `MAX_FINISHED_RUNS`.
+fn prune(runs: &mut HashMap<String, Entry>) {
+    let mut finished: Vec<(DateTime<Utc>, String)> = runs.values()
+        .filter(|e| !e.run.status.is_active())
+        .map(|e| (e.run.created_at, e.run.id.clone()))
+        .collect();
+    if finished.len() <= MAX_FINISHED_RUNS {
+        return;
+    }
+    finished.sort();
+    let excess = finished.len() - MAX_FINISHED_RUNS;
+    for (_, id) in finished.into_iter().take(excess) {
+        runs.remove(&id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tokio::sync::Notify;
+
+    /// Replies with the task after `release` is notified (or at once), and
+    /// records announcements.
+    #[derive(Default)]
+    struct Recorder {
+        gate: Option<Arc<Notify>>,
+        running: Mutex<usize>,
+        peak: Mutex<usize>,
+        announced: Mutex<Vec<String>>,
+        tokens: Mutex<Vec<CancellationToken>>,
+    }
+
+    #[async_trait]
+    impl SubagentExecutor for Recorder {
+        async fn run_subagent(&self, run: &SubagentRun, cancel: CancellationToken) -> Result<String, String> {
+            self.tokens.lock().unwrap().push(cancel);
+            {
+                let mut running = self.running.lock().unwrap();
+                *running += 1;
+                let mut peak = self.peak.lock().unwrap();
+                *peak = (*peak).max(*running);
+            }
+            if let Some(gate) = &self.gate {
+                gate.notified().await;
+            }
+            *self.running.lock().unwrap() -= 1;
+            if run.task == "fail" {
+                return Err("boom".into());
+            }
+            Ok(format!("done: {}", run.task))
+        }
+
+        async fn announce(&self, run: &SubagentRun) {
+            self.announced.lock().unwrap().push(run.announcement());
+        }
+    }
+
+    fn request(task: &str) -> SpawnRequest {
+        SpawnRequest {
+            task: task.into(),
+            agent_id: "main".into(),
+            parent_session: "agent:main:telegram:42".into(),
+            ..Default::default()
+        }
+    }
+
+    async fn wait_until_finished(service: &SubagentService) {
+        for _ in 0..200 {
+            if service.active_count().await == 0 {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        panic!("subagents did not finish");
+    }
+
+    #[tokio::test]
+    async fn runs_report_back_to_the_parent() {
+        let recorder = Arc::new(Recorder::default());
+        let service = SubagentService::new(2).with_executor(recorder.clone());
+        let run = service.spawn(SpawnRequest { label: Some("research".into()), ..request("find flights") }).await.unwrap();
+        assert_eq!(run.session_key, format!("agent:main:subagent:{}", run.id));
+        service.spawn(request("fail")).await.unwrap();
+        wait_until_finished(&service).await;
+
+        let finished = service.get(&run.id).await.unwrap();
+        assert_eq!((finished.status, finished.result.as_deref()), (SubagentStatus::Ok, Some("done: find flights")));
+        let announced = recorder.announced.lock().unwrap().clone();
+        assert!(announced.contains(&"Subagent \"research\" finished:\n\ndone: find flights".to_string()));
+        assert!(announced.iter().any(|a| a.contains("failed: boom")));
+        assert_eq!(service.list(Some("agent:main:telegram:42")).await.len(), 2);
+        assert!(service.list(Some("other")).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn concurrency_is_limited_and_runs_can_be_cancelled() {
+        let gate = Arc::new(Notify::new());
+        let recorder = Arc::new(Recorder { gate: Some(gate.clone()), ..Default::default() });
+        let service = SubagentService::new(2).with_executor(recorder.clone());
+        let mut ids = Vec::new();
+        for i in 0..4 {
+            ids.push(service.spawn(request(&format!("task {}", i))).await.unwrap().id);
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let queued = service.list(None).await.iter().filter(|r| r.status == SubagentStatus::Queued).count();
+        assert_eq!(queued, 2);
+
+        let cancelled = service.cancel(&ids[3]).await.unwrap();
+        assert_eq!(cancelled.status, SubagentStatus::Cancelled);
+        assert!(matches!(service.cancel(&ids[3]).await, Err(SubagentError::Finished(_))));
+        assert!(matches!(service.cancel("nope").await, Err(SubagentError::NotFound(_))));
+
+        for _ in 0..20 {
+            gate.notify_waiters();
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        wait_until_finished(&service).await;
+        assert_eq!(*recorder.peak.lock().unwrap(), 2);
+        assert_eq!(recorder.announced.lock().unwrap().len(), 3);
+        assert_eq!(service.get(&ids[3]).await.unwrap().status, SubagentStatus::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn cancelling_a_run_cancels_its_tool_calls() {
+        let gate = Arc::new(Notify::new());
+        let recorder = Arc::new(Recorder { gate: Some(gate), ..Default::default() });
+        let shutdown = CancellationToken::new();
+        let service = SubagentService::new(2).with_executor(recorder.clone()).with_cancel(shutdown.clone());
+        let first = service.spawn(request("first")).await.unwrap();
+        service.spawn(request("second")).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        service.cancel(&first.id).await.unwrap();
+        let cancelled = || recorder.tokens.lock().unwrap().iter().filter(|t| t.is_cancelled()).count();
+        assert_eq!(cancelled(), 1);
+        shutdown.cancel();
+        assert_eq!(cancelled(), 2);
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_requests_and_times_out() {
+        assert!(matches!(SubagentService::new(1).spawn(request("x")).await, Err(SubagentError::Unavailable)));
+
+        let gate = Arc::new(Notify::new());
+        let recorder = Arc::new(Recorder { gate: Some(gate), ..Default::default() });
+        let service = SubagentService::new(1).with_executor(recorder.clone())
+            .with_default_timeout(Duration::from_millis(20));
+        assert!(matches!(service.spawn(request("  ")).await, Err(SubagentError::Invalid(_))));
+        let nested = SpawnRequest { parent_session: "agent:main:subagent:abc".into(), ..request("x") };
+        assert!(matches!(service.spawn(nested).await, Err(SubagentError::Invalid(_))));
+
+        let run = service.spawn(request("slow")).await.unwrap();
+        wait_until_finished(&service).await;
+        assert_eq!(service.get(&run.id).await.unwrap().status, SubagentStatus::Timeout);
+        assert_eq!(recorder.announced.lock().unwrap()[0], "Subagent \"slow\" timed out.");
+
+        assert!(!run.allows_tool("sessions_spawn"));
+        let narrowed = SubagentRun { tools: Some(vec!["Read".into()]), ..run };
+        assert!(narrowed.allows_tool("Read") && !narrowed.allows_tool("exec"));
+    }
+
+    #[test]
+    fn limits_from_config() {
+        assert_eq!(concurrency_limits(&OpenClawConfig::default()), (DEFAULT_MAX_CONCURRENT, DEFAULT_MAX_CHILDREN));
+        let config: OpenClawConfig = serde_json::from_value(serde_json::json!({
+            "agents": { "defaults": { "maxConcurrent": 2, "subagents": { "maxConcurrent": 0 } } }
+        })).unwrap();
+        assert_eq!(concurrency_limits(&config), (2, 1));
+    }
+}
@@ -0,0 +1,292 @@
+use super::executor::ToolContext;
+use super::{Tool, ToolResult};
+use crate::config::CronJobConfig;
+use crate::cron_system::schedule::parse_interval;
+use crate::cron_system::{CronJob, CronService, JobSource, Schedule};
+use async_trait::async_trait;
+use chrono::Utc;
+use crate::provider::types::ToolDefinition;
+use serde_json::json;
+use std::collections::HashMap;
+
+/// Jobs the agent may have scheduled at once.
+pub const MAX_AGENT_JOBS: usize = 20;
+/// Shortest repeat interval the agent may schedule.
+pub const MIN_AGENT_INTERVAL: chrono::Duration = chrono::Duration::minutes(5);
+const MAX_PROMPT_CHARS: usize = 2000;
+const HISTORY_LIMIT: usize = 5;
+
+fn error(content: impl Into<String>) -> ToolResult {
+    ToolResult { content: content.into(), is_error: true, metadata: HashMap::new() }
+}
+
+fn str_field(input: &serde_json::Value, key: &str) -> Option<String> {
+    input.get(key).and_then(|v| v.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+fn describe(job: &CronJob) -> String {
+    let next = match (job.enabled, job.next_run) {
+        (false, _) => "disabled".to_string(),
+        (true, Some(next)) => format!("next {}", next.to_rfc3339()),
+        (true, None) => "done".to_string(),
+    };
+    format!("- {} \"{}\" [{}] {}", job.id, job.name, job.schedule, next)
+}
+
+/// The `cron` tool.
+pub struct CronTool;
+
+#[async_trait]
+impl Tool for CronTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "cron".into(),
+            description: "Schedule reminders and recurring tasks. Each job runs the prompt as an agent turn and delivers the reply to the current chat. Schedules are intervals (\"2h\"), cron expressions (\"0 0 9 * * Mon-Fri\") or timestamps; use \"in\" for a one-off delay.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": { "type": "string", "enum": ["list", "add", "update", "remove", "run", "history"] },
+                    "id": { "type": "string", "description": "Job to update, remove, run or inspect" },
+                    "name": { "type": "string" },
+                    "schedule": { "type": "string", "description": "Interval, 6-field cron expression or ISO 8601 timestamp" },
+                    "in": { "type": "string", "description": "Run once after this delay, e.g. \"20m\"" },
+                    "prompt": { "type": "string", "description": "What to do when the job runs" },
+                    "timezone": { "type": "string", "description": "IANA timezone for cron expressions and timestamps" },
+                    "enabled": { "type": "boolean" }
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+        execute_cron(input, ctx).await
+    }
+}
+
+/// Execute the cron tool: let the agent schedule, change and inspect its own
+/// jobs. Jobs created here deliver to the chat the request came from.
+pub async fn execute_cron(input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+    let Some(cron) = &ctx.cron else {
+        return error("Scheduling is not available in this context");
+    };
+    let action = input.get("action").and_then(|v| v.as_str()).unwrap_or("list");
+    let id = str_field(input, "id");
+
+    match action {
+        "list" => {
+            let jobs = cron.list_jobs().await;
+            if jobs.is_empty() {
+                return ToolResult { content: "No scheduled jobs.".into(), is_error: false, metadata: HashMap::new() };
+            }
+            let mut metadata = HashMap::new();
+            metadata.insert("jobs".into(), json!(jobs.iter().map(|j| &j.id).collect::<Vec<_>>()));
+            let lines: Vec<String> = jobs.iter().map(describe).collect();
+            ToolResult { content: lines.join("\n"), is_error: false, metadata }
+        }
+        "add" => add_job(input, ctx, cron).await,
+        "update" | "remove" | "run" | "history" => {
+            let Some(id) = id else {
+                return error(format!("id is required for {}", action));
+            };
+            let Some(job) = cron.get_job(&id).await else {
+                return error(format!("No cron job with id {}", id));
+            };
+            if action == "history" {
+                let runs: Vec<String> = cron.history(&id).await.into_iter().take(HISTORY_LIMIT)
+                    .map(|r| format!(
+                        "- {} {:?}: {}",
+                        r.started_at.to_rfc3339(),
+                        r.status,
+                        r.error.or(r.excerpt).unwrap_or_default(),
+                    ))
+                    .collect();
+                let content = if runs.is_empty() { format!("Job {} has not run yet.", id) } else { runs.join("\n") };
+                return ToolResult { content, is_error: false, metadata: HashMap::new() };
+            }
+            if job.source != JobSource::Agent || job.agent_id != ctx.agent_id {
+                return error(format!("Job {} was not created with this tool and can't be changed here", id));
+            }
+            match action {
+                "update" => {
+                    let patch = match job_input(input) {
+                        Ok(patch) => patch,
+                        Err(e) => return error(e),
+                    };
+                    if let Err(e) = check_interval(&patch) {
+                        return error(e);
+                    }
+                    match cron.update_job(&id, &patch).await {
+                        Ok(job) => ToolResult { content: format!("Updated:\n{}", describe(&job)), is_error: false, metadata: HashMap::new() },
+                        Err(e) => error(e.to_string()),
+                    }
+                }
+                "remove" => {
+                    cron.remove_job(&id).await;
+                    ToolResult { content: format!("Removed job {}", id), is_error: false, metadata: HashMap::new() }
+                }
+                _ => match cron.trigger(&id).await {
+                    Ok(()) => ToolResult { content: format!("Started job {}", id), is_error: false, metadata: HashMap::new() },
+                    Err(e) => error(e.to_string()),
+                },
+            }
+        }
+        other => error(format!("Unknown action: {}", other)),
+    }
+}
+
+async fn add_job(input: &serde_json::Value, ctx: &ToolContext, cron: &CronService) -> ToolResult {
+    let mut config = match job_input(input) {
+        Ok(config) => config,
+        Err(e) => return error(e),
+    };
+    if config.schedule.is_none() {
+        return error("schedule or in is required");
+    }
+    if let Err(e) = check_interval(&config) {
+        return error(e);
+    }
+    let existing = cron.list_jobs().await.iter().filter(|j| j.source == JobSource::Agent).count();
+    if existing >= MAX_AGENT_JOBS {
+        return error(format!("Too many scheduled jobs ({}); remove one first", MAX_AGENT_JOBS));
+    }
+    if let (Some(channel), Some(to)) = (&ctx.channel, &ctx.chat_id) {
+        config.channel = Some(channel.clone());
+        config.to = Some(to.clone());
+    }
+    config.agent_id = ctx.agent_id.clone();
+
+    match cron.create_job(&config, JobSource::Agent).await {
+        Ok(job) => {
+            let mut metadata = HashMap::new();
+            metadata.insert("id".into(), json!(job.id));
+            metadata.insert("nextRun".into(), json!(job.next_run));
+            ToolResult { content: format!("Scheduled:\n{}", describe(&job)), is_error: false, metadata }
+        }
+        Err(e) => error(e.to_string()),
+    }
+}
+
+/// Job fields from tool input. `in` ("20m") is shorthand for a one-shot
+/// schedule that long from now.
+fn job_input(input: &serde_json::Value) -> Result<CronJobConfig, String> {
+    let mut schedule = str_field(input, "schedule");
+    if let Some(delay) = str_field(input, "in") {
+        let parsed = parse_interval(&delay)
+            .ok_or_else(|| format!("invalid delay '{}'; use e.g. \"20m\" or \"2h\"", delay))??;
+        if parsed <= chrono::Duration::zero() {
+            return Err(format!("delay must be positive: '{}'", delay));
+        }
+        schedule = Some((Utc::now() + parsed).to_rfc3339());
+    }
+    let prompt = str_field(input, "prompt");
+    if prompt.as_ref().is_some_and(|p| p.chars().count() > MAX_PROMPT_CHARS) {
+        return Err(format!("prompt is longer than {} characters", MAX_PROMPT_CHARS));
+    }
+    Ok(CronJobConfig {
+        name: str_field(input, "name"),
+        schedule,
+        enabled: input.get("enabled").and_then(|v| v.as_bool()),
+        prompt,
+        timezone: str_field(input, "timezone"),
+        ..Default::default()
+    })
+}
+
+/// Reject recurring schedules that fire more often than `MIN_AGENT_INTERVAL`.
+fn check_interval(config: &CronJobConfig) -> Result<(), String> {
+    let Some(schedule) = &config.schedule else { return Ok(()) };
+    let tz = match config.timezone.as_deref() {
+        Some(name) => crate::cron_system::parse_timezone(name)?,
+        None => chrono_tz::Tz::UTC,
+    };
+    let parsed = Schedule::parse_in(schedule, tz)?;
+    match parsed.shortest_gap(Utc::now(), 10) {
+        Some(gap) if gap < MIN_AGENT_INTERVAL => Err(format!(
+            "schedule repeats too often; the minimum interval is {} minutes",
+            MIN_AGENT_INTERVAL.num_minutes(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> ToolContext {
+        ToolContext::new("/tmp").with_cron(CronService::new()).with_origin("telegram", "42")
+    }
+
+    async fn run(ctx: &ToolContext, input: serde_json::Value) -> ToolResult {
+        execute_cron(&input, ctx).await
+    }
+
+    #[tokio::test]
+    async fn schedules_reminders_for_the_current_chat() {
+        let ctx = ctx();
+        let added = run(&ctx, json!({ "action": "add", "in": "20m", "prompt": "Remind me to stretch", "name": "stretch" })).await;
+        assert!(!added.is_error, "{}", added.content);
+        let id = added.metadata["id"].as_str().unwrap().to_string();
+
+        let job = ctx.cron.as_ref().unwrap().get_job(&id).await.unwrap();
+        assert_eq!(job.source, JobSource::Agent);
+        assert_eq!((job.channel.as_deref(), job.to.as_deref()), (Some("telegram"), Some("42")));
+        assert!(Schedule::parse(&job.schedule).unwrap().is_one_shot());
+
+        let listed = run(&ctx, json!({ "action": "list" })).await;
+        assert!(listed.content.contains("\"stretch\""));
+
+        let updated = run(&ctx, json!({ "action": "update", "id": id, "schedule": "0 0 9 * * *", "timezone": "Europe/Lisbon" })).await;
+        assert!(!updated.is_error, "{}", updated.content);
+        let history = run(&ctx, json!({ "action": "history", "id": id })).await;
+        assert!(history.content.contains("has not run yet"));
+        assert!(!run(&ctx, json!({ "action": "remove", "id": id })).await.is_error);
+        assert!(ctx.cron.as_ref().unwrap().list_jobs().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn enforces_limits() {
+        let ctx = ctx();
+        let too_often = run(&ctx, json!({ "action": "add", "schedule": "1m", "prompt": "ping" })).await;
+        assert!(too_often.content.contains("too often"));
+        let every_minute = run(&ctx, json!({ "action": "add", "schedule": "0 * * * * *", "prompt": "ping" })).await;
+        assert!(every_minute.is_error);
+        assert!(run(&ctx, json!({ "action": "add", "prompt": "no schedule" })).await.is_error);
+        assert!(run(&ctx, json!({ "action": "add", "in": "soon", "prompt": "x" })).await.is_error);
+        assert!(run(&ctx, json!({ "action": "add", "in": "9999999999999999h", "prompt": "x" })).await.content.contains("longer than"));
+        assert!(run(&ctx, json!({ "action": "add", "schedule": "100000000d", "prompt": "x" })).await.is_error);
+        assert!(run(&ctx, json!({ "action": "add", "in": "1h", "prompt": "x".repeat(MAX_PROMPT_CHARS + 1) })).await.is_error);
+
+        for i in 0..MAX_AGENT_JOBS {
+            let added = run(&ctx, json!({ "action": "add", "schedule": "1h", "prompt": format!("job {}", i) })).await;
+            assert!(!added.is_error, "{}", added.content);
+        }
+        let over = run(&ctx, json!({ "action": "add", "schedule": "1h", "prompt": "one more" })).await;
+        assert!(over.content.contains("Too many"));
+    }
+
+    #[tokio::test]
+    async fn leaves_other_jobs_alone() {
+        let ctx = ctx();
+        let cron = ctx.cron.clone().unwrap();
+        cron.add_job(CronJob::from_config(&CronJobConfig {
+            id: Some("digest".into()),
+            schedule: Some("1h".into()),
+            prompt: Some("Summarize".into()),
+            ..Default::default()
+        })).await;
+        let removed = run(&ctx, json!({ "action": "remove", "id": "digest" })).await;
+        assert!(removed.is_error);
+        assert!(cron.get_job("digest").await.is_some());
+
+        let work = ctx.clone().with_session("agent:work:main:main", "work");
+        let added = run(&work, json!({ "action": "add", "in": "1h", "prompt": "Standup notes" })).await;
+        let id = added.metadata["id"].as_str().unwrap();
+        assert_eq!(cron.get_job(id).await.unwrap().agent_id.as_deref(), Some("work"));
+        assert!(run(&ctx, json!({ "action": "remove", "id": id })).await.is_error);
+        assert!(!run(&work, json!({ "action": "remove", "id": id })).await.is_error);
+        assert!(run(&ctx, json!({ "action": "update" })).await.content.contains("id is required"));
+        assert!(run(&ToolContext::new("/tmp"), json!({ "action": "list" })).await.is_error);
+    }
+}
@@ -0,0 +1,461 @@
+//! Edit and MultiEdit: exact-text replacements applied all-or-nothing, with a
+//! unified diff of the result.
+
+use super::executor::{allowed_path, ToolContext};
+use super::{Tool, ToolResult};
+use crate::provider::types::ToolDefinition;
+use async_trait::async_trait;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Lines of context around each diff hunk.
+const DIFF_CONTEXT: usize = 3;
+/// Above this many (changed old lines × changed new lines) the diff shows the
+/// changed block as removed and re-added instead of aligning it.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum EditError {
+    #[error("old_string is required")]
+    Empty,
+    #[error("old_string and new_string are identical")]
+    NoChange,
+    #[error("old_string not found in file")]
+    NotFound,
+    #[error("old_string matches {} times (lines {}); add surrounding context to make it unique or set replace_all", .0.len(), join_lines(.0))]
+    Ambiguous(Vec<usize>),
+}
+
+fn join_lines(lines: &[usize]) -> String {
+    lines.iter().map(|l| l.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// One replacement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditOp {
+    pub old: String,
+    pub new: String,
+    /// Replace every occurrence instead of requiring exactly one.
+    pub replace_all: bool,
+}
+
+impl EditOp {
+    /// Read `old_string`/`new_string`/`replace_all` (or the `oldText`/`newText` aliases).
+    pub fn from_input(input: &serde_json::Value) -> Self {
+        let text = |keys: [&str; 2]| keys.iter()
+            .find_map(|k| input.get(*k).and_then(|v| v.as_str()))
+            .unwrap_or("")
+            .to_string();
+        Self {
+            old: text(["old_string", "oldText"]),
+            new: text(["new_string", "newText"]),
+            replace_all: input.get("replace_all").or_else(|| input.get("replaceAll"))
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// Apply `edits` in order to `content`. Returns the new content and the
+/// number of replacements, or the index of the failing edit.
+///
+/// `old` matches whether the file uses `\n` or `\r\n` there, and `new` is
+/// inserted with the line ending of the text it replaces; the rest of the
+/// file is left byte for byte.
+pub fn apply_edits(content: &str, edits: &[EditOp]) -> Result<(String, usize), (usize, EditError)> {
+    let mut content = content.to_string();
+    let mut replacements = 0;
+    for (i, edit) in edits.iter().enumerate() {
+        let old = edit.old.replace("\r\n", "\n");
+        let new = edit.new.replace("\r\n", "\n");
+        if old.is_empty() {
+            return Err((i, EditError::Empty));
+        }
+        if old == new {
+            return Err((i, EditError::NoChange));
+        }
+        let found = find_matches(&content, &old);
+        match found.len() {
+            0 => return Err((i, EditError::NotFound)),
+            1 => {}
+            _ if edit.replace_all => {}
+            _ => {
+                let lines = found.iter().map(|(at, _)| content[..*at].matches('\n').count() + 1).collect();
+                return Err((i, EditError::Ambiguous(lines)));
+            }
+        }
+        replacements += found.len();
+        let new_crlf = new.replace('\n', "\r\n");
+        let mut out = String::with_capacity(content.len());
+        let mut last = 0;
+        for (at, len) in found {
+            out.push_str(&content[last..at]);
+            out.push_str(if crlf_at(&content, at, len) { &new_crlf } else { &new });
+            last = at + len;
+        }
+        out.push_str(&content[last..]);
+        content = out;
+    }
+    Ok((content, replacements))
+}
+
+/// Non-overlapping `(start, len)` matches of `old` (written with `\n`) in
+/// `content`, in either line ending.
+fn find_matches(content: &str, old: &str) -> Vec<(usize, usize)> {
+    let mut found: Vec<(usize, usize)> = content.match_indices(old)
+        // A leading \n that is really the end of a \r\n belongs to the CRLF form
+        .filter(|(at, _)| !(old.starts_with('\n') && content[..*at].ends_with('\r')))
+        .map(|(at, _)| (at, old.len()))
+        .collect();
+    if old.contains('\n') {
+        let crlf = old.replace('\n', "\r\n");
+        found.extend(content.match_indices(&crlf).map(|(at, _)| (at, crlf.len())));
+        found.sort();
+        let mut end = 0;
+        found.retain(|(at, len)| {
+            let keep = *at >= end;
+            if keep {
+                end = at + len;
+            }
+            keep
+        });
+    }
+    found
+}
+
+/// Whether the match at `at` sits in `\r\n` text: its own line breaks if it
+/// has any, else the line it is on, else the file's more common ending.
+fn crlf_at(content: &str, at: usize, len: usize) -> bool {
+    let matched = &content[at..at + len];
+    if matched.contains('\n') {
+        return matched.contains("\r\n");
+    }
+    match content[at + len..].find('\n') {
+        Some(end) => content[..at + len + end].ends_with('\r'),
+        None => content.matches("\r\n").count() * 2 > content.matches('\n').count(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Line diff of `old` against `new` (longest common subsequence).
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffLine<'a>> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..].iter().rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (a, b) = (&old[prefix..old.len() - suffix], &new[prefix..new.len() - suffix]);
+
+    let mut out: Vec<DiffLine> = old[..prefix].iter().map(|l| DiffLine::Same(l)).collect();
+    if a.len().saturating_mul(b.len()) > MAX_DIFF_CELLS {
+        out.extend(a.iter().map(|l| DiffLine::Removed(l)));
+        out.extend(b.iter().map(|l| DiffLine::Added(l)));
+    } else {
+        // lcs[i * w + j]: common lines of a[i..] and b[j..]
+        let (n, m, w) = (a.len(), b.len(), b.len() + 1);
+        let mut lcs = vec![0u32; (n + 1) * w];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i * w + j] = if a[i] == b[j] {
+                    lcs[(i + 1) * w + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * w + j].max(lcs[i * w + j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && a[i] == b[j] {
+                out.push(DiffLine::Same(a[i]));
+                i += 1;
+                j += 1;
+            } else if i < n && (j == m || lcs[(i + 1) * w + j] >= lcs[i * w + j + 1]) {
+                out.push(DiffLine::Removed(a[i]));
+                i += 1;
+            } else {
+                out.push(DiffLine::Added(b[j]));
+                j += 1;
+            }
+        }
+    }
+    out.extend(old[old.len() - suffix..].iter().map(|l| DiffLine::Same(l)));
+    out
+}
+
+/// Unified diff of a file's change; empty when nothing changed.
+pub fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let lines = diff_lines(&old_lines, &new_lines);
+    let changed: Vec<usize> = lines.iter().enumerate()
+        .filter(|(_, l)| !matches!(l, DiffLine::Same(_)))
+        .map(|(i, _)| i)
+        .collect();
+    if changed.is_empty() {
+        return String::new();
+    }
+
+    // Hunks as ranges of `lines`, merged when their context would overlap
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &i in &changed {
+        let (start, end) = (i.saturating_sub(DIFF_CONTEXT), (i + DIFF_CONTEXT + 1).min(lines.len()));
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut out = format!("--- a/{}\n+++ b/{}\n", path, path);
+    for (start, end) in hunks {
+        let in_old = |l: &DiffLine| !matches!(l, DiffLine::Added(_));
+        let in_new = |l: &DiffLine| !matches!(l, DiffLine::Removed(_));
+        let old_start = lines[..start].iter().filter(|l| in_old(l)).count();
+        let new_start = lines[..start].iter().filter(|l| in_new(l)).count();
+        let old_len = lines[start..end].iter().filter(|l| in_old(l)).count();
+        let new_len = lines[start..end].iter().filter(|l| in_new(l)).count();
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start + usize::from(old_len > 0), old_len,
+            new_start + usize::from(new_len > 0), new_len,
+        ));
+        for line in &lines[start..end] {
+            let (sign, text) = match line {
+                DiffLine::Same(t) => (' ', t),
+                DiffLine::Removed(t) => ('-', t),
+                DiffLine::Added(t) => ('+', t),
+            };
+            out.push(sign);
+            out.push_str(text);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Replace `path` with `content` via a temp file in the same directory,
+/// keeping the original permissions.
+async fn write_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+    let permissions = tokio::fs::metadata(path).await?.permissions();
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let tmp = path.with_file_name(format!(".{}.{}.tmp", name, &uuid::Uuid::new_v4().to_string()[..8]));
+    let written = async {
+        tokio::fs::write(&tmp, content).await?;
+        tokio::fs::set_permissions(&tmp, permissions).await?;
+        tokio::fs::rename(&tmp, path).await
+    }.await;
+    if written.is_err() {
+        let _ = tokio::fs::remove_file(&tmp).await;
+    }
+    written
+}
+
+fn error(content: impl Into<String>) -> ToolResult {
+    ToolResult { content: content.into(), is_error: true, metadata: HashMap::new() }
+}
+
+/// Apply `edits` to `file_path`: all of them or, if any fails, none.
+async fn edit_file(file_path: &str, edits: &[EditOp], ctx: &ToolContext) -> ToolResult {
+    if file_path.is_empty() || edits.is_empty() {
+        return error("file_path and old_string are required");
+    }
+    let resolved = match allowed_path(file_path, ctx) {
+        Ok(path) => path,
+        Err(result) => return result,
+    };
+    let original = match tokio::fs::read_to_string(&resolved).await {
+        Ok(content) => content,
+        Err(e) => return error(format!("Error reading {}: {}", file_path, e)),
+    };
+
+    let (after, replacements) = match apply_edits(&original, edits) {
+        Ok(done) => done,
+        Err((_, e)) if edits.len() == 1 => return error(e.to_string()),
+        Err((i, e)) => return error(format!("Edit {} of {}: {}; no changes were made", i + 1, edits.len(), e)),
+    };
+    if let Err(e) = write_atomic(&resolved, &after).await {
+        return error(format!("Error writing {}: {}", file_path, e));
+    }
+
+    let mut metadata = HashMap::new();
+    metadata.insert("replacements".into(), json!(replacements));
+    let lf = |s: &str| s.replace("\r\n", "\n");
+    metadata.insert("diff".into(), json!(unified_diff(file_path, &lf(&original), &lf(&after))));
+    let noun = if replacements == 1 { "replacement" } else { "replacements" };
+    ToolResult {
+        content: format!("Successfully edited {} ({} {})", file_path, replacements, noun),
+        is_error: false,
+        metadata,
+    }
+}
+
+fn file_path(input: &serde_json::Value) -> &str {
+    input.get("file_path").or_else(|| input.get("path")).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+/// The `Edit` tool.
+pub struct EditTool;
+
+#[async_trait]
+impl Tool for EditTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "Edit".into(),
+            description: "Edit a file by replacing exact text. old_string must match exactly once unless replace_all is set. Returns a diff of the change.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "file_path": { "type": "string", "description": "Path to the file to edit" },
+                    "old_string": { "type": "string", "description": "Exact text to find and replace" },
+                    "new_string": { "type": "string", "description": "New text to replace with" },
+                    "replace_all": { "type": "boolean", "description": "Replace every occurrence of old_string" }
+                },
+                "required": ["file_path", "old_string", "new_string"]
+            }),
+        }
+    }
+
+    fn aliases(&self) -> &'static [(&'static str, &'static str)] {
+        &[("path", "file_path"), ("oldText", "old_string"), ("newText", "new_string"), ("replaceAll", "replace_all")]
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+        execute_edit(input, ctx).await
+    }
+}
+
+/// Execute the Edit tool.
+pub async fn execute_edit(input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+    edit_file(file_path(input), &[EditOp::from_input(input)], ctx).await
+}
+
+/// The `MultiEdit` tool.
+pub struct MultiEditTool;
+
+#[async_trait]
+impl Tool for MultiEditTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "MultiEdit".into(),
+            description: "Make several exact-text edits to one file in order. Either all edits apply or none do. Returns a diff of the change.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "file_path": { "type": "string", "description": "Path to the file to edit" },
+                    "edits": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "old_string": { "type": "string" },
+                                "new_string": { "type": "string" },
+                                "replace_all": { "type": "boolean" }
+                            },
+                            "required": ["old_string", "new_string"]
+                        }
+                    }
+                },
+                "required": ["file_path", "edits"]
+            }),
+        }
+    }
+
+    fn aliases(&self) -> &'static [(&'static str, &'static str)] {
+        &[("path", "file_path"), ("oldText", "old_string"), ("newText", "new_string"), ("replaceAll", "replace_all")]
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+        execute_multi_edit(input, ctx).await
+    }
+}
+
+/// Execute the MultiEdit tool: several edits to one file, applied in order.
+pub async fn execute_multi_edit(input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+    let edits: Vec<EditOp> = input.get("edits").and_then(|v| v.as_array())
+        .map(|edits| edits.iter().map(EditOp::from_input).collect())
+        .unwrap_or_default();
+    if edits.is_empty() {
+        return error("edits must be a non-empty array");
+    }
+    edit_file(file_path(input), &edits, ctx).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn op(old: &str, new: &str, replace_all: bool) -> EditOp {
+        EditOp { old: old.into(), new: new.into(), replace_all }
+    }
+
+    #[test]
+    fn requires_unique_matches() {
+        let content = "let a = 1;\nlet b = 1;\nlet c = 2;\n";
+        assert_eq!(apply_edits(content, &[op("= 1", "= 3", false)]), Err((0, EditError::Ambiguous(vec![1, 2]))));
+        assert!(EditError::Ambiguous(vec![1, 2]).to_string().contains("matches 2 times (lines 1, 2)"));
+        assert_eq!(
+            apply_edits(content, &[op("= 1", "= 3", true)]).unwrap(),
+            ("let a = 3;\nlet b = 3;\nlet c = 2;\n".to_string(), 2),
+        );
+        // Later edits see earlier ones; any failure rejects the batch
+        let batch = [op("let c", "let d", false), op("let d = 2", "let d = 4", false)];
+        assert_eq!(apply_edits(content, &batch).unwrap().0, "let a = 1;\nlet b = 1;\nlet d = 4;\n");
+        assert_eq!(apply_edits(content, &[batch[0].clone(), op("zzz", "y", false)]), Err((1, EditError::NotFound)));
+        assert_eq!(apply_edits(content, &[op("a", "a", false)]), Err((0, EditError::NoChange)));
+    }
+
+    #[test]
+    fn diffs_changed_lines() {
+        let old = (1..=12).map(|i| format!("line {}\n", i)).collect::<String>();
+        let new = old.replace("line 2\n", "line two\n").replace("line 11\n", "");
+        let diff = unified_diff("notes.txt", &old, &new);
+        assert!(diff.starts_with("--- a/notes.txt\n+++ b/notes.txt\n@@ -1,5 +1,5 @@\n line 1\n-line 2\n+line two\n line 3\n"));
+        assert!(diff.contains("@@ -8,5 +8,4 @@\n line 8\n line 9\n line 10\n-line 11\n line 12\n"), "{}", diff);
+        assert_eq!(unified_diff("x", "same\n", "same\n"), "");
+    }
+
+    #[tokio::test]
+    async fn keeps_line_endings_and_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.sh");
+        std::fs::write(&path, "echo one\r\necho two\r\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o750)).unwrap();
+        let ctx = ToolContext::new(dir.path().to_str().unwrap());
+
+        let input = json!({ "file_path": "run.sh", "edits": [
+            { "old_string": "echo one\necho two", "new_string": "echo 1\necho 2" },
+            { "old_string": "echo 2", "new_string": "echo 2\necho 3" },
+        ]});
+        let result = execute_multi_edit(&input, &ctx).await;
+        assert!(!result.is_error, "{}", result.content);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "echo 1\r\necho 2\r\necho 3\r\n");
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o750);
+        assert!(result.metadata["diff"].as_str().unwrap().contains("-echo one\n-echo two\n+echo 1\n+echo 2\n+echo 3\n"));
+
+        let failed = execute_multi_edit(&json!({ "file_path": "run.sh", "edits": [
+            { "old_string": "echo 1", "new_string": "echo one" },
+            { "old_string": "missing", "new_string": "x" },
+        ]}), &ctx).await;
+        assert_eq!(failed.content, "Edit 2 of 2: old_string not found in file; no changes were made");
+        assert!(std::fs::read_to_string(&path).unwrap().starts_with("echo 1\r\n"));
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1, "no temp files left behind");
+    }
+
+    #[test]
+    fn mixed_line_endings_are_left_alone() {
+        let content = "a\r\nb\r\nc\nd\ne\r\n";
+        let edit = |old: &str, new: &str| apply_edits(content, &[op(old, new, false)]).unwrap().0;
+        assert_eq!(edit("a\nb", "A\nB"), "A\r\nB\r\nc\nd\ne\r\n");
+        assert_eq!(edit("c\nd", "C\nX\nD"), "a\r\nb\r\nC\nX\nD\ne\r\n");
+        assert_eq!(edit("b", "b\nb2"), "a\r\nb\r\nb2\r\nc\nd\ne\r\n");
+        assert_eq!(edit("d", "d\nd2"), "a\r\nb\r\nc\nd\nd2\ne\r\n");
+        assert_eq!(edit("\nc", "\nC"), "a\r\nb\r\nC\nd\ne\r\n");
+    }
+}
@@ -1,22 +1,168 @@
-use super::ToolResult;
+use super::message::MessagePolicy;
+use super::sandbox::SandboxPolicy;
+use super::web_fetch::WebFetchOptions;
+use super::web_search::WebSearch;
+use super::{Tool, ToolResult};
+use crate::approvals::{ApprovalService, ExecOrigin};
+use crate::channel::ChannelManager;
+use crate::cron_system::CronService;
+use crate::memory::{MemoryIndex, MemoryJournal, MemorySearchOptions};
+use crate::polls::PollService;
+use crate::process::ProcessManager;
+use crate::provider::types::ToolDefinition;
+use crate::security::file_access::FileAccessPolicy;
+use crate::subagents::SubagentService;
+use crate::utils::resolve_user_path;
+use async_trait::async_trait;
 use std::collections::HashMap;
-use std::path::Path;
-use std::process::Stdio;
-use tokio::process::Command;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
 use tracing::debug;
 
-/// Execute a tool call by name with given input.
+/// Gateway services a tool call may need beyond its input.
+#[derive(Clone, Default)]
+pub struct ToolContext {
+    pub workspace_dir: String,
+    /// Where Read/Write/Edit may go.
+    pub files: FileAccessPolicy,
+    pub polls: Option<PollService>,
+    pub channels: Option<Arc<RwLock<ChannelManager>>>,
+    pub message_policy: MessagePolicy,
+    /// Channel and chat the current conversation came from, if any.
+    pub channel: Option<String>,
+    pub chat_id: Option<String>,
+    pub web_fetch: WebFetchOptions,
+    /// Isolation and limits for `exec`.
+    pub sandbox: SandboxPolicy,
+    pub web_search: Option<WebSearch>,
+    pub memory: MemorySearchOptions,
+    /// Embedding index; memory_search falls back to text search without one.
+    pub memory_index: Option<MemoryIndex>,
+    /// Daily-note writer; defaults to the workspace in local time.
+    pub memory_journal: Option<MemoryJournal>,
+    /// Scheduler for the `cron` tool.
+    pub cron: Option<CronService>,
+    /// Gate for `exec`; commands run unchecked without one.
+    pub approvals: Option<ApprovalService>,
+    /// Background commands for `exec` and the `process` tool.
+    pub processes: Option<ProcessManager>,
+    /// Background runs for the `sessions_spawn` tool.
+    pub subagents: Option<SubagentService>,
+    /// Session and agent the current turn runs as.
+    pub session_key: Option<String>,
+    pub agent_id: Option<String>,
+    /// Cancelled when the turn is abandoned; running tool calls stop early.
+    pub cancel: CancellationToken,
+}
+
+impl ToolContext {
+    pub fn new(workspace_dir: &str) -> Self {
+        Self {
+            workspace_dir: workspace_dir.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_files(mut self, files: FileAccessPolicy) -> Self {
+        self.files = files;
+        self
+    }
+
+    pub fn with_polls(mut self, polls: PollService) -> Self {
+        self.polls = Some(polls);
+        self
+    }
+
+    pub fn with_channels(mut self, channels: Arc<RwLock<ChannelManager>>, policy: MessagePolicy) -> Self {
+        self.channels = Some(channels);
+        self.message_policy = policy;
+        self
+    }
+
+    pub fn with_web_fetch(mut self, options: WebFetchOptions) -> Self {
+        self.web_fetch = options;
+        self
+    }
+
+    pub fn with_sandbox(mut self, sandbox: SandboxPolicy) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    pub fn with_web_search(mut self, search: WebSearch) -> Self {
+        self.web_search = Some(search);
+        self
+    }
+
+    pub fn with_memory(mut self, options: MemorySearchOptions) -> Self {
+        self.memory = options;
+        self
+    }
+
+    pub fn with_memory_index(mut self, index: MemoryIndex) -> Self {
+        self.memory_index = Some(index);
+        self
+    }
+
+    pub fn with_memory_journal(mut self, journal: MemoryJournal) -> Self {
+        self.memory_journal = Some(journal);
+        self
+    }
+
+    pub fn with_cron(mut self, cron: CronService) -> Self {
+        self.cron = Some(cron);
+        self
+    }
+
+    pub fn with_approvals(mut self, approvals: ApprovalService) -> Self {
+        self.approvals = Some(approvals);
+        self
+    }
+
+    pub fn with_processes(mut self, processes: ProcessManager) -> Self {
+        self.processes = Some(processes);
+        self
+    }
+
+    pub fn with_subagents(mut self, subagents: SubagentService) -> Self {
+        self.subagents = Some(subagents);
+        self
+    }
+
+    /// Mark the session and agent the turn runs as.
+    pub fn with_session(mut self, session_key: &str, agent_id: &str) -> Self {
+        self.session_key = Some(session_key.to_string());
+        self.agent_id = Some(agent_id.to_string());
+        self
+    }
+
+    /// Token that cancels this turn's tool calls.
+    pub fn with_cancel(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Mark the channel/chat the conversation originated from.
+    pub fn with_origin(mut self, channel: &str, chat_id: &str) -> Self {
+        self.channel = Some(channel.to_string());
+        self.chat_id = Some(chat_id.to_string());
+        self
+    }
+}
+
+/// Execute a built-in tool call by name with given input.
 pub async fn execute_tool(
     name: &str,
     input: &serde_json::Value,
-    workspace_dir: &str,
+    ctx: &ToolContext,
 ) -> ToolResult {
-    match name {
-        "Read" => execute_read(input, workspace_dir).await,
-        "Write" => execute_write(input, workspace_dir).await,
-        "Edit" => execute_edit(input, workspace_dir).await,
-        "exec" => execute_exec(input, workspace_dir).await,
-        _ => ToolResult {
+    static BUILTINS: OnceLock<Vec<Arc<dyn Tool>>> = OnceLock::new();
+    let builtins = BUILTINS.get_or_init(super::builtin::builtin_tools);
+    match builtins.iter().find(|tool| tool.definition().name == name) {
+        Some(tool) => super::run_tool(tool.as_ref(), input, ctx).await,
+        None => ToolResult {
             content: format!("Unknown tool: {}", name),
             is_error: true,
             metadata: HashMap::new(),
@@ -24,7 +170,123 @@ pub async fn execute_tool(
     }
 }
 
-async fn execute_read(input: &serde_json::Value, workspace_dir: &str) -> ToolResult {
+/// The `Read` tool.
+pub struct ReadTool;
+
+#[async_trait]
+impl Tool for ReadTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "Read".into(),
+            description: "Read the contents of a file.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "file_path": { "type": "string", "description": "Path to the file to read" },
+                    "offset": { "type": "number", "description": "Line number to start reading from (1-indexed)" },
+                    "limit": { "type": "number", "description": "Maximum number of lines to read" }
+                },
+                "required": ["file_path"]
+            }),
+        }
+    }
+
+    fn aliases(&self) -> &'static [(&'static str, &'static str)] {
+        &[("path", "file_path")]
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+        execute_read(input, ctx).await
+    }
+}
+
+/// The `Write` tool.
+pub struct WriteTool;
+
+#[async_trait]
+impl Tool for WriteTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "Write".into(),
+            description: "Write content to a file. Creates the file if it doesn't exist, overwrites if it does.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "file_path": { "type": "string", "description": "Path to the file to write" },
+                    "content": { "type": "string", "description": "Content to write to the file" }
+                },
+                "required": ["file_path", "content"]
+            }),
+        }
+    }
+
+    fn aliases(&self) -> &'static [(&'static str, &'static str)] {
+        &[("path", "file_path")]
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+        execute_write(input, ctx).await
+    }
+}
+
+/// The `exec` tool; calls go through the approval gate first.
+pub struct ExecTool;
+
+#[async_trait]
+impl Tool for ExecTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "exec".into(),
+            description: "Execute shell commands.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "command": { "type": "string", "description": "Shell command to execute" },
+                    "workdir": { "type": "string", "description": "Working directory" },
+                    "timeout": { "type": "number", "description": "Timeout in seconds" },
+                    "background": { "type": "boolean", "description": "Start the command and return a session id at once; manage it with the process tool" }
+                },
+                "required": ["command"]
+            }),
+        }
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+        match authorize_exec(input, ctx).await {
+            Ok(()) => execute_exec(input, ctx).await,
+            Err(result) => result,
+        }
+    }
+}
+
+/// The `poll_results` tool.
+pub struct PollResultsTool;
+
+#[async_trait]
+impl Tool for PollResultsTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "poll_results".into(),
+            description: "Get vote counts for a poll sent with the message tool. Omit pollId to list recent polls.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "pollId": { "type": "string", "description": "Poll ID returned when the poll was sent" }
+                }
+            }),
+        }
+    }
+
+    fn aliases(&self) -> &'static [(&'static str, &'static str)] {
+        &[("poll_id", "pollId")]
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+        execute_poll_results(input, ctx).await
+    }
+}
+
+async fn execute_read(input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
     let file_path = input.get("file_path")
         .or_else(|| input.get("path"))
         .and_then(|v| v.as_str())
@@ -38,7 +300,10 @@ async fn execute_read(input: &serde_json::Value, workspace_dir: &str) -> ToolRes
         };
     }
 
-    let resolved = resolve_path(file_path, workspace_dir);
+    let resolved = match allowed_path(file_path, ctx) {
+        Ok(path) => path,
+        Err(result) => return result,
+    };
     let offset = input.get("offset").and_then(|v| v.as_u64()).unwrap_or(1) as usize;
     let limit = input.get("limit").and_then(|v| v.as_u64()).unwrap_or(2000) as usize;
 
@@ -67,7 +332,7 @@ async fn execute_read(input: &serde_json::Value, workspace_dir: &str) -> ToolRes
     }
 }
 
-async fn execute_write(input: &serde_json::Value, workspace_dir: &str) -> ToolResult {
+async fn execute_write(input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
     let file_path = input.get("file_path")
         .or_else(|| input.get("path"))
         .and_then(|v| v.as_str())
@@ -82,7 +347,10 @@ async fn execute_write(input: &serde_json::Value, workspace_dir: &str) -> ToolRe
         };
     }
 
-    let resolved = resolve_path(file_path, workspace_dir);
+    let resolved = match allowed_path(file_path, ctx) {
+        Ok(path) => path,
+        Err(result) => return result,
+    };
 
     // Create parent directories
     if let Some(parent) = Path::new(&resolved).parent() {
@@ -109,64 +377,26 @@ async fn execute_write(input: &serde_json::Value, workspace_dir: &str) -> ToolRe
     }
 }
 
-async fn execute_edit(input: &serde_json::Value, workspace_dir: &str) -> ToolResult {
-    let file_path = input.get("file_path")
-        .or_else(|| input.get("path"))
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-    let old_string = input.get("old_string")
-        .or_else(|| input.get("oldText"))
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-    let new_string = input.get("new_string")
-        .or_else(|| input.get("newText"))
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-
-    if file_path.is_empty() || old_string.is_empty() {
-        return ToolResult {
-            content: "file_path and old_string are required".into(),
-            is_error: true,
-            metadata: HashMap::new(),
-        };
-    }
-
-    let resolved = resolve_path(file_path, workspace_dir);
-
-    match tokio::fs::read_to_string(&resolved).await {
-        Ok(content) => {
-            if !content.contains(old_string) {
-                return ToolResult {
-                    content: "old_string not found in file".into(),
-                    is_error: true,
-                    metadata: HashMap::new(),
-                };
-            }
-            let new_content = content.replacen(old_string, new_string, 1);
-            match tokio::fs::write(&resolved, &new_content).await {
-                Ok(_) => ToolResult {
-                    content: format!("Successfully edited {}", file_path),
-                    is_error: false,
-                    metadata: HashMap::new(),
-                },
-                Err(e) => ToolResult {
-                    content: format!("Error writing {}: {}", file_path, e),
-                    is_error: true,
-                    metadata: HashMap::new(),
-                },
-            }
-        }
-        Err(e) => ToolResult {
-            content: format!("Error reading {}: {}", file_path, e),
-            is_error: true,
-            metadata: HashMap::new(),
-        },
+/// Ask the approval gate about an exec call; `Err` is the tool result to
+/// return instead of running it.
+async fn authorize_exec(input: &serde_json::Value, ctx: &ToolContext) -> Result<(), ToolResult> {
+    let Some(approvals) = &ctx.approvals else { return Ok(()) };
+    let command = input.get("command").and_then(|v| v.as_str()).unwrap_or("");
+    if command.is_empty() {
+        return Ok(());
     }
+    let workdir = input.get("workdir").and_then(|v| v.as_str()).unwrap_or(&ctx.workspace_dir);
+    let origin = ExecOrigin { session_key: ctx.session_key.clone(), agent_id: ctx.agent_id.clone() };
+    approvals.authorize(command, workdir, origin).await.map_err(|e| ToolResult {
+        content: e.to_string(),
+        is_error: true,
+        metadata: HashMap::new(),
+    })
 }
 
-async fn execute_exec(input: &serde_json::Value, workspace_dir: &str) -> ToolResult {
+async fn execute_exec(input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
     let command = input.get("command").and_then(|v| v.as_str()).unwrap_or("");
-    let workdir = input.get("workdir").and_then(|v| v.as_str()).unwrap_or(workspace_dir);
+    let workdir = input.get("workdir").and_then(|v| v.as_str()).unwrap_or(&ctx.workspace_dir);
     let timeout_secs = input.get("timeout").and_then(|v| v.as_u64()).unwrap_or(30);
 
     if command.is_empty() {
@@ -177,63 +407,120 @@ async fn execute_exec(input: &serde_json::Value, workspace_dir: &str) -> ToolRes
         };
     }
 
+    if input.get("background").and_then(|v| v.as_bool()).unwrap_or(false) {
+        debug!("Starting in background: {} in {}", command, workdir);
+        return super::process::start_background(command, workdir, ctx).await;
+    }
+
     debug!("Executing: {} in {}", command, workdir);
 
-    let result = tokio::time::timeout(
-        std::time::Duration::from_secs(timeout_secs),
-        Command::new("bash")
-            .arg("-c")
-            .arg(command)
-            .current_dir(workdir)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-    ).await;
+    let mut metadata = HashMap::new();
+    metadata.insert("sandbox".into(), ctx.sandbox.metadata());
+    let result = ctx.sandbox.run(command, workdir, &ctx.workspace_dir, std::time::Duration::from_secs(timeout_secs)).await;
 
     match result {
-        Ok(Ok(output)) => {
-            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-            let exit_code = output.status.code().unwrap_or(-1);
-
-            let content = if stderr.is_empty() {
-                stdout
-            } else if stdout.is_empty() {
-                stderr
+        Ok(output) => {
+            let mut content = if output.stderr.is_empty() {
+                output.stdout
+            } else if output.stdout.is_empty() {
+                output.stderr
             } else {
-                format!("{}\n{}", stdout, stderr)
+                format!("{}\n{}", output.stdout, output.stderr)
             };
+            if output.truncated {
+                content.push_str(&format!("\n[output truncated at {} bytes; command stopped]", ctx.sandbox.max_output_bytes));
+            }
+            if output.timed_out {
+                content = if content.is_empty() {
+                    format!("Command timed out after {}s", timeout_secs)
+                } else {
+                    format!("Command timed out after {}s\n{}", timeout_secs, content)
+                };
+            }
 
-            let is_error = exit_code != 0;
-            let mut metadata = HashMap::new();
+            let exit_code = output.status.and_then(|s| s.code()).unwrap_or(-1);
             metadata.insert("exit_code".into(), serde_json::json!(exit_code));
-
-            ToolResult { content, is_error, metadata }
+            if output.truncated {
+                metadata.insert("truncated".into(), serde_json::json!(true));
+            }
+            ToolResult { content, is_error: exit_code != 0, metadata }
         }
-        Ok(Err(e)) => ToolResult {
+        Err(e) => ToolResult {
             content: format!("Error executing command: {}", e),
             is_error: true,
-            metadata: HashMap::new(),
+            metadata,
         },
-        Err(_) => ToolResult {
-            content: format!("Command timed out after {}s", timeout_secs),
+    }
+}
+
+async fn execute_poll_results(input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+    let Some(polls) = &ctx.polls else {
+        return ToolResult {
+            content: "Polls are not available in this context".into(),
             is_error: true,
             metadata: HashMap::new(),
+        };
+    };
+
+    let poll_id = input.get("pollId")
+        .or_else(|| input.get("poll_id"))
+        .and_then(|v| v.as_str());
+
+    match poll_id {
+        Some(id) => match polls.results(id).await {
+            Ok(results) => {
+                let mut metadata = HashMap::new();
+                metadata.insert("results".into(), serde_json::json!(results));
+                ToolResult { content: results.to_text(), is_error: false, metadata }
+            }
+            Err(e) => ToolResult {
+                content: e.to_string(),
+                is_error: true,
+                metadata: HashMap::new(),
+            },
         },
+        None => {
+            let list = polls.list().await;
+            let content = if list.is_empty() {
+                "No polls have been sent".to_string()
+            } else {
+                list.iter().take(20).map(|p| {
+                    let status = if p.closed { "closed" } else { "open" };
+                    format!("{} — {} ({}, {} voters)", p.id, p.question, status, p.votes.len())
+                }).collect::<Vec<_>>().join("\n")
+            };
+            let mut metadata = HashMap::new();
+            metadata.insert("count".into(), serde_json::json!(list.len()));
+            ToolResult { content, is_error: false, metadata }
+        }
     }
 }
 
+/// Resolve a file tool path and check it against the context's file policy;
+/// `Err` is the tool result to return instead.
+pub(super) fn allowed_path(file_path: &str, ctx: &ToolContext) -> Result<PathBuf, ToolResult> {
+    let resolved = resolve_path(file_path, &ctx.workspace_dir);
+    ctx.files.check(Path::new(&resolved), &ctx.workspace_dir).map_err(|e| ToolResult {
+        content: e.to_string(),
+        is_error: true,
+        metadata: HashMap::new(),
+    })
+}
+
 fn resolve_path(path: &str, workspace_dir: &str) -> String {
-    if Path::new(path).is_absolute() {
+    if path.starts_with('~') {
+        resolve_user_path(path).to_string_lossy().to_string()
+    } else if Path::new(path).is_absolute() {
         path.to_string()
     } else {
-        Path::new(workspace_dir).join(path).to_string_lossy().to_string()
+        resolve_user_path(workspace_dir).join(path).to_string_lossy().to_string()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tools::edit::execute_edit;
 
     #[tokio::test]
     async fn execute_read_file() {
@@ -244,7 +531,7 @@ mod tests {
         let input = serde_json::json!({
             "file_path": file_path.to_str().unwrap()
         });
-        let result = execute_read(&input, dir.path().to_str().unwrap()).await;
+        let result = execute_read(&input, &ToolContext::new(dir.path().to_str().unwrap())).await;
         assert!(!result.is_error);
         assert!(result.content.contains("line1"));
     }
@@ -260,7 +547,7 @@ mod tests {
             "offset": 2,
             "limit": 2
         });
-        let result = execute_read(&input, dir.path().to_str().unwrap()).await;
+        let result = execute_read(&input, &ToolContext::new(dir.path().to_str().unwrap())).await;
         assert!(!result.is_error);
         assert!(result.content.contains("line2"));
         assert!(result.content.contains("line3"));
@@ -276,7 +563,7 @@ mod tests {
             "file_path": file_path.to_str().unwrap(),
             "content": "hello world"
         });
-        let result = execute_write(&input, dir.path().to_str().unwrap()).await;
+        let result = execute_write(&input, &ToolContext::new(dir.path().to_str().unwrap())).await;
         assert!(!result.is_error);
         assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "hello world");
     }
@@ -290,7 +577,7 @@ mod tests {
             "file_path": file_path.to_str().unwrap(),
             "content": "nested"
         });
-        let result = execute_write(&input, dir.path().to_str().unwrap()).await;
+        let result = execute_write(&input, &ToolContext::new(dir.path().to_str().unwrap())).await;
         assert!(!result.is_error);
     }
 
@@ -305,7 +592,7 @@ mod tests {
             "old_string": "world",
             "new_string": "rust"
         });
-        let result = execute_edit(&input, dir.path().to_str().unwrap()).await;
+        let result = execute_edit(&input, &ToolContext::new(dir.path().to_str().unwrap())).await;
         assert!(!result.is_error);
         assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "hello rust");
     }
@@ -321,7 +608,7 @@ mod tests {
             "old_string": "nonexistent",
             "new_string": "replacement"
         });
-        let result = execute_edit(&input, dir.path().to_str().unwrap()).await;
+        let result = execute_edit(&input, &ToolContext::new(dir.path().to_str().unwrap())).await;
         assert!(result.is_error);
     }
 
@@ -331,7 +618,7 @@ mod tests {
         let input = serde_json::json!({
             "command": "echo hello"
         });
-        let result = execute_exec(&input, dir.path().to_str().unwrap()).await;
+        let result = execute_exec(&input, &ToolContext::new(dir.path().to_str().unwrap())).await;
         assert!(!result.is_error);
         assert!(result.content.contains("hello"));
     }
@@ -342,22 +629,125 @@ mod tests {
         let input = serde_json::json!({
             "command": "false"
         });
-        let result = execute_exec(&input, dir.path().to_str().unwrap()).await;
+        let result = execute_exec(&input, &ToolContext::new(dir.path().to_str().unwrap())).await;
         assert!(result.is_error);
     }
 
+    #[tokio::test]
+    async fn exec_goes_through_approvals() {
+        use crate::approvals::ApprovalMode;
+        use crate::config::ApprovalsConfig;
+        let dir = tempfile::tempdir().unwrap();
+        let approvals = crate::approvals::ApprovalService::from_config(&ApprovalsConfig {
+            mode: Some("on-miss".into()),
+            allow: Some(vec!["echo *".into()]),
+            deny: Some(vec!["touch *".into()]),
+            timeout_seconds: Some(1),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(approvals.mode(), ApprovalMode::OnMiss);
+        let ctx = ToolContext::new(dir.path().to_str().unwrap()).with_approvals(approvals);
+
+        let allowed = execute_tool("exec", &serde_json::json!({ "command": "echo ok" }), &ctx).await;
+        assert!(!allowed.is_error && allowed.content.contains("ok"));
+        let blocked = execute_tool("exec", &serde_json::json!({ "command": "touch marker" }), &ctx).await;
+        assert!(blocked.is_error && blocked.content.contains("blocked"));
+        let unanswered = execute_tool("exec", &serde_json::json!({ "command": "pwd" }), &ctx).await;
+        assert!(unanswered.is_error && unanswered.content.contains("not run"));
+        assert!(!dir.path().join("marker").exists());
+    }
+
+    #[tokio::test]
+    async fn file_tools_stay_in_the_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let ws = dir.path().join("ws");
+        std::fs::create_dir(&ws).unwrap();
+        std::fs::write(dir.path().join("outside.txt"), "secret").unwrap();
+        let ctx = ToolContext::new(ws.to_str().unwrap());
+
+        let wrote = execute_tool("Write", &serde_json::json!({ "file_path": "notes/a.md", "content": "hi" }), &ctx).await;
+        assert!(!wrote.is_error, "{}", wrote.content);
+        assert_eq!(std::fs::read_to_string(ws.join("notes/a.md")).unwrap(), "hi");
+
+        for (tool, input) in [
+            ("Read", serde_json::json!({ "file_path": "../outside.txt" })),
+            ("Write", serde_json::json!({ "file_path": dir.path().join("new.txt"), "content": "x" })),
+            ("Edit", serde_json::json!({ "file_path": "../outside.txt", "old_string": "secret", "new_string": "x" })),
+        ] {
+            let result = execute_tool(tool, &input, &ctx).await;
+            assert!(result.is_error && result.content.contains("outside the workspace"), "{}: {}", tool, result.content);
+        }
+        assert!(!dir.path().join("new.txt").exists());
+        assert_eq!(std::fs::read_to_string(dir.path().join("outside.txt")).unwrap(), "secret");
+
+        // A dangling link would create its target on write
+        std::os::unix::fs::symlink(dir.path().join("planted.txt"), ws.join("dangling")).unwrap();
+        let planted = execute_tool("Write", &serde_json::json!({ "file_path": "dangling", "content": "x" }), &ctx).await;
+        assert!(planted.is_error && planted.content.contains("through a symlink"), "{}", planted.content);
+        assert!(!dir.path().join("planted.txt").exists());
+
+        let open = ctx.clone().with_files(FileAccessPolicy::new(false, &[], &[]));
+        assert_eq!(execute_tool("Read", &serde_json::json!({ "file_path": "../outside.txt" }), &open).await.content, "secret");
+    }
+
     #[tokio::test]
     async fn execute_unknown_tool() {
-        let result = execute_tool("nonexistent", &serde_json::json!({}), "/tmp").await;
+        let result = execute_tool("nonexistent", &serde_json::json!({}), &ToolContext::new("/tmp")).await;
         assert!(result.is_error);
         assert!(result.content.contains("Unknown tool"));
     }
 
+    #[tokio::test]
+    async fn builtin_input_is_validated() {
+        let ctx = ToolContext::new("/tmp");
+        let result = execute_tool("Read", &serde_json::json!({ "limit": "ten" }), &ctx).await;
+        assert!(result.is_error);
+        assert_eq!(
+            result.content,
+            "Invalid input for tool 'Read': missing required property 'file_path'; 'limit' must be number, got string \"ten\"",
+        );
+        let result = execute_tool("exec", &serde_json::json!({ "command": "echo hi", "background": "yes" }), &ctx).await;
+        assert!(result.content.contains("'background' must be boolean"), "{}", result.content);
+    }
+
+    #[tokio::test]
+    async fn poll_results_reports_tallies() {
+        use crate::channel::{mock::MockChannel, ChannelCapabilities, ChannelManager};
+        use crate::polls::PollInput;
+
+        let mut mgr = ChannelManager::new();
+        mgr.register(Box::new(MockChannel::new("plain", ChannelCapabilities::default())));
+        let polls = PollService::new();
+        let poll = polls.create(&mgr, "plain", "+1555", &PollInput {
+            question: "Lunch?".into(),
+            options: vec!["Pizza".into(), "Sushi".into()],
+            max_selections: None,
+            duration_seconds: None,
+            duration_hours: None,
+        }).await.unwrap();
+        polls.record_vote(&poll.id, "+1", vec![1]).await.unwrap();
+
+        let ctx = ToolContext::new("/tmp").with_polls(polls);
+        let result = execute_tool("poll_results", &serde_json::json!({ "pollId": poll.id }), &ctx).await;
+        assert!(!result.is_error);
+        assert!(result.content.contains("2. Sushi — 1"));
+
+        let listing = execute_tool("poll_results", &serde_json::json!({}), &ctx).await;
+        assert!(listing.content.contains("Lunch?"));
+    }
+
     #[test]
     fn resolve_path_absolute() {
         assert_eq!(resolve_path("/tmp/file.txt", "/workspace"), "/tmp/file.txt");
     }
 
+    #[test]
+    fn resolve_path_expands_home() {
+        let home = resolve_user_path("~");
+        assert_eq!(resolve_path("~/notes.md", "/workspace"), home.join("notes.md").to_string_lossy());
+        assert_eq!(resolve_path("a.md", "~/ws"), home.join("ws/a.md").to_string_lossy());
+    }
+
     #[test]
     fn resolve_path_relative() {
         let p = resolve_path("file.txt", "/workspace");
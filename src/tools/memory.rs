@@ -0,0 +1,411 @@
+use super::executor::ToolContext;
+use super::{Tool, ToolResult};
+use crate::memory::journal::{EntryKind, MemoryEntry, MemoryJournal};
+use crate::memory::{read_memory_span, search_memory_with, SearchResult};
+use crate::provider::types::ToolDefinition;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tracing::warn;
+
+const DEFAULT_SEARCH_LIMIT: usize = 8;
+const MAX_SEARCH_LIMIT: usize = 50;
+const MAX_SNIPPET_CHARS: usize = 700;
+const DEFAULT_GET_LINES: usize = 100;
+
+fn disabled() -> ToolResult {
+    ToolResult {
+        content: "Memory search is disabled (agents.defaults.memorySearch.enabled = false)".into(),
+        is_error: true,
+        metadata: HashMap::new(),
+    }
+}
+
+fn truncate_snippet(text: &str) -> String {
+    if text.chars().count() <= MAX_SNIPPET_CHARS {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(MAX_SNIPPET_CHARS).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// The `memory_search` tool.
+pub struct MemorySearchTool;
+
+#[async_trait]
+impl Tool for MemorySearchTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "memory_search".into(),
+            description: "Search memory and knowledge files. Returns ranked snippets with file paths and line numbers.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Search query" },
+                    "limit": { "type": "number", "description": "Max results" }
+                },
+                "required": ["query"]
+            }),
+        }
+    }
+
+    fn aliases(&self) -> &'static [(&'static str, &'static str)] {
+        &[("maxResults", "limit")]
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+        execute_memory_search(input, ctx).await
+    }
+}
+
+/// Execute the memory_search tool.
+pub async fn execute_memory_search(input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+    if !ctx.memory.enabled {
+        return disabled();
+    }
+    let query = input.get("query").and_then(|v| v.as_str()).unwrap_or("").trim().to_string();
+    if query.is_empty() {
+        return ToolResult {
+            content: "query is required".into(),
+            is_error: true,
+            metadata: HashMap::new(),
+        };
+    }
+    let limit = input.get("limit")
+        .or_else(|| input.get("maxResults"))
+        .and_then(|v| v.as_u64())
+        .map(|l| (l as usize).clamp(1, MAX_SEARCH_LIMIT))
+        .unwrap_or(DEFAULT_SEARCH_LIMIT);
+
+    let mut vector_hits: Option<Vec<SearchResult>> = None;
+    let mut mode = "text";
+    if let Some(index) = &ctx.memory_index {
+        let searched = match index.sync_for_search().await {
+            Ok(_) => index.query(&query, limit).await,
+            Err(e) => Err(e),
+        };
+        match searched {
+            Ok(hits) => {
+                vector_hits = Some(hits);
+                mode = index.mode();
+            }
+            Err(e) => warn!("Vector memory search failed, falling back to text search: {}", e),
+        }
+    }
+    let results = match vector_hits {
+        Some(hits) => hits,
+        None => {
+            let workspace_dir = ctx.workspace_dir.clone();
+            let options = ctx.memory.clone();
+            let search_query = query.clone();
+            tokio::task::spawn_blocking(move || {
+                search_memory_with(&workspace_dir, &search_query, limit, &options)
+            }).await.unwrap_or_default()
+        }
+    };
+
+    if results.is_empty() {
+        let mut metadata = HashMap::new();
+        metadata.insert("count".into(), serde_json::json!(0));
+        metadata.insert("mode".into(), serde_json::json!(mode));
+        return ToolResult {
+            content: format!("No memory matches for \"{}\"", query),
+            is_error: false,
+            metadata,
+        };
+    }
+
+    let mut content = String::new();
+    let mut hits = Vec::new();
+    for (i, r) in results.iter().enumerate() {
+        let start = r.line_number.unwrap_or(1);
+        let end = r.end_line.unwrap_or(start);
+        let location = if start == end {
+            format!("{}:{}", r.file_path, start)
+        } else {
+            format!("{}:{}-{}", r.file_path, start, end)
+        };
+        // Search results carry a "[path]" header; the location line replaces it.
+        let body = r.content.split_once('\n').map(|(_, rest)| rest).unwrap_or(&r.content);
+        content.push_str(&format!("{}. {} (score {:.2})\n{}\n\n", i + 1, location, r.score, truncate_snippet(body)));
+        hits.push(serde_json::json!({
+            "path": r.file_path,
+            "startLine": start,
+            "endLine": end,
+            "score": r.score,
+        }));
+    }
+    content.push_str("Use memory_get with path and from/lines to read more context.");
+
+    let mut metadata = HashMap::new();
+    metadata.insert("count".into(), serde_json::json!(results.len()));
+    metadata.insert("mode".into(), serde_json::json!(mode));
+    metadata.insert("results".into(), serde_json::json!(hits));
+    ToolResult { content, is_error: false, metadata }
+}
+
+/// The `memory_get` tool.
+pub struct MemoryGetTool;
+
+#[async_trait]
+impl Tool for MemoryGetTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "memory_get".into(),
+            description: "Read lines from a memory file, e.g. to expand a memory_search hit.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Memory file path as returned by memory_search" },
+                    "from": { "type": "number", "description": "First line to read (1-indexed)" },
+                    "lines": { "type": "number", "description": "Number of lines to read" }
+                },
+                "required": ["path"]
+            }),
+        }
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+        execute_memory_get(input, ctx).await
+    }
+}
+
+/// Execute the memory_get tool: read a line range from a memory file.
+pub async fn execute_memory_get(input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+    if !ctx.memory.enabled {
+        return disabled();
+    }
+    let path = input.get("path").and_then(|v| v.as_str()).unwrap_or("").trim().to_string();
+    if path.is_empty() {
+        return ToolResult {
+            content: "path is required".into(),
+            is_error: true,
+            metadata: HashMap::new(),
+        };
+    }
+    let from = input.get("from").and_then(|v| v.as_u64()).unwrap_or(1) as usize;
+    let lines = input.get("lines").and_then(|v| v.as_u64()).unwrap_or(DEFAULT_GET_LINES as u64) as usize;
+
+    let workspace_dir = ctx.workspace_dir.clone();
+    let options = ctx.memory.clone();
+    let read_path = path.clone();
+    let span = tokio::task::spawn_blocking(move || {
+        read_memory_span(&workspace_dir, &read_path, from, lines, &options)
+    }).await;
+
+    match span {
+        Ok(Ok(span)) => {
+            let mut metadata = HashMap::new();
+            metadata.insert("path".into(), serde_json::json!(span.file_path));
+            metadata.insert("startLine".into(), serde_json::json!(span.start_line));
+            metadata.insert("endLine".into(), serde_json::json!(span.end_line));
+            metadata.insert("totalLines".into(), serde_json::json!(span.total_lines));
+            let content = format!(
+                "[{}:{}-{} of {}]\n{}",
+                span.file_path, span.start_line, span.end_line, span.total_lines, span.text,
+            );
+            ToolResult { content, is_error: false, metadata }
+        }
+        Ok(Err(e)) => ToolResult {
+            content: e.to_string(),
+            is_error: true,
+            metadata: HashMap::new(),
+        },
+        Err(e) => ToolResult {
+            content: format!("memory_get failed: {}", e),
+            is_error: true,
+            metadata: HashMap::new(),
+        },
+    }
+}
+
+fn parse_entry(value: &serde_json::Value) -> Result<MemoryEntry, String> {
+    let text = value.get("text").and_then(|v| v.as_str()).unwrap_or("").trim();
+    if text.is_empty() {
+        return Err("each entry needs text".into());
+    }
+    let kind = match value.get("kind").and_then(|v| v.as_str()) {
+        Some(kind) => EntryKind::parse(kind)
+            .ok_or_else(|| format!("unknown kind '{}' (fact, preference, decision, todo, note)", kind))?,
+        None => EntryKind::Note,
+    };
+    let tags = value.get("tags")
+        .and_then(|v| v.as_array())
+        .map(|tags| tags.iter().filter_map(|t| t.as_str().map(String::from)).collect())
+        .unwrap_or_default();
+    Ok(MemoryEntry::new(kind, text).with_tags(tags))
+}
+
+/// The `memory_append` tool.
+pub struct MemoryAppendTool;
+
+#[async_trait]
+impl Tool for MemoryAppendTool {
+    fn definition(&self) -> ToolDefinition {
+        let entry = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "kind": { "type": "string", "enum": ["fact", "preference", "decision", "todo", "note"] },
+                "text": { "type": "string", "description": "What to remember, in one or two sentences" },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["text"]
+        });
+        ToolDefinition {
+            name: "memory_append".into(),
+            description: "Record durable facts, preferences, decisions or todos in today's memory note (memory/YYYY-MM-DD.md).".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "entries": { "type": "array", "items": entry },
+                    "kind": entry["properties"]["kind"],
+                    "text": { "type": "string", "description": "Single entry shorthand" },
+                    "tags": entry["properties"]["tags"]
+                }
+            }),
+        }
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+        execute_memory_append(input, ctx).await
+    }
+}
+
+/// Execute the memory_append tool: add entries to today's daily note.
+pub async fn execute_memory_append(input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+    let parsed: Result<Vec<MemoryEntry>, String> = match input.get("entries").and_then(|v| v.as_array()) {
+        Some(entries) if !entries.is_empty() => entries.iter().map(parse_entry).collect(),
+        Some(_) => Err("entries is empty".into()),
+        None => parse_entry(input).map(|e| vec![e]),
+    };
+    let entries = match parsed {
+        Ok(entries) => entries,
+        Err(e) => {
+            return ToolResult { content: e, is_error: true, metadata: HashMap::new() };
+        }
+    };
+
+    let journal = ctx.memory_journal.clone()
+        .unwrap_or_else(|| MemoryJournal::new(&ctx.workspace_dir));
+    let appended = tokio::task::spawn_blocking(move || journal.append(&entries)).await;
+    match appended {
+        Ok(Ok(outcome)) => {
+            let file = format!("memory/{}.md", outcome.date.format("%Y-%m-%d"));
+            let mut content = format!("Appended {} entr{} to {}", outcome.written, if outcome.written == 1 { "y" } else { "ies" }, file);
+            if outcome.skipped > 0 {
+                content.push_str(&format!(" ({} already recorded)", outcome.skipped));
+            }
+            let mut metadata = HashMap::new();
+            metadata.insert("path".into(), serde_json::json!(file));
+            metadata.insert("written".into(), serde_json::json!(outcome.written));
+            metadata.insert("skipped".into(), serde_json::json!(outcome.skipped));
+            ToolResult { content, is_error: false, metadata }
+        }
+        Ok(Err(e)) => ToolResult {
+            content: e.to_string(),
+            is_error: true,
+            metadata: HashMap::new(),
+        },
+        Err(e) => ToolResult {
+            content: format!("memory_append failed: {}", e),
+            is_error: true,
+            metadata: HashMap::new(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemorySearchOptions;
+
+    fn workspace() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("memory")).unwrap();
+        std::fs::write(
+            dir.path().join("memory/2026-03-01.md"),
+            "# March 1\n\nDentist appointment moved to Friday.\nBring the insurance card.\n\nCalled mom.",
+        ).unwrap();
+        std::fs::write(dir.path().join("MEMORY.md"), "Prefers tea over coffee.").unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn search_formats_locations() {
+        let dir = workspace();
+        let ctx = ToolContext::new(dir.path().to_str().unwrap());
+        let result = execute_memory_search(&serde_json::json!({ "query": "dentist insurance" }), &ctx).await;
+        assert!(!result.is_error);
+        assert!(result.content.starts_with("1. memory/2026-03-01.md:3-4 (score 1.00)\nDentist appointment"));
+        assert_eq!(result.metadata["results"][0]["startLine"], 3);
+
+        let tea = execute_memory_search(&serde_json::json!({ "query": "tea" }), &ctx).await;
+        assert!(tea.content.contains("MEMORY.md:1"));
+    }
+
+    #[tokio::test]
+    async fn search_respects_disabled() {
+        let dir = workspace();
+        let ctx = ToolContext::new(dir.path().to_str().unwrap())
+            .with_memory(MemorySearchOptions { enabled: false, ..Default::default() });
+        let result = execute_memory_search(&serde_json::json!({ "query": "tea" }), &ctx).await;
+        assert!(result.is_error);
+    }
+
+    #[tokio::test]
+    async fn search_uses_vector_index_when_configured() {
+        use crate::memory::embedding::HashingEmbedder;
+        use crate::memory::MemoryIndex;
+        use std::sync::Arc;
+
+        let dir = workspace();
+        let ws = dir.path().to_str().unwrap();
+        let index = MemoryIndex::new(ws, MemorySearchOptions::default(), Arc::new(HashingEmbedder::default()));
+        let ctx = ToolContext::new(ws).with_memory_index(index);
+        let result = execute_memory_search(&serde_json::json!({ "query": "insurance card for the dentist" }), &ctx).await;
+        assert!(!result.is_error);
+        assert_eq!(result.metadata["mode"], "vector");
+        assert!(result.content.starts_with("1. memory/2026-03-01.md:1-6"));
+    }
+
+    #[tokio::test]
+    async fn get_reads_span() {
+        let dir = workspace();
+        let ctx = ToolContext::new(dir.path().to_str().unwrap());
+        let result = execute_memory_get(&serde_json::json!({ "path": "memory/2026-03-01.md", "from": 3, "lines": 2 }), &ctx).await;
+        assert!(!result.is_error);
+        assert_eq!(
+            result.content,
+            "[memory/2026-03-01.md:3-4 of 6]\nDentist appointment moved to Friday.\nBring the insurance card."
+        );
+
+        let outside = execute_memory_get(&serde_json::json!({ "path": "/etc/hostname" }), &ctx).await;
+        assert!(outside.is_error);
+    }
+
+    #[tokio::test]
+    async fn append_writes_structured_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = ToolContext::new(dir.path().to_str().unwrap());
+        let result = execute_memory_append(&serde_json::json!({
+            "entries": [
+                { "kind": "fact", "text": "Sister lives in Porto", "tags": ["family"] },
+                { "kind": "todo", "text": "Renew passport" }
+            ]
+        }), &ctx).await;
+        assert!(!result.is_error, "{}", result.content);
+        assert_eq!(result.metadata["written"], 2);
+
+        let path = dir.path().join(result.metadata["path"].as_str().unwrap());
+        let note = std::fs::read_to_string(path).unwrap();
+        assert!(note.contains("[fact] Sister lives in Porto #family"));
+        assert!(note.contains("[todo] Renew passport"));
+
+        let again = execute_memory_append(&serde_json::json!({ "text": "Renew passport", "kind": "todo" }), &ctx).await;
+        assert_eq!(again.metadata["skipped"], 1);
+
+        let bad = execute_memory_append(&serde_json::json!({ "text": "x", "kind": "rumor" }), &ctx).await;
+        assert!(bad.is_error);
+        let empty = execute_memory_append(&serde_json::json!({ "entries": [] }), &ctx).await;
+        assert!(empty.is_error);
+    }
+}
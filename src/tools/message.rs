@@ -0,0 +1,490 @@
+use super::executor::ToolContext;
+use super::{Tool, ToolResult};
+use crate::channel::{DispatchOutcome, OutboundAction, OutgoingMessage};
+use crate::config::OpenClawConfig;
+use crate::polls::PollInput;
+use crate::provider::types::ToolDefinition;
+use crate::utils::{normalize_e164, to_whatsapp_jid};
+use async_trait::async_trait;
+use serde_json::json;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+/// A resolved outbound destination.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageTarget {
+    pub channel: String,
+    pub to: String,
+}
+
+impl MessageTarget {
+    pub fn label(&self) -> String {
+        format!("{}:{}", self.channel, self.to)
+    }
+}
+
+/// Outbound messaging policy for the `message` tool.
+#[derive(Debug, Clone, Default)]
+pub struct MessagePolicy {
+    /// Allowed targets ("whatsapp:+1555…", "whatsapp:*", "*" or an alias).
+    pub allow: Vec<String>,
+    pub aliases: HashMap<String, String>,
+    pub default_channel: Option<String>,
+    /// JSONL file receiving one entry per cross-channel send.
+    pub audit_path: Option<PathBuf>,
+}
+
+impl MessagePolicy {
+    /// Build from `tools.message`, also allowing the owners listed in each
+    /// channel's `allowFrom` (wildcards there don't grant outbound access).
+    pub fn from_config(config: &OpenClawConfig) -> Self {
+        let message = config.tools.as_ref().and_then(|t| t.message.as_ref());
+        let mut allow = message.and_then(|m| m.allow.clone()).unwrap_or_default();
+
+        if let Some(channels) = &config.channels {
+            let owners = [
+                ("whatsapp", channels.whatsapp.as_ref().and_then(|c| c.allow_from.as_ref())),
+                ("telegram", channels.telegram.as_ref().and_then(|c| c.allow_from.as_ref())),
+            ];
+            for (channel, allow_from) in owners {
+                for entry in allow_from.into_iter().flatten() {
+                    if entry != "*" {
+                        allow.push(format!("{}:{}", channel, entry));
+                    }
+                }
+            }
+        }
+
+        Self {
+            allow,
+            aliases: message.and_then(|m| m.aliases.clone()).unwrap_or_default(),
+            default_channel: message.and_then(|m| m.default_channel.clone()),
+            audit_path: None,
+        }
+    }
+
+    pub fn with_audit_path(mut self, path: PathBuf) -> Self {
+        self.audit_path = Some(path);
+        self
+    }
+
+    /// Resolve "whatsapp:+1555…", a bare number/JID, or a contact alias.
+    pub fn resolve_target(&self, raw: &str, fallback_channel: Option<&str>) -> Result<MessageTarget, String> {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            return Err("target is required".into());
+        }
+
+        let aliased = self.aliases.iter()
+            .find(|(alias, _)| alias.eq_ignore_ascii_case(trimmed))
+            .map(|(_, target)| target.as_str())
+            .unwrap_or(trimmed);
+
+        let (channel, id) = match split_channel_prefix(aliased) {
+            Some((channel, id)) => (channel.to_string(), id),
+            None if aliased.contains('@') => ("whatsapp".to_string(), aliased),
+            None => {
+                let channel = fallback_channel
+                    .or(self.default_channel.as_deref())
+                    .ok_or_else(|| format!("target '{}' needs a channel prefix, e.g. whatsapp:+15551234567", raw))?;
+                (channel.to_string(), aliased)
+            }
+        };
+
+        let to = match channel.as_str() {
+            "whatsapp" => {
+                if !id.contains('@') {
+                    let digits = normalize_e164(id).chars().filter(|c| c.is_ascii_digit()).count();
+                    if digits < 5 {
+                        return Err(format!("invalid WhatsApp number: {}", id));
+                    }
+                }
+                to_whatsapp_jid(id)
+            }
+            _ => id.trim().to_string(),
+        };
+        if to.is_empty() {
+            return Err(format!("invalid target: {}", raw));
+        }
+
+        Ok(MessageTarget { channel, to })
+    }
+
+    /// Check a resolved target against the allowlist.
+    pub fn is_allowed(&self, target: &MessageTarget) -> bool {
+        self.allow.iter().any(|entry| {
+            let entry = entry.trim();
+            if entry == "*" || entry == format!("{}:*", target.channel) {
+                return true;
+            }
+            self.resolve_target(entry, None).is_ok_and(|allowed| &allowed == target)
+        })
+    }
+
+    fn audit(&self, entry: serde_json::Value) {
+        info!(target: "rustyclaw::audit", "cross-channel message: {}", entry);
+        let Some(path) = &self.audit_path else { return };
+        let result = (|| -> std::io::Result<()> {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", entry)
+        })();
+        if let Err(e) = result {
+            warn!("Failed to write message audit log {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Split "channel:rest" when the prefix looks like a channel name.
+fn split_channel_prefix(raw: &str) -> Option<(&str, &str)> {
+    let (prefix, rest) = raw.split_once(':')?;
+    let is_name = prefix.chars().next().is_some_and(|c| c.is_ascii_lowercase())
+        && prefix.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+    if is_name { Some((prefix, rest.trim())) } else { None }
+}
+
+fn error(content: String) -> ToolResult {
+    ToolResult { content, is_error: true, metadata: HashMap::new() }
+}
+
+fn str_param<'a>(input: &'a serde_json::Value, keys: &[&str]) -> Option<&'a str> {
+    keys.iter()
+        .find_map(|k| input.get(*k).and_then(|v| v.as_str()))
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// The `message` tool.
+pub struct MessageTool;
+
+#[async_trait]
+impl Tool for MessageTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "message".into(),
+            description: "Send messages via channel plugins. Targets look like \"whatsapp:+15551234567\" or a contact alias; omit target to reply in the current chat.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": { "type": "string", "enum": ["send", "react", "edit", "delete", "poll"] },
+                    "target": { "type": "string", "description": "Target channel/user" },
+                    "message": { "type": "string", "description": "Message text" },
+                    "messageId": { "type": "string", "description": "Message to react to, edit or delete" },
+                    "replyTo": { "type": "string", "description": "Message ID to reply to" },
+                    "emoji": { "type": "string", "description": "Reaction emoji" },
+                    "pollQuestion": { "type": "string" },
+                    "pollOptions": { "type": "array", "items": { "type": "string" } },
+                    "pollMaxSelections": { "type": "integer", "minimum": 1 },
+                    "pollDurationHours": { "type": "integer", "minimum": 1 }
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn aliases(&self) -> &'static [(&'static str, &'static str)] {
+        &[("options", "pollOptions")]
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+        execute_message(input, ctx).await
+    }
+}
+
+/// Execute the `message` tool: send, react, edit, delete or poll via `ChannelManager`.
+pub async fn execute_message(input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+    let Some(channels) = &ctx.channels else {
+        return error("Messaging is not available in this context".into());
+    };
+    let action = input.get("action").and_then(|v| v.as_str()).unwrap_or("send");
+    let policy = &ctx.message_policy;
+
+    // Without a target, reply in the chat the conversation came from
+    let target = match str_param(input, &["target", "to"]) {
+        Some(raw) => match policy.resolve_target(raw, ctx.channel.as_deref()) {
+            Ok(t) => t,
+            Err(e) => return error(e),
+        },
+        None => match (&ctx.channel, &ctx.chat_id) {
+            (Some(channel), Some(chat_id)) => MessageTarget { channel: channel.clone(), to: chat_id.clone() },
+            _ => return error("target is required".into()),
+        },
+    };
+
+    let is_origin_chat = ctx.channel.as_deref() == Some(target.channel.as_str())
+        && ctx.chat_id.as_deref().is_some_and(|chat| {
+            policy.resolve_target(chat, Some(&target.channel)).is_ok_and(|origin| origin == target)
+        });
+    if !is_origin_chat && !policy.is_allowed(&target) {
+        return error(format!(
+            "Target {} is not in the outbound allowlist (tools.message.allow)",
+            target.label()
+        ));
+    }
+
+    let channels = channels.read().await;
+    if channels.get(&target.channel).is_none() {
+        return error(format!("No plugin for channel: {}", target.channel));
+    }
+
+    let message_id = str_param(input, &["messageId", "message_id"]);
+    let text = str_param(input, &["message", "text"]);
+    let mut metadata = HashMap::new();
+
+    let result: Result<DispatchOutcome, String> = match action {
+        "send" => match text {
+            Some(text) => {
+                let mut msg = OutgoingMessage::text(&target.channel, &target.to, text);
+                msg.reply_to = str_param(input, &["replyTo", "reply_to"]).map(String::from);
+                channels.send(&msg).await.map_err(|e| e.to_string())
+            }
+            None => Err("message is required for send".into()),
+        },
+        "react" => match (message_id, str_param(input, &["emoji"])) {
+            (Some(message_id), Some(emoji)) => channels.dispatch(&OutboundAction::React {
+                channel: target.channel.clone(),
+                chat_id: target.to.clone(),
+                message_id: message_id.to_string(),
+                emoji: emoji.to_string(),
+            }).await.map_err(|e| e.to_string()),
+            _ => Err("messageId and emoji are required for react".into()),
+        },
+        "edit" => match (message_id, text) {
+            (Some(message_id), Some(text)) => channels.dispatch(&OutboundAction::Edit {
+                channel: target.channel.clone(),
+                chat_id: target.to.clone(),
+                message_id: message_id.to_string(),
+                text: text.to_string(),
+            }).await.map_err(|e| e.to_string()),
+            _ => Err("messageId and message are required for edit".into()),
+        },
+        "delete" => match message_id {
+            Some(message_id) => channels.dispatch(&OutboundAction::Delete {
+                channel: target.channel.clone(),
+                chat_id: target.to.clone(),
+                message_id: message_id.to_string(),
+            }).await.map_err(|e| e.to_string()),
+            None => Err("messageId is required for delete".into()),
+        },
+        "poll" => {
+            let poll = PollInput {
+                question: str_param(input, &["pollQuestion", "question"]).unwrap_or_default().to_string(),
+                options: input.get("pollOptions").or_else(|| input.get("options"))
+                    .and_then(|v| v.as_array())
+                    .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+                    .unwrap_or_default(),
+                // Integers per the schema, possibly written as 2.0
+                max_selections: input.get("pollMaxSelections").and_then(|v| v.as_f64()).map(|v| v as u32),
+                duration_seconds: None,
+                duration_hours: input.get("pollDurationHours").and_then(|v| v.as_f64()).map(|v| v as u32),
+            };
+            match &ctx.polls {
+                Some(polls) => polls.create(&channels, &target.channel, &target.to, &poll).await
+                    .map(|record| {
+                        metadata.insert("pollId".into(), json!(record.id));
+                        DispatchOutcome {
+                            message_ids: record.message_ids,
+                            fallback: if record.native { None } else { Some("poll".into()) },
+                        }
+                    })
+                    .map_err(|e| e.to_string()),
+                None => channels.dispatch(&OutboundAction::Poll {
+                    channel: target.channel.clone(),
+                    to: target.to.clone(),
+                    poll,
+                }).await.map_err(|e| e.to_string()),
+            }
+        }
+        other => Err(format!("Unknown message action: {}", other)),
+    };
+
+    let outcome = match result {
+        Ok(outcome) => outcome,
+        Err(e) => return error(format!("message {} to {} failed: {}", action, target.label(), e)),
+    };
+
+    if let Some(origin) = ctx.channel.as_deref().filter(|origin| *origin != target.channel) {
+        policy.audit(json!({
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "action": action,
+            "fromChannel": origin,
+            "fromChat": ctx.chat_id,
+            "channel": target.channel,
+            "to": target.to,
+            "messageIds": outcome.message_ids,
+        }));
+    }
+
+    metadata.insert("channel".into(), json!(target.channel));
+    metadata.insert("to".into(), json!(target.to));
+    metadata.insert("messageIds".into(), json!(outcome.message_ids));
+    if let Some(fallback) = &outcome.fallback {
+        metadata.insert("fallback".into(), json!(fallback));
+    }
+
+    let mut content = match action {
+        "send" => format!("Sent to {}", target.label()),
+        "poll" => format!("Poll sent to {}", target.label()),
+        other => format!("{} done in {}", other, target.label()),
+    };
+    if !outcome.message_ids.is_empty() {
+        content.push_str(&format!(" (message ids: {})", outcome.message_ids.join(", ")));
+    }
+    if let Some(poll_id) = metadata.get("pollId").and_then(|v| v.as_str()) {
+        content.push_str(&format!("\npollId: {}", poll_id));
+    }
+    if let Some(fallback) = &outcome.fallback {
+        content.push_str(&format!("\nNote: channel lacks native {} support; a fallback was used", fallback));
+    }
+
+    ToolResult { content, is_error: false, metadata }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::{mock::MockChannel, ChannelCapabilities, ChannelManager};
+    use crate::polls::PollService;
+    use std::sync::Arc;
+    use tokio::sync::RwLock;
+
+    fn policy() -> MessagePolicy {
+        MessagePolicy {
+            allow: vec!["whatsapp:+1 555 000 1111".into(), "telegram:*".into()],
+            aliases: HashMap::from([("mom".to_string(), "whatsapp:+15550001111".to_string())]),
+            default_channel: None,
+            audit_path: None,
+        }
+    }
+
+    fn context(channels: Vec<MockChannel>) -> ToolContext {
+        let mut mgr = ChannelManager::new();
+        for channel in channels {
+            mgr.register(Box::new(channel));
+        }
+        let mut ctx = ToolContext::new("/tmp");
+        ctx.channels = Some(Arc::new(RwLock::new(mgr)));
+        ctx.message_policy = policy();
+        ctx
+    }
+
+    #[test]
+    fn resolves_prefixed_numbers_and_aliases() {
+        let p = policy();
+        let target = p.resolve_target("whatsapp:+1 (555) 000-1111", None).unwrap();
+        assert_eq!(target, MessageTarget { channel: "whatsapp".into(), to: "15550001111@s.whatsapp.net".into() });
+        assert_eq!(p.resolve_target("Mom", None).unwrap(), target);
+        assert_eq!(p.resolve_target("123-456@g.us", None).unwrap().to, "123-456@g.us");
+        assert_eq!(p.resolve_target("+15550001111", Some("whatsapp")).unwrap(), target);
+        assert!(p.resolve_target("+15550001111", None).is_err());
+        assert!(p.resolve_target("whatsapp:abc", None).is_err());
+    }
+
+    #[test]
+    fn allowlist_matches_normalized_targets() {
+        let p = policy();
+        assert!(p.is_allowed(&p.resolve_target("whatsapp:15550001111@s.whatsapp.net", None).unwrap()));
+        assert!(p.is_allowed(&p.resolve_target("telegram:42", None).unwrap()));
+        assert!(!p.is_allowed(&p.resolve_target("whatsapp:+19999999999", None).unwrap()));
+    }
+
+    #[test]
+    fn policy_from_config_includes_owners() {
+        let config: OpenClawConfig = serde_json::from_str(r#"{
+            "channels": { "whatsapp": { "allowFrom": ["+15550001111", "*"] } },
+            "tools": { "message": { "allow": ["telegram:7"], "aliases": { "me": "whatsapp:+15550001111" } } }
+        }"#).unwrap();
+        let p = MessagePolicy::from_config(&config);
+        assert_eq!(p.allow, vec!["telegram:7".to_string(), "whatsapp:+15550001111".to_string()]);
+        assert!(p.is_allowed(&p.resolve_target("me", None).unwrap()));
+    }
+
+    #[tokio::test]
+    async fn sends_and_returns_delivery_ids() {
+        let wa = MockChannel::new("whatsapp", ChannelCapabilities::default());
+        let ctx = context(vec![wa.clone()]);
+        let result = execute_message(&json!({
+            "action": "send",
+            "target": "mom",
+            "message": "hello"
+        }), &ctx).await;
+        assert!(!result.is_error, "{}", result.content);
+        assert_eq!(result.metadata["messageIds"], json!(["msg-1"]));
+        assert_eq!(wa.sent.lock().unwrap()[0].to, "15550001111@s.whatsapp.net");
+    }
+
+    #[tokio::test]
+    async fn blocks_targets_outside_allowlist() {
+        let wa = MockChannel::new("whatsapp", ChannelCapabilities::default());
+        let ctx = context(vec![wa.clone()]);
+        let result = execute_message(&json!({
+            "action": "send",
+            "target": "whatsapp:+19999999999",
+            "message": "spam"
+        }), &ctx).await;
+        assert!(result.is_error);
+        assert!(result.content.contains("allowlist"));
+        assert!(wa.sent.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn origin_chat_is_always_allowed() {
+        let wa = MockChannel::new("whatsapp", ChannelCapabilities::default());
+        let mut ctx = context(vec![wa.clone()]);
+        ctx.channel = Some("whatsapp".into());
+        ctx.chat_id = Some("group-1@g.us".into());
+        let result = execute_message(&json!({ "message": "hi group" }), &ctx).await;
+        assert!(!result.is_error, "{}", result.content);
+        assert_eq!(wa.sent.lock().unwrap()[0].to, "group-1@g.us");
+    }
+
+    #[tokio::test]
+    async fn cross_channel_sends_are_audited() {
+        let dir = tempfile::tempdir().unwrap();
+        let audit = dir.path().join("audit.jsonl");
+        let tg = MockChannel::new("telegram", ChannelCapabilities::default());
+        let mut ctx = context(vec![tg]);
+        ctx.message_policy = policy().with_audit_path(audit.clone());
+        ctx.channel = Some("whatsapp".into());
+        ctx.chat_id = Some("15550001111@s.whatsapp.net".into());
+
+        let result = execute_message(&json!({ "target": "telegram:42", "message": "fwd" }), &ctx).await;
+        assert!(!result.is_error, "{}", result.content);
+        let log = std::fs::read_to_string(&audit).unwrap();
+        let entry: serde_json::Value = serde_json::from_str(log.lines().next().unwrap()).unwrap();
+        assert_eq!(entry["fromChannel"], "whatsapp");
+        assert_eq!(entry["channel"], "telegram");
+        assert_eq!(entry["messageIds"], json!(["msg-1"]));
+    }
+
+    #[tokio::test]
+    async fn poll_action_goes_through_poll_service() {
+        let wa = MockChannel::new("whatsapp", ChannelCapabilities { polls: true, ..Default::default() });
+        let polls = PollService::new();
+        let ctx = context(vec![wa.clone()]).with_polls(polls.clone());
+        let result = execute_message(&json!({
+            "action": "poll",
+            "target": "mom",
+            "pollQuestion": "Dinner?",
+            "pollOptions": ["Pasta", "Curry"],
+            "pollMaxSelections": 2.0
+        }), &ctx).await;
+        assert!(!result.is_error, "{}", result.content);
+        let poll_id = result.metadata["pollId"].as_str().unwrap();
+        assert_eq!(polls.get(poll_id).await.unwrap().max_selections, 2);
+        assert_eq!(wa.polls.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn react_requires_message_id() {
+        let wa = MockChannel::new("whatsapp", ChannelCapabilities { reactions: true, ..Default::default() });
+        let ctx = context(vec![wa]);
+        let result = execute_message(&json!({ "action": "react", "target": "mom", "emoji": "👍" }), &ctx).await;
+        assert!(result.is_error);
+        assert!(result.content.contains("messageId"));
+    }
+}
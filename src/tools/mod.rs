@@ -1,8 +1,22 @@
 pub mod executor;
 pub mod builtin;
+pub mod cron;
+pub mod edit;
+pub mod sessions;
+pub mod memory;
+pub mod message;
+pub mod process;
+pub mod sandbox;
+pub mod schema;
+pub mod search;
+pub mod web_fetch;
+pub mod web_search;
 
 use crate::provider::types::ToolDefinition;
+use async_trait::async_trait;
+use executor::ToolContext;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -15,7 +29,66 @@ pub struct ToolResult {
     pub metadata: HashMap<String, serde_json::Value>,
 }
 
-/// Tool registry — stores available tools and their definitions.
+/// A tool the agent can call: what the model sees and how to run it.
+#[async_trait]
+pub trait Tool: Send + Sync {
+    fn definition(&self) -> ToolDefinition;
+
+    /// Alternate input names as `(alias, name)` pairs, renamed at any depth
+    /// before the input is validated.
+    fn aliases(&self) -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+
+    /// Run the tool. `input` has already passed the definition's schema.
+    async fn execute(&self, input: &Value, ctx: &ToolContext) -> ToolResult;
+}
+
+/// Run `tool` with `input`: apply aliases, validate against the input schema
+/// and execute unless the context is cancelled first.
+pub async fn run_tool(tool: &dyn Tool, input: &Value, ctx: &ToolContext) -> ToolResult {
+    let definition = tool.definition();
+    let input = apply_aliases(input, tool.aliases());
+    if let Err(problems) = schema::validate(&definition.input_schema, &input) {
+        return ToolResult {
+            content: format!("Invalid input for tool '{}': {}", definition.name, problems),
+            is_error: true,
+            metadata: HashMap::new(),
+        };
+    }
+    tokio::select! {
+        biased;
+        _ = ctx.cancel.cancelled() => ToolResult {
+            content: format!("Tool call '{}' was cancelled", definition.name),
+            is_error: true,
+            metadata: HashMap::new(),
+        },
+        result = tool.execute(&input, ctx) => result,
+    }
+}
+
+/// `input` with aliased keys renamed, unless the real name is also present.
+fn apply_aliases(input: &Value, aliases: &[(&str, &str)]) -> Value {
+    match input {
+        Value::Object(object) if !aliases.is_empty() => {
+            let mut out = serde_json::Map::new();
+            for (key, value) in object {
+                let value = apply_aliases(value, aliases);
+                match aliases.iter().find(|(alias, _)| alias == key) {
+                    Some((_, name)) if !object.contains_key(*name) => out.insert(name.to_string(), value),
+                    _ => out.insert(key.clone(), value),
+                };
+            }
+            Value::Object(out)
+        }
+        Value::Array(items) if !aliases.is_empty() => {
+            Value::Array(items.iter().map(|item| apply_aliases(item, aliases)).collect())
+        }
+        other => other.clone(),
+    }
+}
+
+/// Tool registry — stores available tools and their handlers.
 #[derive(Clone)]
 pub struct ToolRegistry {
     tools: Arc<RwLock<HashMap<String, RegisteredTool>>>,
@@ -23,10 +96,20 @@ pub struct ToolRegistry {
     allow_list: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct RegisteredTool {
     pub definition: ToolDefinition,
     pub category: ToolCategory,
+    pub handler: Arc<dyn Tool>,
+}
+
+impl std::fmt::Debug for RegisteredTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegisteredTool")
+            .field("definition", &self.definition)
+            .field("category", &self.category)
+            .finish_non_exhaustive()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -39,12 +122,11 @@ pub enum ToolCategory {
 
 impl ToolRegistry {
     pub fn new() -> Self {
-        let registry = Self {
+        Self {
             tools: Arc::new(RwLock::new(HashMap::new())),
             deny_list: Vec::new(),
             allow_list: Vec::new(),
-        };
-        registry
+        }
     }
 
     /// Create with deny/allow lists from config.
@@ -56,13 +138,14 @@ impl ToolRegistry {
         }
     }
 
-    /// Register a tool.
-    pub async fn register(&self, tool: RegisteredTool) {
+    /// Register a tool; it replaces any tool of the same name.
+    pub async fn register(&self, handler: Arc<dyn Tool>, category: ToolCategory) {
+        let definition = handler.definition();
         let mut tools = self.tools.write().await;
-        tools.insert(tool.definition.name.clone(), tool);
+        tools.insert(definition.name.clone(), RegisteredTool { definition, category, handler });
     }
 
-    /// Get a tool definition by name.
+    /// Get a tool by name.
     pub async fn get(&self, name: &str) -> Option<RegisteredTool> {
         let tools = self.tools.read().await;
         tools.get(name).cloned()
@@ -77,6 +160,27 @@ impl ToolRegistry {
             .collect()
     }
 
+    /// Handlers for all tools the policy allows.
+    pub async fn list_tools(&self) -> Vec<Arc<dyn Tool>> {
+        let tools = self.tools.read().await;
+        tools.values()
+            .filter(|t| self.is_allowed(&t.definition.name))
+            .map(|t| t.handler.clone())
+            .collect()
+    }
+
+    /// Run an allowed tool by name.
+    pub async fn execute(&self, name: &str, input: &Value, ctx: &ToolContext) -> ToolResult {
+        match self.get(name).await.filter(|_| self.is_allowed(name)) {
+            Some(tool) => run_tool(tool.handler.as_ref(), input, ctx).await,
+            None => ToolResult {
+                content: format!("Unknown tool: {}", name),
+                is_error: true,
+                metadata: HashMap::new(),
+            },
+        }
+    }
+
     /// Check if a tool name is allowed by policy.
     pub fn is_allowed(&self, name: &str) -> bool {
         if self.deny_list.contains(&name.to_string()) {
@@ -87,36 +191,80 @@ impl ToolRegistry {
 
     /// Register all builtin tools.
     pub async fn register_builtins(&self) {
-        for tool in builtin::all_builtin_tools() {
-            self.register(RegisteredTool {
-                definition: tool,
-                category: ToolCategory::Builtin,
-            }).await;
+        for tool in builtin::builtin_tools() {
+            self.register(tool, ToolCategory::Builtin).await;
         }
     }
 }
 
+impl Default for ToolRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
+
+    struct Echo(&'static str);
+
+    #[async_trait]
+    impl Tool for Echo {
+        fn definition(&self) -> ToolDefinition {
+            ToolDefinition {
+                name: self.0.into(),
+                description: "Echo text back".into(),
+                input_schema: json!({
+                    "type": "object",
+                    "properties": { "text": { "type": "string" }, "delay_ms": { "type": "number" } },
+                    "required": ["text"]
+                }),
+            }
+        }
+
+        fn aliases(&self) -> &'static [(&'static str, &'static str)] {
+            &[("message", "text")]
+        }
+
+        async fn execute(&self, input: &Value, _ctx: &ToolContext) -> ToolResult {
+            let delay = input.get("delay_ms").and_then(|v| v.as_u64()).unwrap_or(0);
+            tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+            ToolResult { content: input["text"].as_str().unwrap_or("").into(), is_error: false, metadata: HashMap::new() }
+        }
+    }
 
     #[tokio::test]
     async fn register_and_get_tool() {
         let registry = ToolRegistry::new();
-        registry.register(RegisteredTool {
-            definition: ToolDefinition {
-                name: "test_tool".into(),
-                description: "A test tool".into(),
-                input_schema: serde_json::json!({"type": "object"}),
-            },
-            category: ToolCategory::Custom,
-        }).await;
+        registry.register(Arc::new(Echo("test_tool")), ToolCategory::Custom).await;
 
         let tool = registry.get("test_tool").await;
         assert!(tool.is_some());
         assert_eq!(tool.unwrap().definition.name, "test_tool");
     }
 
+    #[tokio::test]
+    async fn registered_tools_are_executable() {
+        let registry = ToolRegistry::with_policy(vec!["blocked".into()], vec![]);
+        registry.register(Arc::new(Echo("echo")), ToolCategory::Custom).await;
+        registry.register(Arc::new(Echo("blocked")), ToolCategory::Custom).await;
+        let ctx = ToolContext::new("/tmp");
+
+        let result = registry.execute("echo", &json!({ "message": "hi" }), &ctx).await;
+        assert!(!result.is_error && result.content == "hi", "{}", result.content);
+        let invalid = registry.execute("echo", &json!({ "text": 5 }), &ctx).await;
+        assert!(invalid.is_error);
+        assert_eq!(invalid.content, "Invalid input for tool 'echo': 'text' must be string, got integer 5");
+        assert!(registry.execute("blocked", &json!({ "text": "x" }), &ctx).await.content.contains("Unknown tool"));
+        assert_eq!(registry.list_tools().await.len(), 1);
+
+        ctx.cancel.cancel();
+        let cancelled = registry.execute("echo", &json!({ "text": "x", "delay_ms": 5000 }), &ctx).await;
+        assert_eq!(cancelled.content, "Tool call 'echo' was cancelled");
+    }
+
     #[tokio::test]
     async fn deny_list() {
         let registry = ToolRegistry::with_policy(
@@ -139,22 +287,8 @@ mod tests {
     #[tokio::test]
     async fn list_definitions() {
         let registry = ToolRegistry::with_policy(vec!["blocked".into()], vec![]);
-        registry.register(RegisteredTool {
-            definition: ToolDefinition {
-                name: "allowed".into(),
-                description: "OK".into(),
-                input_schema: serde_json::json!({}),
-            },
-            category: ToolCategory::Builtin,
-        }).await;
-        registry.register(RegisteredTool {
-            definition: ToolDefinition {
-                name: "blocked".into(),
-                description: "NO".into(),
-                input_schema: serde_json::json!({}),
-            },
-            category: ToolCategory::Builtin,
-        }).await;
+        registry.register(Arc::new(Echo("allowed")), ToolCategory::Builtin).await;
+        registry.register(Arc::new(Echo("blocked")), ToolCategory::Builtin).await;
         let defs = registry.list_definitions().await;
         assert_eq!(defs.len(), 1);
         assert_eq!(defs[0].name, "allowed");
@@ -0,0 +1,195 @@
+use super::executor::ToolContext;
+use super::{Tool, ToolResult};
+use crate::process::{ProcessError, ProcessInfo, ProcessStatus};
+use crate::provider::types::ToolDefinition;
+use async_trait::async_trait;
+use serde_json::json;
+use std::collections::HashMap;
+
+fn error(content: impl Into<String>) -> ToolResult {
+    ToolResult { content: content.into(), is_error: true, metadata: HashMap::new() }
+}
+
+fn state(process: &ProcessInfo) -> String {
+    match (process.status, process.exit_code) {
+        (ProcessStatus::Running, _) => "running".to_string(),
+        (ProcessStatus::Exited, Some(code)) => format!("exited {}", code),
+        (ProcessStatus::Exited, None) => "exited".to_string(),
+        (ProcessStatus::Killed, _) => "killed".to_string(),
+    }
+}
+
+fn describe(process: &ProcessInfo) -> String {
+    format!("- {} [{}] {}", process.id, state(process), process.command)
+}
+
+/// Start an `exec` command in the background and return its id at once.
+pub async fn start_background(command: &str, workdir: &str, ctx: &ToolContext) -> ToolResult {
+    let Some(processes) = &ctx.processes else {
+        return error("Background processes are not available in this context");
+    };
+    let started = processes.start(command, workdir, &ctx.workspace_dir, &ctx.sandbox, ctx.session_key.as_deref()).await;
+    match started {
+        Ok(process) => {
+            let mut metadata = HashMap::new();
+            metadata.insert("sessionId".into(), json!(process.id));
+            metadata.insert("pid".into(), json!(process.pid));
+            metadata.insert("sandbox".into(), ctx.sandbox.metadata());
+            ToolResult {
+                content: format!(
+                    "Started background process {}. Use the process tool to poll its output, write to it or stop it.",
+                    process.id,
+                ),
+                is_error: false,
+                metadata,
+            }
+        }
+        Err(e) => error(e.to_string()),
+    }
+}
+
+/// The `process` tool.
+pub struct ProcessTool;
+
+#[async_trait]
+impl Tool for ProcessTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "process".into(),
+            description: "Manage commands started with exec in the background: list them, poll new output, write to stdin, send signals, kill or remove finished ones.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": { "type": "string", "enum": ["list", "poll", "write", "signal", "kill", "remove"] },
+                    "sessionId": { "type": "string", "description": "Process id returned by exec" },
+                    "offset": { "type": "number", "description": "Poll output from this byte offset instead of where the last poll stopped" },
+                    "data": { "type": "string", "description": "Text to write to stdin" },
+                    "eof": { "type": "boolean", "description": "Close stdin after writing" },
+                    "signal": { "type": "string", "description": "Signal name or number, e.g. TERM, INT, HUP" }
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+        execute_process(input, ctx).await
+    }
+}
+
+/// Execute the process tool: manage commands started with `exec` in the
+/// background. A session only sees the processes its own turns started.
+pub async fn execute_process(input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+    let Some(processes) = &ctx.processes else {
+        return error("Background processes are not available in this context");
+    };
+    let action = input.get("action").and_then(|v| v.as_str()).unwrap_or("list");
+    if action == "list" {
+        let list: Vec<ProcessInfo> = processes.list().await.into_iter()
+            .filter(|p| p.session_key == ctx.session_key)
+            .collect();
+        if list.is_empty() {
+            return ToolResult { content: "No background processes.".into(), is_error: false, metadata: HashMap::new() };
+        }
+        let mut metadata = HashMap::new();
+        metadata.insert("processes".into(), json!(list));
+        let lines: Vec<String> = list.iter().map(describe).collect();
+        return ToolResult { content: lines.join("\n"), is_error: false, metadata };
+    }
+
+    let Some(id) = input.get("sessionId").and_then(|v| v.as_str()).map(str::trim).filter(|s| !s.is_empty()) else {
+        return error(format!("sessionId is required for {}", action));
+    };
+    if processes.get(id).await.is_none_or(|p| p.session_key != ctx.session_key) {
+        return error(ProcessError::NotFound(id.to_string()).to_string());
+    }
+    let done = |content: String| ToolResult { content, is_error: false, metadata: HashMap::new() };
+    let result = match action {
+        "poll" => {
+            let offset = input.get("offset").and_then(|v| v.as_u64());
+            processes.poll(id, offset).await.map(|out| {
+                let mut content = String::new();
+                if out.dropped > 0 {
+                    content.push_str(&format!("[{} earlier bytes dropped]\n", out.dropped));
+                }
+                content.push_str(&out.output);
+                if out.process.status != ProcessStatus::Running {
+                    content.push_str(&format!("\n[{}]", state(&out.process)));
+                }
+                let mut metadata = HashMap::new();
+                metadata.insert("offset".into(), json!(out.offset));
+                metadata.insert("process".into(), json!(out.process));
+                ToolResult { content, is_error: false, metadata }
+            })
+        }
+        "write" => {
+            let data = input.get("data").and_then(|v| v.as_str()).unwrap_or("");
+            let eof = input.get("eof").and_then(|v| v.as_bool()).unwrap_or(false);
+            processes.write(id, data, eof).await
+                .map(|_| done(format!("Wrote {} bytes to {}{}", data.len(), id, if eof { " and closed stdin" } else { "" })))
+        }
+        "signal" => {
+            let signal = input.get("signal").and_then(|v| v.as_str()).unwrap_or("TERM");
+            processes.signal(id, signal).await.map(|_| done(format!("Sent {} to {}", signal, id)))
+        }
+        "kill" => processes.kill(id).await.map(|_| done(format!("Killed {}", id))),
+        "remove" => processes.remove(id).await.map(|p| done(format!("Removed {}", p.id))),
+        other => return error(format!("Unknown action: {}", other)),
+    };
+    result.unwrap_or_else(|e| error(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::ProcessManager;
+    use crate::tools::executor::execute_tool;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn exec_in_background_then_poll() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = ToolContext::new(dir.path().to_str().unwrap()).with_processes(ProcessManager::default());
+        let started = execute_tool("exec", &json!({ "command": "read name; echo hi $name", "background": true }), &ctx).await;
+        assert!(!started.is_error, "{}", started.content);
+        let id = started.metadata["sessionId"].as_str().unwrap().to_string();
+
+        let listed = execute_process(&json!({ "action": "list" }), &ctx).await;
+        assert!(listed.content.contains(&format!("{} [running]", id)));
+        let wrote = execute_process(&json!({ "action": "write", "sessionId": id, "data": "ana\n", "eof": true }), &ctx).await;
+        assert!(!wrote.is_error, "{}", wrote.content);
+
+        let mut polled = String::new();
+        for _ in 0..100 {
+            let result = execute_process(&json!({ "action": "poll", "sessionId": id }), &ctx).await;
+            polled.push_str(&result.content);
+            if polled.contains("exited") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(polled.starts_with("hi ana\n") && polled.ends_with("[exited 0]"), "{}", polled);
+
+        assert!(execute_process(&json!({ "action": "kill" }), &ctx).await.content.contains("sessionId is required"));
+        assert!(execute_process(&json!({ "action": "kill", "sessionId": id }), &ctx).await.content.contains("not running"));
+        assert!(execute_process(&json!({ "action": "list" }), &ToolContext::new("/tmp")).await.is_error);
+    }
+
+    #[tokio::test]
+    async fn sessions_only_see_their_own_processes() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = ToolContext::new(dir.path().to_str().unwrap()).with_processes(ProcessManager::default());
+        let mine = ctx.clone().with_session("agent:main:telegram:1", "main");
+        let theirs = ctx.with_session("agent:main:telegram:2", "main");
+        let started = execute_tool("exec", &json!({ "command": "sleep 5", "background": true }), &mine).await;
+        let id = started.metadata["sessionId"].as_str().unwrap().to_string();
+
+        assert!(execute_process(&json!({ "action": "list" }), &mine).await.content.contains(&id));
+        assert_eq!(execute_process(&json!({ "action": "list" }), &theirs).await.content, "No background processes.");
+        for action in ["poll", "write", "signal", "kill", "remove"] {
+            let result = execute_process(&json!({ "action": action, "sessionId": id }), &theirs).await;
+            assert!(result.is_error && result.content.contains("not found"), "{}: {}", action, result.content);
+        }
+        assert!(!execute_process(&json!({ "action": "kill", "sessionId": id }), &mine).await.is_error);
+    }
+}
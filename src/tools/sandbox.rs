@@ -0,0 +1,405 @@
+//! Running `exec` commands: each in its own process group with capped output,
+//! optionally sandboxed (bubblewrap when installed, resource limits otherwise).
+
+use crate::config::{ExecSandboxConfig, OpenClawConfig};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::{Child, Command};
+use tracing::warn;
+
+/// Output kept from a command when `tools.exec.maxOutputBytes` is unset.
+pub const DEFAULT_MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+/// Limits applied by a sandbox unless configured otherwise.
+pub const DEFAULT_CPU_SECONDS: u64 = 120;
+pub const DEFAULT_MEMORY_MB: u64 = 2048;
+/// Environment a sandboxed command keeps when `passEnv` is unset.
+pub const DEFAULT_PASS_ENV: &[&str] = &["PATH", "HOME", "USER", "LANG", "LC_ALL", "TERM", "TZ"];
+
+/// `tools.exec.sandbox.mode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SandboxMode {
+    #[default]
+    Off,
+    Auto,
+    Bubblewrap,
+    Rlimit,
+}
+
+impl SandboxMode {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" | "none" => Some(Self::Off),
+            "auto" => Some(Self::Auto),
+            "bubblewrap" | "bwrap" => Some(Self::Bubblewrap),
+            "rlimit" | "rlimits" => Some(Self::Rlimit),
+            _ => None,
+        }
+    }
+}
+
+/// How commands are actually isolated on this host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SandboxBackend {
+    None,
+    Bubblewrap,
+    Rlimit,
+}
+
+/// The isolation and limits `exec` runs commands under.
+#[derive(Debug, Clone)]
+pub struct SandboxPolicy {
+    pub backend: SandboxBackend,
+    pub cpu_seconds: Option<u64>,
+    pub memory_mb: Option<u64>,
+    pub max_output_bytes: usize,
+    /// Network access; only enforceable with bubblewrap.
+    pub network: bool,
+    pub pass_env: Vec<String>,
+    bwrap: Option<PathBuf>,
+}
+
+impl Default for SandboxPolicy {
+    fn default() -> Self {
+        Self {
+            backend: SandboxBackend::None,
+            cpu_seconds: None,
+            memory_mb: None,
+            max_output_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+            network: true,
+            pass_env: Vec::new(),
+            bwrap: None,
+        }
+    }
+}
+
+/// What a finished (or stopped) command produced.
+#[derive(Debug)]
+pub struct ExecOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// `None` when the command was killed.
+    pub status: Option<ExitStatus>,
+    pub timed_out: bool,
+    /// Output went past `max_output_bytes` and the command was stopped.
+    pub truncated: bool,
+}
+
+impl SandboxPolicy {
+    /// Build the policy from `tools.exec`, probing for bubblewrap when asked.
+    pub fn from_config(config: &OpenClawConfig) -> Self {
+        let exec = config.tools.as_ref().and_then(|t| t.exec.clone()).unwrap_or_default();
+        let max_output_bytes = exec.max_output_bytes.filter(|n| *n > 0).unwrap_or(DEFAULT_MAX_OUTPUT_BYTES);
+        let sandbox = exec.sandbox.unwrap_or_default();
+        let mode = match sandbox.mode.as_deref() {
+            None => SandboxMode::Off,
+            Some(s) => SandboxMode::parse(s).unwrap_or_else(|| {
+                // Fail closed: a typo shouldn't turn the sandbox off
+                warn!("Unknown exec sandbox mode '{}', using auto", s);
+                SandboxMode::Auto
+            }),
+        };
+        Self::for_mode(mode, &sandbox, find_in_path("bwrap")).with_max_output_bytes(max_output_bytes)
+    }
+
+    fn for_mode(mode: SandboxMode, config: &ExecSandboxConfig, bwrap: Option<PathBuf>) -> Self {
+        let backend = match (mode, &bwrap) {
+            (SandboxMode::Off, _) => return Self::default(),
+            (SandboxMode::Rlimit, _) => SandboxBackend::Rlimit,
+            (SandboxMode::Auto | SandboxMode::Bubblewrap, Some(_)) => SandboxBackend::Bubblewrap,
+            (SandboxMode::Auto, None) => SandboxBackend::Rlimit,
+            (SandboxMode::Bubblewrap, None) => {
+                warn!("bubblewrap (bwrap) not found; exec falls back to resource limits only");
+                SandboxBackend::Rlimit
+            }
+        };
+        Self {
+            backend,
+            cpu_seconds: Some(config.cpu_seconds.unwrap_or(DEFAULT_CPU_SECONDS)).filter(|n| *n > 0),
+            memory_mb: Some(config.memory_mb.unwrap_or(DEFAULT_MEMORY_MB)).filter(|n| *n > 0),
+            max_output_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+            network: config.network.unwrap_or(false),
+            pass_env: config.pass_env.clone()
+                .unwrap_or_else(|| DEFAULT_PASS_ENV.iter().map(|v| v.to_string()).collect()),
+            bwrap: if backend == SandboxBackend::Bubblewrap { bwrap } else { None },
+        }
+    }
+
+    pub fn with_max_output_bytes(mut self, max: usize) -> Self {
+        self.max_output_bytes = max;
+        self
+    }
+
+    /// The policy as reported in `exec` results: what is actually enforced.
+    pub fn metadata(&self) -> serde_json::Value {
+        let sandboxed = self.backend != SandboxBackend::None;
+        serde_json::json!({
+            "backend": self.backend,
+            "cpuSeconds": self.cpu_seconds,
+            "memoryMb": self.memory_mb,
+            "maxOutputBytes": self.max_output_bytes,
+            "network": self.network || self.backend != SandboxBackend::Bubblewrap,
+            "readOnlyRoot": self.backend == SandboxBackend::Bubblewrap,
+            "envScrubbed": sandboxed,
+            "processGroupKill": true,
+        })
+    }
+
+    /// Arguments to bubblewrap: the host read-only, `workspace` writable,
+    /// private /tmp and pid namespace, no network unless allowed.
+    pub fn bwrap_args(&self, command: &str, workdir: &str, workspace: &str) -> Vec<String> {
+        let mut args: Vec<String> = [
+            "--ro-bind", "/", "/",
+            "--dev", "/dev",
+            "--proc", "/proc",
+            "--tmpfs", "/tmp",
+            "--bind", workspace, workspace,
+            "--unshare-pid",
+            "--die-with-parent",
+        ].iter().map(|s| s.to_string()).collect();
+        if !self.network {
+            args.push("--unshare-net".into());
+        }
+        args.extend(["--chdir", workdir, "--", "bash", "-c", command].iter().map(|s| s.to_string()));
+        args
+    }
+
+    fn command(&self, command: &str, workdir: &str, workspace: &str) -> Command {
+        let mut cmd = match (&self.backend, &self.bwrap) {
+            (SandboxBackend::Bubblewrap, Some(bwrap)) => {
+                let mut cmd = Command::new(bwrap);
+                cmd.args(self.bwrap_args(command, workdir, workspace));
+                cmd
+            }
+            _ => {
+                let mut cmd = Command::new("bash");
+                cmd.arg("-c").arg(command).current_dir(workdir);
+                cmd
+            }
+        };
+        if self.backend != SandboxBackend::None {
+            cmd.env_clear();
+            for name in &self.pass_env {
+                if let Ok(value) = std::env::var(name) {
+                    cmd.env(name, value);
+                }
+            }
+            let cpu = self.cpu_seconds;
+            let memory = self.memory_mb.map(|mb| mb * 1024 * 1024);
+            // SAFETY: only calls setrlimit, which is async-signal-safe.
+            unsafe {
+                cmd.pre_exec(move || {
+                    if let Some(secs) = cpu {
+                        check(libc::setrlimit(libc::RLIMIT_CPU, &rlimit(secs)))?;
+                    }
+                    if let Some(bytes) = memory {
+                        check(libc::setrlimit(libc::RLIMIT_AS, &rlimit(bytes)))?;
+                    }
+                    Ok(())
+                });
+            }
+        }
+        cmd.process_group(0)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        cmd
+    }
+
+    /// Start `command` with stdin open and output piped, for callers that
+    /// manage the process themselves. It leads its own process group.
+    pub fn spawn(&self, command: &str, workdir: &str, workspace: &str) -> std::io::Result<Child> {
+        self.command(command, workdir, workspace).stdin(Stdio::piped()).spawn()
+    }
+
+    /// Run `command` in `workdir`. On timeout or too much output the whole
+    /// process group is killed, so background children don't outlive it.
+    pub async fn run(&self, command: &str, workdir: &str, workspace: &str, timeout: Duration) -> std::io::Result<ExecOutput> {
+        let mut child = self.command(command, workdir, workspace).spawn()?;
+        let pgid = child.id();
+        let (Some(mut stdout), Some(mut stderr)) = (child.stdout.take(), child.stderr.take()) else {
+            kill_group(pgid);
+            return Err(std::io::Error::other("command output was not captured"));
+        };
+
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let limit = self.max_output_bytes;
+        let finished = tokio::time::timeout(timeout, async {
+            let truncated = read_capped(&mut stdout, &mut stderr, &mut out, &mut err, limit).await;
+            if truncated {
+                kill_group(pgid);
+            }
+            (truncated, child.wait().await)
+        }).await;
+
+        let (status, timed_out, truncated) = match finished {
+            Ok((truncated, status)) => {
+                // Background jobs may still hold on to the group
+                kill_group(pgid);
+                (Some(status?).filter(|_| !truncated), false, truncated)
+            }
+            Err(_) => {
+                kill_group(pgid);
+                let _ = child.wait().await;
+                (None, true, false)
+            }
+        };
+        Ok(ExecOutput {
+            stdout: String::from_utf8_lossy(&out).into_owned(),
+            stderr: String::from_utf8_lossy(&err).into_owned(),
+            status,
+            timed_out,
+            truncated,
+        })
+    }
+}
+
+/// Read both streams until they close or `limit` bytes have been read
+/// between them; returns whether the limit was hit.
+async fn read_capped(
+    stdout: &mut (impl AsyncRead + Unpin),
+    stderr: &mut (impl AsyncRead + Unpin),
+    out: &mut Vec<u8>,
+    err: &mut Vec<u8>,
+    limit: usize,
+) -> bool {
+    let (mut out_open, mut err_open) = (true, true);
+    let (mut out_buf, mut err_buf) = ([0u8; 8192], [0u8; 8192]);
+    while out_open || err_open {
+        tokio::select! {
+            n = stdout.read(&mut out_buf), if out_open => match n {
+                Ok(0) | Err(_) => out_open = false,
+                Ok(n) => out.extend_from_slice(&out_buf[..n]),
+            },
+            n = stderr.read(&mut err_buf), if err_open => match n {
+                Ok(0) | Err(_) => err_open = false,
+                Ok(n) => err.extend_from_slice(&err_buf[..n]),
+            },
+        }
+        let total = out.len() + err.len();
+        if total > limit {
+            // Trim whichever stream pushed past the limit
+            let over = total - limit;
+            let longer = if out.len() >= over { out } else { err };
+            longer.truncate(longer.len() - over);
+            return true;
+        }
+    }
+    false
+}
+
+fn rlimit(value: u64) -> libc::rlimit {
+    libc::rlimit { rlim_cur: value as libc::rlim_t, rlim_max: value as libc::rlim_t }
+}
+
+fn check(ret: libc::c_int) -> std::io::Result<()> {
+    if ret != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Send `signal` to every process in group `pgid`.
+pub fn signal_group(pgid: u32, signal: libc::c_int) -> std::io::Result<()> {
+    let pgid = libc::pid_t::try_from(pgid).map_err(std::io::Error::other)?;
+    // SAFETY: plain syscall; callers only pass groups they created.
+    check(unsafe { libc::killpg(pgid, signal) })
+}
+
+fn kill_group(pgid: Option<u32>) {
+    if let Some(pgid) = pgid {
+        let _ = signal_group(pgid, libc::SIGKILL);
+    }
+}
+
+/// First executable `name` on `PATH`.
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(name))
+        .find(|path| is_executable(path))
+}
+
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata().is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rlimited() -> SandboxPolicy {
+        SandboxPolicy::for_mode(SandboxMode::Rlimit, &ExecSandboxConfig {
+            cpu_seconds: Some(5),
+            memory_mb: Some(512),
+            pass_env: Some(vec!["PATH".into()]),
+            ..Default::default()
+        }, None)
+    }
+
+    #[test]
+    fn picks_a_backend() {
+        let bwrap = Some(PathBuf::from("/usr/bin/bwrap"));
+        let config = ExecSandboxConfig::default();
+        assert_eq!(SandboxPolicy::for_mode(SandboxMode::Auto, &config, bwrap.clone()).backend, SandboxBackend::Bubblewrap);
+        assert_eq!(SandboxPolicy::for_mode(SandboxMode::Auto, &config, None).backend, SandboxBackend::Rlimit);
+        assert_eq!(SandboxPolicy::for_mode(SandboxMode::Bubblewrap, &config, None).backend, SandboxBackend::Rlimit);
+        assert_eq!(SandboxPolicy::for_mode(SandboxMode::Off, &config, bwrap).backend, SandboxBackend::None);
+        assert_eq!(SandboxMode::parse("BWRAP"), Some(SandboxMode::Bubblewrap));
+        assert_eq!(SandboxMode::parse("jail"), None);
+
+        let policy = rlimited();
+        let meta = policy.metadata();
+        assert_eq!((meta["backend"].as_str(), meta["cpuSeconds"].as_u64()), (Some("rlimit"), Some(5)));
+        // Without bubblewrap the network can't be taken away
+        assert_eq!(meta["network"], true);
+        assert_eq!(meta["readOnlyRoot"], false);
+    }
+
+    #[test]
+    fn bubblewrap_isolates_all_but_the_workspace() {
+        let policy = SandboxPolicy::for_mode(SandboxMode::Bubblewrap, &ExecSandboxConfig::default(), Some("/usr/bin/bwrap".into()));
+        let args = policy.bwrap_args("ls", "/ws/src", "/ws").join(" ");
+        assert!(args.starts_with("--ro-bind / /"));
+        assert!(args.contains("--bind /ws /ws") && args.contains("--unshare-net"));
+        assert!(args.ends_with("--chdir /ws/src -- bash -c ls"));
+        assert_eq!(policy.metadata()["network"], false);
+    }
+
+    #[tokio::test]
+    async fn applies_limits_and_scrubs_the_environment() {
+        let dir = tempfile::tempdir().unwrap();
+        let ws = dir.path().to_str().unwrap();
+        let out = rlimited().run("ulimit -v; ulimit -t; echo ${CARGO_MANIFEST_DIR:-scrubbed}", ws, ws, Duration::from_secs(10)).await.unwrap();
+        assert_eq!(out.stdout, "524288\n5\nscrubbed\n");
+        assert!(out.status.unwrap().success());
+    }
+
+    #[tokio::test]
+    async fn caps_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let ws = dir.path().to_str().unwrap();
+        let policy = SandboxPolicy::default().with_max_output_bytes(1000);
+        let out = policy.run("yes", ws, ws, Duration::from_secs(10)).await.unwrap();
+        assert!(out.truncated && !out.timed_out);
+        assert_eq!(out.stdout.len(), 1000);
+    }
+
+    #[tokio::test]
+    async fn timeouts_kill_the_process_group() {
+        let dir = tempfile::tempdir().unwrap();
+        let ws = dir.path().to_str().unwrap();
+        let out = SandboxPolicy::default()
+            .run("sleep 30 & echo $!; wait", ws, ws, Duration::from_millis(500)).await.unwrap();
+        assert!(out.timed_out && out.status.is_none());
+
+        let pid = out.stdout.trim();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let state = std::fs::read_to_string(format!("/proc/{}/status", pid)).unwrap_or_default();
+        assert!(state.is_empty() || state.contains("State:\tZ"), "background child survived: {}", state);
+    }
+}
@@ -0,0 +1,150 @@
+//! Checking tool input against a tool's `input_schema` before it runs, with
+//! errors the model can act on.
+//!
+//! Covers the JSON Schema keywords tool definitions use: `type`,
+//! `properties`, `required`, `items`, `enum`, `minimum`/`maximum` and
+//! `additionalProperties: false`.
+
+use serde_json::Value;
+
+/// Check `input` against `schema`. The error names every problem found.
+pub fn validate(schema: &Value, input: &Value) -> Result<(), String> {
+    let mut problems = Vec::new();
+    check(schema, input, "", &mut problems);
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems.join("; "))
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "number" => value.is_number(),
+        "integer" => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        other => type_name(value) == other,
+    }
+}
+
+/// `value` shortened for an error message.
+fn preview(value: &Value) -> String {
+    let text = value.to_string();
+    match text.char_indices().nth(40) {
+        Some((at, _)) => format!("{}…", &text[..at]),
+        None => text,
+    }
+}
+
+fn check(schema: &Value, value: &Value, path: &str, problems: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else { return };
+    let name = if path.is_empty() { "input".to_string() } else { format!("'{}'", path) };
+
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(|t| t.as_str()).collect(),
+        _ => Vec::new(),
+    };
+    if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+        problems.push(format!("{} must be {}, got {} {}", name, types.join(" or "), type_name(value), preview(value)));
+        return;
+    }
+
+    if let Some(options) = schema.get("enum").and_then(|e| e.as_array()) {
+        if !options.contains(value) {
+            let allowed: Vec<String> = options.iter().map(|o| o.to_string()).collect();
+            problems.push(format!("{} must be one of {}, got {}", name, allowed.join(", "), preview(value)));
+        }
+    }
+    if let Some(n) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(|m| m.as_f64()).filter(|min| n < *min) {
+            problems.push(format!("{} must be at least {}, got {}", name, min, n));
+        }
+        if let Some(max) = schema.get("maximum").and_then(|m| m.as_f64()).filter(|max| n > *max) {
+            problems.push(format!("{} must be at most {}, got {}", name, max, n));
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        let properties = schema.get("properties").and_then(|p| p.as_object());
+        for key in schema.get("required").and_then(|r| r.as_array()).into_iter().flatten().filter_map(|k| k.as_str()) {
+            if object.get(key).is_none_or(Value::is_null) {
+                problems.push(format!("missing required property '{}'", join(path, key)));
+            }
+        }
+        for (key, item) in object {
+            match properties.and_then(|p| p.get(key)) {
+                Some(property) if !item.is_null() => check(property, item, &join(path, key), problems),
+                Some(_) => {}
+                None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                    let known: Vec<&str> = properties.into_iter().flat_map(|p| p.keys()).map(String::as_str).collect();
+                    problems.push(format!("unknown property '{}' (expected one of: {})", join(path, key), known.join(", ")));
+                }
+                None => {}
+            }
+        }
+    }
+
+    if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+        for (i, item) in array.iter().enumerate() {
+            check(items, item, &format!("{}[{}]", path, i), problems);
+        }
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reports_problems_by_path() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "action": { "type": "string", "enum": ["list", "add"] },
+                "limit": { "type": "integer", "minimum": 1 },
+                "edits": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "old_string": { "type": "string" } },
+                        "required": ["old_string"],
+                        "additionalProperties": false
+                    }
+                }
+            },
+            "required": ["action"]
+        });
+        assert!(validate(&schema, &json!({ "action": "list", "limit": 3.0, "extra": true, "edits": null })).is_ok());
+        assert_eq!(validate(&schema, &json!({})).unwrap_err(), "missing required property 'action'");
+        assert_eq!(
+            validate(&schema, &json!({ "action": "drop", "limit": "10" })).unwrap_err(),
+            "'action' must be one of \"list\", \"add\", got \"drop\"; 'limit' must be integer, got string \"10\"",
+        );
+        assert_eq!(
+            validate(&schema, &json!({ "action": "add", "limit": 0, "edits": [{ "old_string": "a" }, { "new": 1 }] })).unwrap_err(),
+            "missing required property 'edits[1].old_string'; unknown property 'edits[1].new' (expected one of: old_string); 'limit' must be at least 1, got 0",
+        );
+        assert_eq!(validate(&schema, &json!("list")).unwrap_err(), "input must be object, got string \"list\"");
+    }
+}
@@ -0,0 +1,448 @@
+//! glob and grep: finding files and text in the workspace without a shell.
+//! Both skip `.git`, honor `.gitignore` files and the file access policy.
+
+use super::executor::{allowed_path, ToolContext};
+use super::{Tool, ToolResult};
+use crate::provider::types::ToolDefinition;
+use crate::utils::resolve_user_path;
+use async_trait::async_trait;
+use glob::{MatchOptions, Pattern};
+use regex::RegexBuilder;
+use serde_json::json;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Paths returned by glob when no `limit` is given.
+pub const DEFAULT_GLOB_LIMIT: usize = 200;
+/// Matching lines (or files) returned by grep when no `limit` is given.
+pub const DEFAULT_GREP_LIMIT: usize = 200;
+/// Most context lines grep shows around a match.
+const MAX_CONTEXT: usize = 10;
+/// Files larger than this are not searched.
+const MAX_GREP_FILE_BYTES: u64 = 5 * 1024 * 1024;
+/// Bytes checked for a NUL when deciding whether a file is binary.
+const BINARY_SNIFF_BYTES: usize = 8192;
+
+const PATH_MATCH: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+fn error(content: impl Into<String>) -> ToolResult {
+    ToolResult { content: content.into(), is_error: true, metadata: HashMap::new() }
+}
+
+/// One line of a `.gitignore`.
+#[derive(Debug)]
+struct IgnoreRule {
+    pattern: Pattern,
+    negated: bool,
+    dir_only: bool,
+    /// Contains a `/`: matched against the path from the .gitignore's
+    /// directory rather than the file name.
+    anchored: bool,
+}
+
+impl IgnoreRule {
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let pattern = Pattern::new(line.trim_start_matches('/')).ok()?;
+        Some(Self { pattern, negated, dir_only, anchored })
+    }
+
+    fn matches(&self, relative: &Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        if self.anchored {
+            self.pattern.matches_path_with(relative, PATH_MATCH)
+        } else {
+            relative.file_name().is_some_and(|name| self.pattern.matches_with(&name.to_string_lossy(), PATH_MATCH))
+        }
+    }
+}
+
+/// `.gitignore` rules collected while walking down from the search root.
+#[derive(Default)]
+struct GitIgnores {
+    by_dir: HashMap<PathBuf, Vec<IgnoreRule>>,
+}
+
+impl GitIgnores {
+    fn load(&mut self, dir: &Path) {
+        let rules = std::fs::read_to_string(dir.join(".gitignore"))
+            .map(|text| text.lines().filter_map(IgnoreRule::parse).collect())
+            .unwrap_or_default();
+        self.by_dir.insert(dir.to_path_buf(), rules);
+    }
+
+    /// Deeper .gitignore files and later lines win, as in git.
+    fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let mut ignored = false;
+        let mut dirs: Vec<&Path> = path.ancestors().skip(1).collect();
+        dirs.reverse();
+        for dir in dirs {
+            let (Some(rules), Ok(relative)) = (self.by_dir.get(dir), path.strip_prefix(dir)) else { continue };
+            for rule in rules.iter().filter(|r| r.matches(relative, is_dir)) {
+                ignored = !rule.negated;
+            }
+        }
+        ignored
+    }
+}
+
+/// Files under `root` that aren't ignored and pass the file access policy,
+/// in path order.
+fn walk_files(root: &Path, ctx: &ToolContext) -> impl Iterator<Item = PathBuf> {
+    let mut ignores = GitIgnores::default();
+    // Rules from the workspace down to a nested search root still apply
+    let top = workspace_root(ctx);
+    for dir in root.ancestors().skip(1).filter(|d| d.starts_with(&top)) {
+        ignores.load(dir);
+    }
+    let workspace = ctx.workspace_dir.clone();
+    let files = ctx.files.clone();
+    WalkDir::new(root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(move |entry| {
+            let is_dir = entry.file_type().is_dir();
+            if entry.depth() > 0 && (entry.file_name() == ".git" || ignores.is_ignored(entry.path(), is_dir)) {
+                return false;
+            }
+            if is_dir {
+                ignores.load(entry.path());
+            }
+            true
+        })
+        .filter_map(Result::ok)
+        .filter(|entry| !entry.file_type().is_dir())
+        .filter_map(move |entry| {
+            // Also follows symlinks, which must land somewhere allowed
+            let path = files.check(entry.path(), &workspace).ok()?;
+            path.is_file().then(|| entry.into_path())
+        })
+}
+
+/// Search root from the `path` input (default: the workspace).
+fn search_root(input: &serde_json::Value, ctx: &ToolContext) -> Result<PathBuf, ToolResult> {
+    let path = input.get("path").and_then(|v| v.as_str()).map(str::trim).filter(|p| !p.is_empty()).unwrap_or(".");
+    let root = allowed_path(path, ctx)?;
+    if !root.exists() {
+        return Err(error(format!("Path not found: {}", path)));
+    }
+    Ok(root)
+}
+
+/// How a result path is shown: relative to the workspace when inside it.
+fn shown(path: &Path, workspace: &Path) -> String {
+    path.strip_prefix(workspace).unwrap_or(path).to_string_lossy().into_owned()
+}
+
+fn workspace_root(ctx: &ToolContext) -> PathBuf {
+    let workspace = resolve_user_path(&ctx.workspace_dir);
+    workspace.canonicalize().unwrap_or(workspace)
+}
+
+fn limit(input: &serde_json::Value, default: usize) -> usize {
+    input.get("limit").and_then(|v| v.as_u64()).filter(|n| *n > 0).map(|n| n as usize).unwrap_or(default)
+}
+
+/// The `glob` tool.
+pub struct GlobTool;
+
+#[async_trait]
+impl Tool for GlobTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "glob".into(),
+            description: "Find files by path pattern, e.g. \"**/*.rs\" or \"src/*.md\". Skips .git and files ignored by .gitignore.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "pattern": { "type": "string", "description": "Glob matched against paths below path; ** spans directories" },
+                    "path": { "type": "string", "description": "Directory to search (default: the workspace)" },
+                    "limit": { "type": "number", "description": "Maximum number of paths to return" }
+                },
+                "required": ["pattern"]
+            }),
+        }
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+        execute_glob(input, ctx).await
+    }
+}
+
+/// Execute the glob tool: files whose path below `path` matches `pattern`.
+pub async fn execute_glob(input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+    let Some(pattern) = input.get("pattern").and_then(|v| v.as_str()).filter(|p| !p.trim().is_empty()) else {
+        return error("pattern is required");
+    };
+    let pattern = match Pattern::new(pattern.trim()) {
+        Ok(p) => p,
+        Err(e) => return error(format!("Invalid glob pattern: {}", e)),
+    };
+    let root = match search_root(input, ctx) {
+        Ok(root) => root,
+        Err(result) => return result,
+    };
+    let limit = limit(input, DEFAULT_GLOB_LIMIT);
+    let (workspace, ctx) = (workspace_root(ctx), ctx.clone());
+
+    let (matches, truncated) = tokio::task::spawn_blocking(move || {
+        let mut matches = Vec::new();
+        for path in walk_files(&root, &ctx) {
+            let relative = path.strip_prefix(&root).unwrap_or(&path);
+            if pattern.matches_path_with(relative, PATH_MATCH) {
+                if matches.len() == limit {
+                    return (matches, true);
+                }
+                matches.push(shown(&path, &workspace));
+            }
+        }
+        (matches, false)
+    }).await.unwrap_or_default();
+
+    let mut metadata = HashMap::new();
+    metadata.insert("count".into(), json!(matches.len()));
+    metadata.insert("truncated".into(), json!(truncated));
+    let mut content = if matches.is_empty() { "No files found.".to_string() } else { matches.join("\n") };
+    if truncated {
+        content.push_str(&format!("\n[results truncated at {}; narrow the pattern or path]", limit));
+    }
+    ToolResult { content, is_error: false, metadata }
+}
+
+/// Contents of `path` if it is small, UTF-8-ish text.
+fn read_text(path: &Path) -> Option<String> {
+    let file = std::fs::File::open(path).ok()?;
+    if file.metadata().ok()?.len() > MAX_GREP_FILE_BYTES {
+        return None;
+    }
+    let mut bytes = Vec::new();
+    file.take(MAX_GREP_FILE_BYTES).read_to_end(&mut bytes).ok()?;
+    if bytes[..bytes.len().min(BINARY_SNIFF_BYTES)].contains(&0) {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum GrepMode {
+    Content,
+    FilesWithMatches,
+    Count,
+}
+
+/// The `grep` tool.
+pub struct GrepTool;
+
+#[async_trait]
+impl Tool for GrepTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "grep".into(),
+            description: "Search file contents with a regular expression. Skips .git, binary files and files ignored by .gitignore.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "pattern": { "type": "string", "description": "Regular expression to search for" },
+                    "path": { "type": "string", "description": "File or directory to search (default: the workspace)" },
+                    "glob": { "type": "string", "description": "Only search files matching this glob, e.g. \"*.rs\"" },
+                    "output_mode": { "type": "string", "enum": ["content", "files_with_matches", "count"] },
+                    "context": { "type": "number", "description": "Lines of context around each match" },
+                    "case_insensitive": { "type": "boolean" },
+                    "limit": { "type": "number", "description": "Maximum number of matching lines (or files)" }
+                },
+                "required": ["pattern"]
+            }),
+        }
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+        execute_grep(input, ctx).await
+    }
+}
+
+/// Execute the grep tool: lines matching a regex in files below `path`.
+pub async fn execute_grep(input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+    let Some(pattern) = input.get("pattern").and_then(|v| v.as_str()).filter(|p| !p.is_empty()) else {
+        return error("pattern is required");
+    };
+    let case_insensitive = input.get("case_insensitive").and_then(|v| v.as_bool()).unwrap_or(false);
+    let regex = match RegexBuilder::new(pattern).case_insensitive(case_insensitive).build() {
+        Ok(re) => re,
+        Err(e) => return error(format!("Invalid regex: {}", e)),
+    };
+    let file_filter = match input.get("glob").and_then(|v| v.as_str()).filter(|g| !g.trim().is_empty()) {
+        Some(glob) => match Pattern::new(glob.trim()) {
+            Ok(p) => Some(p),
+            Err(e) => return error(format!("Invalid glob pattern: {}", e)),
+        },
+        None => None,
+    };
+    let mode = match input.get("output_mode").and_then(|v| v.as_str()).unwrap_or("content") {
+        "content" => GrepMode::Content,
+        "files_with_matches" => GrepMode::FilesWithMatches,
+        "count" => GrepMode::Count,
+        other => return error(format!("Unknown output_mode: {}", other)),
+    };
+    let context = input.get("context").and_then(|v| v.as_u64()).map(|n| (n as usize).min(MAX_CONTEXT)).unwrap_or(0);
+    let root = match search_root(input, ctx) {
+        Ok(root) => root,
+        Err(result) => return result,
+    };
+    let limit = limit(input, DEFAULT_GREP_LIMIT);
+    let (workspace, ctx) = (workspace_root(ctx), ctx.clone());
+
+    let (mut lines, matches, truncated) = tokio::task::spawn_blocking(move || {
+        let files: Box<dyn Iterator<Item = PathBuf>> = if root.is_file() {
+            Box::new(std::iter::once(root.clone()))
+        } else {
+            Box::new(walk_files(&root, &ctx))
+        };
+        let mut out: Vec<String> = Vec::new();
+        let mut matches = 0;
+        for path in files {
+            let relative = path.strip_prefix(&root).unwrap_or(&path);
+            let name_matches = |p: &Pattern| p.matches_path_with(relative, PATH_MATCH)
+                || relative.file_name().is_some_and(|n| p.matches(&n.to_string_lossy()));
+            if file_filter.as_ref().is_some_and(|p| !name_matches(p)) {
+                continue;
+            }
+            let Some(text) = read_text(&path) else { continue };
+            let file_lines: Vec<&str> = text.lines().collect();
+            let hits: Vec<usize> = file_lines.iter().enumerate()
+                .filter(|(_, line)| regex.is_match(line))
+                .map(|(i, _)| i)
+                .collect();
+            if hits.is_empty() {
+                continue;
+            }
+            let name = shown(&path, &workspace);
+            // Truncated only once a hit past the limit turns up
+            match mode {
+                GrepMode::Content => {
+                    let mut last_shown: Option<usize> = None;
+                    for &hit in &hits {
+                        if matches == limit {
+                            return (out, matches, true);
+                        }
+                        let start = hit.saturating_sub(context);
+                        let end = (hit + context).min(file_lines.len() - 1);
+                        if context > 0 && last_shown.is_some_and(|last| start > last + 1) {
+                            out.push("--".into());
+                        }
+                        let from = start.max(last_shown.map_or(0, |l| l + 1));
+                        for (i, line) in file_lines.iter().enumerate().take(end + 1).skip(from) {
+                            let sep = if hits.binary_search(&i).is_ok() { ':' } else { '-' };
+                            out.push(format!("{}{}{}{}{}", name, sep, i + 1, sep, line));
+                        }
+                        last_shown = Some(end);
+                        matches += 1;
+                    }
+                    if context > 0 {
+                        out.push("--".into());
+                    }
+                    continue;
+                }
+                _ if matches == limit => return (out, matches, true),
+                GrepMode::FilesWithMatches => out.push(name),
+                GrepMode::Count => out.push(format!("{}:{}", name, hits.len())),
+            }
+            matches += 1;
+        }
+        (out, matches, false)
+    }).await.unwrap_or_default();
+    if lines.last().is_some_and(|l| l == "--") {
+        lines.pop();
+    }
+
+    let mut metadata = HashMap::new();
+    metadata.insert("matches".into(), json!(matches));
+    metadata.insert("truncated".into(), json!(truncated));
+    let mut content = if lines.is_empty() { "No matches found.".to_string() } else { lines.join("\n") };
+    if truncated {
+        content.push_str(&format!("\n[results truncated at {}; narrow the pattern, path or glob]", limit));
+    }
+    ToolResult { content, is_error: false, metadata }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace() -> (tempfile::TempDir, ToolContext) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src/nested")).unwrap();
+        std::fs::create_dir_all(root.join("target/debug")).unwrap();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n*.log\n!keep.log\n").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {\n    let answer = 42;\n    println!(\"{}\", answer);\n}\n").unwrap();
+        std::fs::write(root.join("src/nested/lib.rs"), "pub fn answer() -> u32 {\n    42\n}\n").unwrap();
+        std::fs::write(root.join("src/nested/.gitignore"), "generated.rs\n").unwrap();
+        std::fs::write(root.join("src/nested/generated.rs"), "// answer 42\n").unwrap();
+        std::fs::write(root.join("target/debug/out.rs"), "answer 42").unwrap();
+        std::fs::write(root.join(".git/config"), "answer 42").unwrap();
+        std::fs::write(root.join("debug.log"), "answer 42").unwrap();
+        std::fs::write(root.join("keep.log"), "answer 42").unwrap();
+        std::fs::write(root.join("blob.bin"), b"answer 42\0\x01").unwrap();
+        let ctx = ToolContext::new(root.to_str().unwrap());
+        (dir, ctx)
+    }
+
+    #[tokio::test]
+    async fn glob_honors_gitignore() {
+        let (_dir, ctx) = workspace();
+        let result = execute_glob(&json!({ "pattern": "**/*.rs" }), &ctx).await;
+        assert_eq!(result.content, "src/main.rs\nsrc/nested/lib.rs");
+
+        let logs = execute_glob(&json!({ "pattern": "*.log" }), &ctx).await;
+        assert_eq!(logs.content, "keep.log");
+        let nested = execute_glob(&json!({ "pattern": "*.rs", "path": "src/nested" }), &ctx).await;
+        assert_eq!(nested.content, "src/nested/lib.rs");
+        let below_ignored = execute_glob(&json!({ "pattern": "*.log", "path": "src" }), &ctx).await;
+        assert_eq!(below_ignored.content, "No files found.");
+
+        let capped = execute_glob(&json!({ "pattern": "**/*", "limit": 2 }), &ctx).await;
+        assert_eq!(capped.metadata["truncated"], true);
+        assert!(execute_glob(&json!({ "pattern": "*", "path": "../" }), &ctx).await.content.contains("outside the workspace"));
+    }
+
+    #[tokio::test]
+    async fn grep_searches_text_files() {
+        let (_dir, ctx) = workspace();
+        let files = execute_grep(&json!({ "pattern": r"answer\b", "output_mode": "files_with_matches" }), &ctx).await;
+        assert_eq!(files.content, "keep.log\nsrc/main.rs\nsrc/nested/lib.rs", "binary, ignored and .git files are skipped");
+
+        let lines = execute_grep(&json!({ "pattern": "ANSWER =", "case_insensitive": true, "context": 1, "glob": "*.rs" }), &ctx).await;
+        assert_eq!(lines.content, "src/main.rs-1-fn main() {\nsrc/main.rs:2:    let answer = 42;\nsrc/main.rs-3-    println!(\"{}\", answer);");
+
+        let count = execute_grep(&json!({ "pattern": "answer", "path": "src/main.rs", "output_mode": "count" }), &ctx).await;
+        assert_eq!(count.content, "src/main.rs:2");
+        let capped = execute_grep(&json!({ "pattern": "42", "limit": 1 }), &ctx).await;
+        assert!(capped.content.starts_with("keep.log:1:answer 42\n[results truncated at 1"));
+        let exact = execute_grep(&json!({ "pattern": "42", "limit": 3 }), &ctx).await;
+        assert_eq!((exact.metadata["matches"].as_u64(), exact.metadata["truncated"].as_bool()), (Some(3), Some(false)));
+        let all_files = execute_grep(&json!({ "pattern": r"answer\b", "output_mode": "files_with_matches", "limit": 3 }), &ctx).await;
+        assert_eq!(all_files.metadata["truncated"], false);
+        assert!(execute_grep(&json!({ "pattern": "(" }), &ctx).await.content.starts_with("Invalid regex"));
+    }
+}
@@ -0,0 +1,123 @@
+use super::executor::ToolContext;
+use super::{Tool, ToolResult};
+use crate::provider::types::ToolDefinition;
+use crate::subagents::SpawnRequest;
+use async_trait::async_trait;
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+
+fn error(content: impl Into<String>) -> ToolResult {
+    ToolResult { content: content.into(), is_error: true, metadata: HashMap::new() }
+}
+
+/// The `sessions_spawn` tool.
+pub struct SessionsSpawnTool;
+
+#[async_trait]
+impl Tool for SessionsSpawnTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "sessions_spawn".into(),
+            description: "Hand a self-contained task to a background subagent with its own session. Returns at once; the subagent's result is announced in this conversation when it finishes.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "task": { "type": "string", "description": "Everything the subagent needs to know to do the task" },
+                    "label": { "type": "string", "description": "Short name shown when the result is announced" },
+                    "agentId": { "type": "string", "description": "Agent to run the task as; defaults to the current agent" },
+                    "tools": { "type": "array", "items": { "type": "string" }, "description": "Only allow these tools" },
+                    "timeoutSeconds": { "type": "number" }
+                },
+                "required": ["task"]
+            }),
+        }
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+        execute_sessions_spawn(input, ctx).await
+    }
+}
+
+/// Execute the sessions_spawn tool: start a subagent run for a task. The
+/// result is announced to the calling session when the run ends.
+pub async fn execute_sessions_spawn(input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+    let (Some(subagents), Some(parent)) = (&ctx.subagents, &ctx.session_key) else {
+        return error("Subagents are not available in this context");
+    };
+    let task = input.get("task").and_then(|v| v.as_str()).unwrap_or("");
+    let agent_id = input.get("agentId").and_then(|v| v.as_str()).map(str::trim).filter(|s| !s.is_empty())
+        .or(ctx.agent_id.as_deref())
+        .unwrap_or(crate::agent::routing::DEFAULT_AGENT_ID);
+    let tools = input.get("tools").and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(|t| t.as_str()).map(str::to_string).collect());
+    let timeout = input.get("timeoutSeconds").and_then(|v| v.as_u64())
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs);
+
+    let request = SpawnRequest {
+        task: task.to_string(),
+        label: input.get("label").and_then(|v| v.as_str()).map(str::to_string),
+        agent_id: agent_id.to_string(),
+        parent_session: parent.clone(),
+        channel: ctx.channel.clone(),
+        to: ctx.chat_id.clone(),
+        tools,
+        timeout,
+    };
+    match subagents.spawn(request).await {
+        Ok(run) => {
+            let mut metadata = HashMap::new();
+            metadata.insert("runId".into(), json!(run.id));
+            metadata.insert("sessionKey".into(), json!(run.session_key));
+            ToolResult {
+                content: format!(
+                    "Started subagent {} (\"{}\"). Its result will be announced here when it finishes.",
+                    run.id, run.label,
+                ),
+                is_error: false,
+                metadata,
+            }
+        }
+        Err(e) => error(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subagents::{SubagentExecutor, SubagentRun, SubagentService};
+    use std::sync::Arc;
+
+    struct Echo;
+
+    #[async_trait::async_trait]
+    impl SubagentExecutor for Echo {
+        async fn run_subagent(&self, run: &SubagentRun, _cancel: tokio_util::sync::CancellationToken) -> Result<String, String> {
+            Ok(run.task.clone())
+        }
+
+        async fn announce(&self, _run: &SubagentRun) {}
+    }
+
+    #[tokio::test]
+    async fn spawns_from_the_current_session() {
+        let service = SubagentService::new(1).with_executor(Arc::new(Echo));
+        let ctx = ToolContext::new("/tmp")
+            .with_subagents(service.clone())
+            .with_session("agent:work:slack:C1", "work")
+            .with_origin("slack", "C1");
+        let input = json!({ "task": "Summarize the thread", "tools": ["Read"], "timeoutSeconds": 30 });
+        let result = execute_sessions_spawn(&input, &ctx).await;
+        assert!(!result.is_error, "{}", result.content);
+
+        let run = service.get(result.metadata["runId"].as_str().unwrap()).await.unwrap();
+        assert_eq!((run.agent_id.as_str(), run.parent_session.as_str()), ("work", "agent:work:slack:C1"));
+        assert_eq!((run.channel.as_deref(), run.to.as_deref()), (Some("slack"), Some("C1")));
+        assert_eq!(run.tools, Some(vec!["Read".to_string()]));
+
+        assert!(execute_sessions_spawn(&json!({ "task": "" }), &ctx).await.is_error);
+        let no_session = ToolContext::new("/tmp").with_subagents(service);
+        assert!(execute_sessions_spawn(&input, &no_session).await.content.contains("not available"));
+    }
+}
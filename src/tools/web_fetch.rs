@@ -0,0 +1,529 @@
+use super::executor::ToolContext;
+use super::{Tool, ToolResult};
+use crate::config::OpenClawConfig;
+use crate::markdown::html::{extract_readable, markdown_to_plain_text};
+use crate::provider::types::ToolDefinition;
+use crate::security::external_content::{wrap_web_content, ExternalContentSource};
+use async_trait::async_trait;
+use futures::StreamExt;
+use reqwest::Url;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+use tracing::debug;
+
+const DEFAULT_MAX_BYTES: usize = 2 * 1024 * 1024;
+const DEFAULT_MAX_CHARS: usize = 50_000;
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+const DEFAULT_MAX_REDIRECTS: usize = 5;
+const DEFAULT_USER_AGENT: &str = concat!("rustyclaw/", env!("CARGO_PKG_VERSION"), " (web_fetch)");
+
+/// Limits and safety settings for web_fetch.
+#[derive(Debug, Clone)]
+pub struct WebFetchOptions {
+    /// Maximum response body size read from the network.
+    pub max_bytes: usize,
+    /// Maximum characters of extracted content returned to the model.
+    pub max_chars: usize,
+    pub timeout: Duration,
+    pub max_redirects: usize,
+    /// Permit loopback/private/link-local targets. Off by default (SSRF protection).
+    pub allow_private_network: bool,
+    pub user_agent: String,
+}
+
+impl Default for WebFetchOptions {
+    fn default() -> Self {
+        Self {
+            max_bytes: DEFAULT_MAX_BYTES,
+            max_chars: DEFAULT_MAX_CHARS,
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+            allow_private_network: false,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+        }
+    }
+}
+
+impl WebFetchOptions {
+    /// Build options from `tools.web.fetch`.
+    pub fn from_config(config: &OpenClawConfig) -> Self {
+        let defaults = Self::default();
+        let Some(fetch) = config.tools.as_ref()
+            .and_then(|t| t.web.as_ref())
+            .and_then(|w| w.fetch.as_ref())
+        else {
+            return defaults;
+        };
+        Self {
+            max_bytes: fetch.max_bytes.unwrap_or(defaults.max_bytes),
+            max_chars: fetch.max_chars.unwrap_or(defaults.max_chars),
+            timeout: fetch.timeout_seconds.map(Duration::from_secs).unwrap_or(defaults.timeout),
+            max_redirects: fetch.max_redirects.unwrap_or(defaults.max_redirects),
+            allow_private_network: fetch.allow_private_network.unwrap_or(false),
+            user_agent: fetch.user_agent.clone().unwrap_or(defaults.user_agent),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WebFetchError {
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+    #[error("Unsupported URL scheme: {0} (only http and https are allowed)")]
+    UnsupportedScheme(String),
+    #[error("Blocked request to private or local address: {0}")]
+    BlockedAddress(String),
+    #[error("Could not resolve host: {0}")]
+    Resolve(String),
+    #[error("Too many redirects (max {0})")]
+    TooManyRedirects(usize),
+    #[error("HTTP {0}")]
+    Status(u16),
+    #[error("Request timed out after {0}s")]
+    Timeout(u64),
+    #[error("Unsupported content type: {0}")]
+    UnsupportedContentType(String),
+    #[error("Request failed: {0}")]
+    Request(#[from] reqwest::Error),
+}
+
+/// How fetched HTML is rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtractMode {
+    Markdown,
+    Text,
+}
+
+impl ExtractMode {
+    pub fn parse(value: Option<&str>) -> Self {
+        match value {
+            Some(v) if v.eq_ignore_ascii_case("text") => Self::Text,
+            _ => Self::Markdown,
+        }
+    }
+}
+
+/// A fetched and extracted page.
+#[derive(Debug, Clone)]
+pub struct FetchedPage {
+    pub url: String,
+    pub final_url: String,
+    pub status: u16,
+    pub content_type: String,
+    pub title: Option<String>,
+    pub content: String,
+    /// The body hit `max_bytes` or the content hit `max_chars`.
+    pub truncated: bool,
+}
+
+/// Whether an address is loopback, private, link-local or otherwise not
+/// publicly routable.
+pub fn is_private_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_private_ipv4(v4),
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped().or_else(|| embedded_ipv4(v6)) {
+                return is_private_ipv4(v4);
+            }
+            let first = v6.segments()[0];
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                || (first & 0xfe00) == 0xfc00 // unique local fc00::/7
+                || (first & 0xffc0) == 0xfe80 // link-local fe80::/10
+                || first == 0x2001 && v6.segments()[1] == 0x0db8 // documentation
+        }
+    }
+}
+
+/// The IPv4 address carried by an IPv4-compatible (`::a.b.c.d`), NAT64
+/// (`64:ff9b::/96`) or 6to4 (`2002::/16`) address; these reach the embedded
+/// host.
+fn embedded_ipv4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+    let s = ip.segments();
+    let v4 = |hi: u16, lo: u16| Some(Ipv4Addr::from(((hi as u32) << 16) | lo as u32));
+    match s {
+        [0, 0, 0, 0, 0, 0, hi, lo] => v4(hi, lo),
+        [0x64, 0xff9b, 0, 0, 0, 0, hi, lo] => v4(hi, lo),
+        [0x2002, hi, lo, ..] => v4(hi, lo),
+        _ => None,
+    }
+}
+
+fn is_private_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, _, _] = ip.octets();
+    ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || a == 0
+        || (a == 100 && (64..128).contains(&b)) // carrier-grade NAT
+        || (a == 198 && (b == 18 || b == 19)) // benchmarking
+        || a >= 240
+}
+
+/// Resolve the URL's host and check every address against the private-range
+/// policy. Returns the address to pin the connection to, so a second DNS
+/// lookup can't swap in a private address after the check.
+async fn resolve_target(url: &Url, opts: &WebFetchOptions) -> Result<Option<SocketAddr>, WebFetchError> {
+    let host = url.host_str().ok_or_else(|| WebFetchError::InvalidUrl(url.to_string()))?;
+    let port = url.port_or_known_default().unwrap_or(80);
+
+    let literal = host.trim_start_matches('[').trim_end_matches(']');
+    if let Ok(ip) = literal.parse::<IpAddr>() {
+        if !opts.allow_private_network && is_private_ip(ip) {
+            return Err(WebFetchError::BlockedAddress(ip.to_string()));
+        }
+        return Ok(None);
+    }
+
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port)).await
+        .map_err(|e| WebFetchError::Resolve(format!("{}: {}", host, e)))?
+        .collect();
+    if addrs.is_empty() {
+        return Err(WebFetchError::Resolve(host.to_string()));
+    }
+    if !opts.allow_private_network {
+        if let Some(addr) = addrs.iter().find(|a| is_private_ip(a.ip())) {
+            return Err(WebFetchError::BlockedAddress(format!("{} ({})", host, addr.ip())));
+        }
+    }
+    Ok(Some(addrs[0]))
+}
+
+fn check_scheme(url: &Url) -> Result<(), WebFetchError> {
+    match url.scheme() {
+        "http" | "https" => Ok(()),
+        other => Err(WebFetchError::UnsupportedScheme(other.to_string())),
+    }
+}
+
+/// Fetch a URL and extract its readable content.
+pub async fn fetch(url: &str, mode: ExtractMode, opts: &WebFetchOptions) -> Result<FetchedPage, WebFetchError> {
+    let timeout_secs = opts.timeout.as_secs();
+    match tokio::time::timeout(opts.timeout, fetch_inner(url, mode, opts)).await {
+        Ok(result) => result,
+        Err(_) => Err(WebFetchError::Timeout(timeout_secs)),
+    }
+}
+
+async fn fetch_inner(url: &str, mode: ExtractMode, opts: &WebFetchOptions) -> Result<FetchedPage, WebFetchError> {
+    let mut current = Url::parse(url.trim()).map_err(|e| WebFetchError::InvalidUrl(format!("{}: {}", url, e)))?;
+    let mut redirects = 0;
+
+    let response = loop {
+        check_scheme(&current)?;
+        let pinned = resolve_target(&current, opts).await?;
+
+        // Redirects are followed by hand so every hop goes through the address check.
+        // A proxy would connect on our behalf and skip the pinned address
+        let mut builder = reqwest::Client::builder()
+            .no_proxy()
+            .redirect(reqwest::redirect::Policy::none())
+            .user_agent(opts.user_agent.clone())
+            .timeout(opts.timeout);
+        if let (Some(addr), Some(host)) = (pinned, current.host_str()) {
+            builder = builder.resolve(host, addr);
+        }
+        let client = builder.build()?;
+
+        debug!("web_fetch GET {}", current);
+        let response = client.get(current.clone())
+            .header(reqwest::header::ACCEPT, "text/html,application/xhtml+xml,text/plain;q=0.9,*/*;q=0.5")
+            .send().await?;
+
+        if response.status().is_redirection() {
+            let Some(location) = response.headers().get(reqwest::header::LOCATION)
+                .and_then(|v| v.to_str().ok())
+            else {
+                break response;
+            };
+            if redirects >= opts.max_redirects {
+                return Err(WebFetchError::TooManyRedirects(opts.max_redirects));
+            }
+            redirects += 1;
+            current = current.join(location)
+                .map_err(|e| WebFetchError::InvalidUrl(format!("{}: {}", location, e)))?;
+            continue;
+        }
+        break response;
+    };
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(WebFetchError::Status(status.as_u16()));
+    }
+
+    let content_type = response.headers().get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    let mime = content_type.split(';').next().unwrap_or("").trim().to_lowercase();
+    let is_html = mime == "text/html" || mime == "application/xhtml+xml";
+    let is_text = mime.is_empty()
+        || mime.starts_with("text/")
+        || mime.ends_with("json")
+        || mime.ends_with("xml")
+        || mime == "application/javascript";
+    if !is_html && !is_text {
+        return Err(WebFetchError::UnsupportedContentType(mime));
+    }
+
+    // Stream the body so an oversized response is cut off at max_bytes.
+    let mut body: Vec<u8> = Vec::new();
+    let mut truncated = false;
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        let remaining = opts.max_bytes - body.len();
+        if chunk.len() > remaining {
+            body.extend_from_slice(&chunk[..remaining]);
+            truncated = true;
+            break;
+        }
+        body.extend_from_slice(&chunk);
+    }
+    let text = String::from_utf8_lossy(&body).to_string();
+
+    let sniffed_html = mime.is_empty() && text.trim_start().starts_with('<');
+    let (title, mut content) = if is_html || sniffed_html {
+        let page = extract_readable(&text, Some(&current));
+        let content = match mode {
+            ExtractMode::Markdown => page.markdown,
+            ExtractMode::Text => markdown_to_plain_text(&page.markdown),
+        };
+        (page.title, content)
+    } else {
+        (None, text)
+    };
+
+    if content.chars().count() > opts.max_chars {
+        content = content.chars().take(opts.max_chars).collect();
+        truncated = true;
+    }
+
+    Ok(FetchedPage {
+        url: url.to_string(),
+        final_url: current.to_string(),
+        status: status.as_u16(),
+        content_type: mime,
+        title,
+        content,
+        truncated,
+    })
+}
+
+/// The `web_fetch` tool.
+pub struct WebFetchTool;
+
+#[async_trait]
+impl Tool for WebFetchTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "web_fetch".into(),
+            description: "Fetch and extract readable content from a URL. Private and local network addresses are blocked.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "url": { "type": "string", "description": "URL to fetch (http or https)" },
+                    "extractMode": { "type": "string", "enum": ["markdown", "text"] },
+                    "maxChars": { "type": "number", "description": "Maximum characters of content to return" }
+                },
+                "required": ["url"]
+            }),
+        }
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+        execute_web_fetch(input, ctx).await
+    }
+}
+
+/// Execute the web_fetch tool.
+pub async fn execute_web_fetch(input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+    let url = input.get("url").and_then(|v| v.as_str()).unwrap_or("");
+    if url.is_empty() {
+        return ToolResult {
+            content: "url is required".into(),
+            is_error: true,
+            metadata: HashMap::new(),
+        };
+    }
+    let mode = ExtractMode::parse(input.get("extractMode").and_then(|v| v.as_str()));
+
+    let mut opts = ctx.web_fetch.clone();
+    if let Some(max_chars) = input.get("maxChars").and_then(|v| v.as_u64()) {
+        opts.max_chars = opts.max_chars.min(max_chars as usize);
+    }
+
+    match fetch(url, mode, &opts).await {
+        Ok(page) => {
+            let mut header = format!("URL: {}\n", page.final_url);
+            if let Some(title) = &page.title {
+                header.push_str(&format!("Title: {}\n", title));
+            }
+            if page.truncated {
+                header.push_str("(content truncated)\n");
+            }
+            let content = wrap_web_content(
+                &format!("{}\n{}", header, page.content),
+                ExternalContentSource::WebFetch,
+            );
+
+            let mut metadata = HashMap::new();
+            metadata.insert("status".into(), serde_json::json!(page.status));
+            metadata.insert("finalUrl".into(), serde_json::json!(page.final_url));
+            metadata.insert("contentType".into(), serde_json::json!(page.content_type));
+            metadata.insert("truncated".into(), serde_json::json!(page.truncated));
+            if let Some(title) = page.title {
+                metadata.insert("title".into(), serde_json::json!(title));
+            }
+            ToolResult { content, is_error: false, metadata }
+        }
+        Err(e) => ToolResult {
+            content: format!("Error fetching {}: {}", url, e),
+            is_error: true,
+            metadata: HashMap::new(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::header;
+    use axum::response::{IntoResponse, Redirect};
+    use axum::routing::get;
+    use axum::Router;
+
+    const ARTICLE_HTML: &str = "<html><head><title>Fixture</title></head><body>\
+        <nav>Menu</nav><article><h1>Hello</h1><p>Readable <b>text</b>.</p></article></body></html>";
+
+    async fn serve_fixtures() -> String {
+        let app = Router::new()
+            .route("/article", get(|| async {
+                ([(header::CONTENT_TYPE, "text/html; charset=utf-8")], ARTICLE_HTML)
+            }))
+            .route("/redirect", get(|| async { Redirect::temporary("/article") }))
+            .route("/loop", get(|| async { Redirect::temporary("/loop") }))
+            .route("/big", get(|| async {
+                ([(header::CONTENT_TYPE, "text/plain")], "x".repeat(10_000))
+            }))
+            .route("/binary", get(|| async {
+                ([(header::CONTENT_TYPE, "application/octet-stream")], vec![0u8, 1, 2]).into_response()
+            }))
+            .route("/missing", get(|| async { axum::http::StatusCode::NOT_FOUND }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://{}", addr)
+    }
+
+    fn local_opts() -> WebFetchOptions {
+        WebFetchOptions {
+            allow_private_network: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn private_ranges_detected() {
+        for ip in ["127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254",
+                   "100.64.0.1", "0.0.0.0", "::1", "fd00::1", "fe80::1", "::ffff:127.0.0.1",
+                   "::127.0.0.1", "::10.0.0.1", "64:ff9b::7f00:1", "64:ff9b::169.254.169.254",
+                   "2002:c0a8:0101::1", "2002:7f00:1::"] {
+            assert!(is_private_ip(ip.parse().unwrap()), "{} should be private", ip);
+        }
+        for ip in ["8.8.8.8", "1.1.1.1", "2606:4700:4700::1111", "64:ff9b::8.8.8.8", "2002:808:808::1"] {
+            assert!(!is_private_ip(ip.parse().unwrap()), "{} should be public", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn blocks_loopback_by_default() {
+        let base = serve_fixtures().await;
+        let err = fetch(&format!("{}/article", base), ExtractMode::Markdown, &WebFetchOptions::default())
+            .await.unwrap_err();
+        assert!(matches!(err, WebFetchError::BlockedAddress(_)));
+
+        let err = fetch("http://localhost:9/", ExtractMode::Markdown, &WebFetchOptions::default())
+            .await.unwrap_err();
+        assert!(matches!(err, WebFetchError::BlockedAddress(_)));
+    }
+
+    #[tokio::test]
+    async fn rejects_non_http_schemes() {
+        let err = fetch("file:///etc/passwd", ExtractMode::Text, &local_opts()).await.unwrap_err();
+        assert!(matches!(err, WebFetchError::UnsupportedScheme(_)));
+    }
+
+    #[tokio::test]
+    async fn extracts_article_markdown_and_text() {
+        let base = serve_fixtures().await;
+        let page = fetch(&format!("{}/article", base), ExtractMode::Markdown, &local_opts()).await.unwrap();
+        assert_eq!(page.title.as_deref(), Some("Fixture"));
+        assert_eq!(page.content, "# Hello\n\nReadable **text**.");
+        assert!(!page.truncated);
+
+        let page = fetch(&format!("{}/article", base), ExtractMode::Text, &local_opts()).await.unwrap();
+        assert_eq!(page.content, "Hello\n\nReadable text.");
+    }
+
+    #[tokio::test]
+    async fn follows_redirects_with_limit() {
+        let base = serve_fixtures().await;
+        let page = fetch(&format!("{}/redirect", base), ExtractMode::Markdown, &local_opts()).await.unwrap();
+        assert!(page.final_url.ends_with("/article"));
+
+        let err = fetch(&format!("{}/loop", base), ExtractMode::Markdown, &local_opts()).await.unwrap_err();
+        assert!(matches!(err, WebFetchError::TooManyRedirects(5)));
+    }
+
+    #[tokio::test]
+    async fn enforces_size_limits() {
+        let base = serve_fixtures().await;
+        let opts = WebFetchOptions { max_bytes: 1024, ..local_opts() };
+        let page = fetch(&format!("{}/big", base), ExtractMode::Text, &opts).await.unwrap();
+        assert_eq!(page.content.len(), 1024);
+        assert!(page.truncated);
+
+        let opts = WebFetchOptions { max_chars: 100, ..local_opts() };
+        let page = fetch(&format!("{}/big", base), ExtractMode::Text, &opts).await.unwrap();
+        assert_eq!(page.content.len(), 100);
+        assert!(page.truncated);
+    }
+
+    #[tokio::test]
+    async fn rejects_binary_and_error_status() {
+        let base = serve_fixtures().await;
+        let err = fetch(&format!("{}/binary", base), ExtractMode::Text, &local_opts()).await.unwrap_err();
+        assert!(matches!(err, WebFetchError::UnsupportedContentType(_)));
+
+        let err = fetch(&format!("{}/missing", base), ExtractMode::Text, &local_opts()).await.unwrap_err();
+        assert!(matches!(err, WebFetchError::Status(404)));
+    }
+
+    #[tokio::test]
+    async fn tool_wraps_content() {
+        let base = serve_fixtures().await;
+        let mut ctx = ToolContext::new("/tmp");
+        ctx.web_fetch = local_opts();
+        let input = serde_json::json!({ "url": format!("{}/article", base) });
+        let result = execute_web_fetch(&input, &ctx).await;
+        assert!(!result.is_error);
+        assert!(result.content.contains("Source: Web Fetch"));
+        assert!(result.content.contains("Title: Fixture"));
+        assert!(result.content.contains("Readable **text**."));
+        assert_eq!(result.metadata["status"], 200);
+
+        let blocked = execute_web_fetch(&input, &ToolContext::new("/tmp")).await;
+        assert!(blocked.is_error);
+        assert!(blocked.content.contains("Blocked"));
+    }
+}
@@ -0,0 +1,625 @@
+use super::executor::ToolContext;
+use super::{Tool, ToolResult};
+use crate::config::types::{JsonSearchConfig, WebSearchConfig};
+use crate::config::OpenClawConfig;
+use crate::markdown::html::decode_entities;
+use crate::provider::types::ToolDefinition;
+use crate::security::external_content::{wrap_web_content, ExternalContentSource};
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::debug;
+
+const BRAVE_API_URL: &str = "https://api.search.brave.com/res/v1/web/search";
+const DEFAULT_MAX_RESULTS: usize = 10;
+const DEFAULT_TIMEOUT_SECS: u64 = 15;
+const DEFAULT_CACHE_TTL_MINUTES: u64 = 15;
+const MAX_CACHE_ENTRIES: usize = 200;
+
+/// A normalized search hit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SearchError {
+    #[error("Web search is not configured: {0}")]
+    NotConfigured(String),
+    #[error("Search API error: {status} - {message}")]
+    Api { status: u16, message: String },
+    #[error("Search request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Unexpected search response: {0}")]
+    Parse(String),
+}
+
+/// A web search provider.
+#[async_trait]
+pub trait SearchBackend: Send + Sync {
+    /// Backend name, e.g. "brave".
+    fn name(&self) -> &str;
+
+    /// Run a query, returning at most `count` raw results.
+    async fn search(&self, query: &str, count: usize) -> Result<Vec<SearchResult>, SearchError>;
+}
+
+async fn read_json(response: reqwest::Response) -> Result<Value, SearchError> {
+    let status = response.status();
+    if !status.is_success() {
+        let message = response.text().await.unwrap_or_default();
+        return Err(SearchError::Api {
+            status: status.as_u16(),
+            message: message.chars().take(200).collect(),
+        });
+    }
+    response.json::<Value>().await
+        .map_err(|e| SearchError::Parse(e.to_string()))
+}
+
+fn str_field(value: &Value, key: &str) -> String {
+    lookup_path(value, key)
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string()
+}
+
+/// Follow a dot path ("data.items") into a JSON value.
+fn lookup_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    if path.is_empty() {
+        return Some(value);
+    }
+    path.split('.').try_fold(value, |v, key| match key.parse::<usize>() {
+        Ok(idx) if v.is_array() => v.get(idx),
+        _ => v.get(key),
+    })
+}
+
+// ── Brave ──
+
+/// Brave Search API backend.
+pub struct BraveBackend {
+    client: Client,
+    api_key: String,
+    endpoint: String,
+}
+
+impl BraveBackend {
+    pub fn new(client: Client, api_key: String) -> Self {
+        Self { client, api_key, endpoint: BRAVE_API_URL.to_string() }
+    }
+
+    pub fn with_endpoint(mut self, endpoint: String) -> Self {
+        self.endpoint = endpoint;
+        self
+    }
+
+    /// Resolve API key from environment.
+    pub fn api_key_from_env() -> Option<String> {
+        std::env::var("BRAVE_API_KEY").ok().filter(|k| !k.is_empty())
+    }
+}
+
+#[async_trait]
+impl SearchBackend for BraveBackend {
+    fn name(&self) -> &str {
+        "brave"
+    }
+
+    async fn search(&self, query: &str, count: usize) -> Result<Vec<SearchResult>, SearchError> {
+        let response = self.client.get(&self.endpoint)
+            .header("Accept", "application/json")
+            .header("X-Subscription-Token", &self.api_key)
+            .query(&[("q", query), ("count", &count.min(20).to_string())])
+            .send().await?;
+        let body = read_json(response).await?;
+        let results = body.pointer("/web/results")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+        Ok(results.iter().map(|r| SearchResult {
+            title: str_field(r, "title"),
+            url: str_field(r, "url"),
+            snippet: str_field(r, "description"),
+            published: r.get("age").and_then(|v| v.as_str()).map(String::from),
+        }).collect())
+    }
+}
+
+// ── SearXNG ──
+
+/// Self-hosted SearXNG backend (requires the JSON output format to be enabled).
+pub struct SearxngBackend {
+    client: Client,
+    base_url: String,
+}
+
+impl SearxngBackend {
+    pub fn new(client: Client, base_url: String) -> Self {
+        Self { client, base_url: base_url.trim_end_matches('/').to_string() }
+    }
+}
+
+#[async_trait]
+impl SearchBackend for SearxngBackend {
+    fn name(&self) -> &str {
+        "searxng"
+    }
+
+    async fn search(&self, query: &str, _count: usize) -> Result<Vec<SearchResult>, SearchError> {
+        let response = self.client.get(format!("{}/search", self.base_url))
+            .query(&[("q", query), ("format", "json")])
+            .send().await?;
+        let body = read_json(response).await?;
+        let results = body.get("results")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| SearchError::Parse("missing results array".into()))?;
+        Ok(results.iter().map(|r| SearchResult {
+            title: str_field(r, "title"),
+            url: str_field(r, "url"),
+            snippet: str_field(r, "content"),
+            published: r.get("publishedDate").and_then(|v| v.as_str()).map(String::from),
+        }).collect())
+    }
+}
+
+// ── Generic JSON ──
+
+/// Any JSON-over-HTTP search endpoint, mapped through configurable field paths.
+pub struct JsonBackend {
+    client: Client,
+    endpoint: String,
+    query_param: String,
+    count_param: Option<String>,
+    results_path: String,
+    title_field: String,
+    url_field: String,
+    snippet_field: String,
+    headers: HashMap<String, String>,
+}
+
+impl JsonBackend {
+    pub fn new(client: Client, endpoint: String, mapping: &JsonSearchConfig) -> Self {
+        Self {
+            client,
+            endpoint,
+            query_param: mapping.query_param.clone().unwrap_or_else(|| "q".into()),
+            count_param: mapping.count_param.clone(),
+            results_path: mapping.results_path.clone().unwrap_or_else(|| "results".into()),
+            title_field: mapping.title_field.clone().unwrap_or_else(|| "title".into()),
+            url_field: mapping.url_field.clone().unwrap_or_else(|| "url".into()),
+            snippet_field: mapping.snippet_field.clone().unwrap_or_else(|| "snippet".into()),
+            headers: mapping.headers.clone().unwrap_or_default(),
+        }
+    }
+}
+
+#[async_trait]
+impl SearchBackend for JsonBackend {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    async fn search(&self, query: &str, count: usize) -> Result<Vec<SearchResult>, SearchError> {
+        let mut params = vec![(self.query_param.clone(), query.to_string())];
+        if let Some(count_param) = &self.count_param {
+            params.push((count_param.clone(), count.to_string()));
+        }
+        let mut request = self.client.get(&self.endpoint).query(&params);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        let body = read_json(request.send().await?).await?;
+        let results = lookup_path(&body, &self.results_path)
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| SearchError::Parse(format!("no array at '{}'", self.results_path)))?;
+        Ok(results.iter().map(|r| SearchResult {
+            title: str_field(r, &self.title_field),
+            url: str_field(r, &self.url_field),
+            snippet: str_field(r, &self.snippet_field),
+            published: None,
+        }).collect())
+    }
+}
+
+/// Clean up raw backend results: strip highlight markup, decode entities,
+/// drop entries without a URL, de-duplicate by URL and cap the count.
+pub fn normalize_results(results: Vec<SearchResult>, count: usize) -> Vec<SearchResult> {
+    let tag_re = Regex::new(r"<[^>]*>").unwrap();
+    let clean = |s: &str| {
+        let stripped = tag_re.replace_all(s, "");
+        decode_entities(&stripped).split_whitespace().collect::<Vec<_>>().join(" ")
+    };
+
+    let mut seen = HashSet::new();
+    results.into_iter()
+        .filter_map(|r| {
+            let url = r.url.trim().to_string();
+            if url.is_empty() || !seen.insert(url.trim_end_matches('/').to_string()) {
+                return None;
+            }
+            let title = clean(&r.title);
+            Some(SearchResult {
+                title: if title.is_empty() { url.clone() } else { title },
+                url,
+                snippet: clean(&r.snippet),
+                published: r.published,
+            })
+        })
+        .take(count)
+        .collect()
+}
+
+struct CacheEntry {
+    stored_at: Instant,
+    results: Vec<SearchResult>,
+}
+
+/// Configured search backend with a result cache.
+#[derive(Clone)]
+pub struct WebSearch {
+    backend: Arc<dyn SearchBackend>,
+    cache: Arc<RwLock<HashMap<String, CacheEntry>>>,
+    cache_ttl: Duration,
+    max_results: usize,
+}
+
+impl WebSearch {
+    pub fn new(backend: Arc<dyn SearchBackend>) -> Self {
+        Self {
+            backend,
+            cache: Arc::new(RwLock::new(HashMap::new())),
+            cache_ttl: Duration::from_secs(DEFAULT_CACHE_TTL_MINUTES * 60),
+            max_results: DEFAULT_MAX_RESULTS,
+        }
+    }
+
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    pub fn with_max_results(mut self, max_results: usize) -> Self {
+        self.max_results = max_results.max(1);
+        self
+    }
+
+    /// Build from `tools.web.search`. Returns an error when search is disabled
+    /// or the selected backend is missing its endpoint or key.
+    pub fn from_config(config: &OpenClawConfig) -> Result<Self, SearchError> {
+        let search = config.tools.as_ref()
+            .and_then(|t| t.web.as_ref())
+            .and_then(|w| w.search.clone())
+            .unwrap_or_default();
+        Self::from_search_config(&search)
+    }
+
+    pub fn from_search_config(search: &WebSearchConfig) -> Result<Self, SearchError> {
+        if search.enabled == Some(false) {
+            return Err(SearchError::NotConfigured("disabled in config".into()));
+        }
+        let client = Client::builder()
+            .timeout(Duration::from_secs(search.timeout_seconds.unwrap_or(DEFAULT_TIMEOUT_SECS)))
+            .build()?;
+
+        let provider = search.provider.as_deref().unwrap_or("brave").to_lowercase();
+        let backend: Arc<dyn SearchBackend> = match provider.as_str() {
+            "brave" => {
+                let api_key = search.api_key.clone()
+                    .filter(|k| !k.is_empty())
+                    .or_else(BraveBackend::api_key_from_env)
+                    .ok_or_else(|| SearchError::NotConfigured(
+                        "set tools.web.search.apiKey or BRAVE_API_KEY".into(),
+                    ))?;
+                let mut backend = BraveBackend::new(client, api_key);
+                if let Some(url) = &search.base_url {
+                    backend = backend.with_endpoint(url.clone());
+                }
+                Arc::new(backend)
+            }
+            "searxng" => {
+                let base_url = search.base_url.clone().ok_or_else(|| SearchError::NotConfigured(
+                    "searxng requires tools.web.search.baseUrl".into(),
+                ))?;
+                Arc::new(SearxngBackend::new(client, base_url))
+            }
+            "json" => {
+                let endpoint = search.base_url.clone().ok_or_else(|| SearchError::NotConfigured(
+                    "json search requires tools.web.search.baseUrl".into(),
+                ))?;
+                Arc::new(JsonBackend::new(client, endpoint, &search.json.clone().unwrap_or_default()))
+            }
+            other => return Err(SearchError::NotConfigured(format!("unknown provider '{}'", other))),
+        };
+
+        let mut web_search = Self::new(backend)
+            .with_max_results(search.max_results.unwrap_or(DEFAULT_MAX_RESULTS));
+        if let Some(minutes) = search.cache_ttl_minutes {
+            web_search = web_search.with_cache_ttl(Duration::from_secs(minutes * 60));
+        }
+        Ok(web_search)
+    }
+
+    pub fn backend_name(&self) -> &str {
+        self.backend.name()
+    }
+
+    /// Search, serving repeated queries from the cache. Returns the results
+    /// and whether they came from the cache.
+    pub async fn search(&self, query: &str, count: Option<usize>) -> Result<(Vec<SearchResult>, bool), SearchError> {
+        let count = count.unwrap_or(self.max_results).clamp(1, self.max_results);
+        let key = format!("{}\u{0}{}", query.trim().to_lowercase(), count);
+
+        if let Some(entry) = self.cache.read().await.get(&key) {
+            if entry.stored_at.elapsed() < self.cache_ttl {
+                return Ok((entry.results.clone(), true));
+            }
+        }
+
+        debug!("web_search [{}] {}", self.backend.name(), query);
+        let raw = self.backend.search(query.trim(), count).await?;
+        let results = normalize_results(raw, count);
+
+        let mut cache = self.cache.write().await;
+        let ttl = self.cache_ttl;
+        cache.retain(|_, e| e.stored_at.elapsed() < ttl);
+        if cache.len() >= MAX_CACHE_ENTRIES {
+            if let Some(oldest) = cache.iter().min_by_key(|(_, e)| e.stored_at).map(|(k, _)| k.clone()) {
+                cache.remove(&oldest);
+            }
+        }
+        cache.insert(key, CacheEntry { stored_at: Instant::now(), results: results.clone() });
+
+        Ok((results, false))
+    }
+}
+
+/// Render results as a numbered list.
+pub fn format_results(query: &str, results: &[SearchResult]) -> String {
+    if results.is_empty() {
+        return format!("No results for \"{}\"", query);
+    }
+    let mut out = format!("Results for \"{}\":\n", query);
+    for (i, r) in results.iter().enumerate() {
+        out.push_str(&format!("\n{}. {}\n   {}\n", i + 1, r.title, r.url));
+        if let Some(published) = &r.published {
+            out.push_str(&format!("   Published: {}\n", published));
+        }
+        if !r.snippet.is_empty() {
+            out.push_str(&format!("   {}\n", r.snippet));
+        }
+    }
+    out.trim_end().to_string()
+}
+
+/// The `web_search` tool.
+pub struct WebSearchTool;
+
+#[async_trait]
+impl Tool for WebSearchTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "web_search".into(),
+            description: "Search the web. Returns titles, URLs and snippets.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Search query" },
+                    "count": { "type": "number", "description": "Number of results" }
+                },
+                "required": ["query"]
+            }),
+        }
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+        execute_web_search(input, ctx).await
+    }
+}
+
+/// Execute the web_search tool.
+pub async fn execute_web_search(input: &serde_json::Value, ctx: &ToolContext) -> ToolResult {
+    let query = input.get("query").and_then(|v| v.as_str()).unwrap_or("").trim();
+    if query.is_empty() {
+        return ToolResult {
+            content: "query is required".into(),
+            is_error: true,
+            metadata: HashMap::new(),
+        };
+    }
+    let Some(search) = &ctx.web_search else {
+        return ToolResult {
+            content: "Web search is not configured (set tools.web.search in config)".into(),
+            is_error: true,
+            metadata: HashMap::new(),
+        };
+    };
+    let count = input.get("count").and_then(|v| v.as_u64()).map(|c| c as usize);
+
+    match search.search(query, count).await {
+        Ok((results, cached)) => {
+            let content = wrap_web_content(&format_results(query, &results), ExternalContentSource::WebSearch);
+            let mut metadata = HashMap::new();
+            metadata.insert("provider".into(), serde_json::json!(search.backend_name()));
+            metadata.insert("count".into(), serde_json::json!(results.len()));
+            metadata.insert("cached".into(), serde_json::json!(cached));
+            metadata.insert("results".into(), serde_json::json!(results));
+            ToolResult { content, is_error: false, metadata }
+        }
+        Err(e) => ToolResult {
+            content: e.to_string(),
+            is_error: true,
+            metadata: HashMap::new(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::Query;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::response::IntoResponse;
+    use axum::routing::get;
+    use axum::{Json, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    async fn serve_stub(hits: Arc<AtomicUsize>) -> String {
+        let brave_hits = hits.clone();
+        let app = Router::new()
+            .route("/brave", get(move |headers: HeaderMap, Query(q): Query<HashMap<String, String>>| {
+                let hits = brave_hits.clone();
+                async move {
+                    hits.fetch_add(1, Ordering::SeqCst);
+                    if headers.get("x-subscription-token").and_then(|v| v.to_str().ok()) != Some("test-key") {
+                        return (StatusCode::UNAUTHORIZED, Json(serde_json::json!({}))).into_response();
+                    }
+                    Json(serde_json::json!({
+                        "web": { "results": [
+                            { "title": format!("<strong>{}</strong> docs", q["q"]), "url": "https://a.example/", "description": "All &amp; more", "age": "2 days ago" },
+                            { "title": "Duplicate", "url": "https://a.example", "description": "" },
+                            { "title": "", "url": "https://b.example/page", "description": "Second" }
+                        ]}
+                    })).into_response()
+                }
+            }))
+            .route("/searx/search", get(|Query(q): Query<HashMap<String, String>>| async move {
+                assert_eq!(q.get("format").map(String::as_str), Some("json"));
+                Json(serde_json::json!({
+                    "results": [
+                        { "title": "Searx hit", "url": "https://s.example", "content": "From searx" },
+                        { "title": "No url", "url": "", "content": "dropped" }
+                    ]
+                }))
+            }))
+            .route("/custom", get(|Query(q): Query<HashMap<String, String>>| async move {
+                Json(serde_json::json!({
+                    "data": { "items": [
+                        { "name": format!("Custom {}", q["term"]), "link": "https://c.example", "summary": { "text": "nested" } }
+                    ]}
+                }))
+            }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://{}", addr)
+    }
+
+
+    fn config(provider: &str, base_url: String) -> WebSearchConfig {
+        WebSearchConfig {
+            provider: Some(provider.into()),
+            api_key: Some("test-key".into()),
+            base_url: Some(base_url),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn brave_results_are_normalized() {
+        let base = serve_stub(Arc::new(AtomicUsize::new(0))).await;
+        let search = WebSearch::from_search_config(&config("brave", format!("{}/brave", base))).unwrap();
+        let (results, cached) = search.search("rust", None).await.unwrap();
+        assert!(!cached);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "rust docs");
+        assert_eq!(results[0].snippet, "All & more");
+        assert_eq!(results[0].published.as_deref(), Some("2 days ago"));
+        assert_eq!(results[1].title, "https://b.example/page");
+    }
+
+    #[tokio::test]
+    async fn brave_reports_api_errors() {
+        let base = serve_stub(Arc::new(AtomicUsize::new(0))).await;
+        let mut cfg = config("brave", format!("{}/brave", base));
+        cfg.api_key = Some("wrong".into());
+        let search = WebSearch::from_search_config(&cfg).unwrap();
+        let err = search.search("rust", None).await.unwrap_err();
+        assert!(matches!(err, SearchError::Api { status: 401, .. }));
+    }
+
+    #[tokio::test]
+    async fn searxng_backend() {
+        let base = serve_stub(Arc::new(AtomicUsize::new(0))).await;
+        let search = WebSearch::from_search_config(&config("searxng", format!("{}/searx/", base))).unwrap();
+        assert_eq!(search.backend_name(), "searxng");
+        let (results, _) = search.search("anything", Some(5)).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].snippet, "From searx");
+    }
+
+    #[tokio::test]
+    async fn json_backend_uses_field_mapping() {
+        let base = serve_stub(Arc::new(AtomicUsize::new(0))).await;
+        let mut cfg = config("json", format!("{}/custom", base));
+        cfg.json = Some(JsonSearchConfig {
+            query_param: Some("term".into()),
+            results_path: Some("data.items".into()),
+            title_field: Some("name".into()),
+            url_field: Some("link".into()),
+            snippet_field: Some("summary.text".into()),
+            ..Default::default()
+        });
+        let search = WebSearch::from_search_config(&cfg).unwrap();
+        let (results, _) = search.search("widgets", None).await.unwrap();
+        assert_eq!(results, vec![SearchResult {
+            title: "Custom widgets".into(),
+            url: "https://c.example".into(),
+            snippet: "nested".into(),
+            published: None,
+        }]);
+    }
+
+    #[tokio::test]
+    async fn repeated_queries_hit_cache() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let base = serve_stub(hits.clone()).await;
+        let search = WebSearch::from_search_config(&config("brave", format!("{}/brave", base))).unwrap();
+        search.search("Rust", None).await.unwrap();
+        let (_, cached) = search.search("  rust ", None).await.unwrap();
+        assert!(cached);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        let expired = search.clone().with_cache_ttl(Duration::ZERO);
+        let (_, cached) = expired.search("rust", None).await.unwrap();
+        assert!(!cached);
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn config_errors() {
+        let missing = WebSearchConfig { provider: Some("searxng".into()), ..Default::default() };
+        assert!(matches!(WebSearch::from_search_config(&missing), Err(SearchError::NotConfigured(_))));
+        let unknown = WebSearchConfig { provider: Some("altavista".into()), ..Default::default() };
+        assert!(matches!(WebSearch::from_search_config(&unknown), Err(SearchError::NotConfigured(_))));
+    }
+
+    #[tokio::test]
+    async fn tool_wraps_results() {
+        let base = serve_stub(Arc::new(AtomicUsize::new(0))).await;
+        let search = WebSearch::from_search_config(&config("brave", format!("{}/brave", base))).unwrap();
+        let ctx = ToolContext::new("/tmp").with_web_search(search);
+        let result = execute_web_search(&serde_json::json!({ "query": "rust", "count": 1 }), &ctx).await;
+        assert!(!result.is_error);
+        assert!(result.content.contains("Source: Web Search"));
+        assert!(result.content.contains("1. rust docs\n   https://a.example/"));
+        assert!(!result.content.contains("b.example"));
+        assert_eq!(result.metadata["provider"], "brave");
+
+        let unconfigured = execute_web_search(&serde_json::json!({ "query": "rust" }), &ToolContext::new("/tmp")).await;
+        assert!(unconfigured.is_error);
+    }
+}
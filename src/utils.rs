@@ -1,5 +1,8 @@
 use std::path::{Path, PathBuf};
 
+/// Length of run excerpts kept for status and history.
+const EXCERPT_CHARS: usize = 280;
+
 /// Clamp a number between min and max.
 pub fn clamp_number(value: f64, min: f64, max: f64) -> f64 {
     value.max(min).min(max)
@@ -55,8 +58,8 @@ pub fn with_whatsapp_prefix(number: &str) -> String {
 pub fn normalize_e164(number: &str) -> String {
     let without_prefix = number.trim_start_matches("whatsapp:").trim();
     let digits: String = without_prefix.chars().filter(|c| c.is_ascii_digit() || *c == '+').collect();
-    if digits.starts_with('+') {
-        format!("+{}", &digits[1..])
+    if let Some(rest) = digits.strip_prefix('+') {
+        format!("+{}", rest)
     } else {
         format!("+{}", digits)
     }
@@ -205,15 +208,11 @@ pub fn slice_utf16_safe(input: &str, start: usize, end: Option<usize>) -> String
     }
 
     // Avoid splitting surrogate pairs
-    if from > 0 && from < len {
-        if is_low_surrogate(utf16[from]) && from > 0 && is_high_surrogate(utf16[from - 1]) {
-            from += 1;
-        }
+    if from > 0 && from < len && is_low_surrogate(utf16[from]) && is_high_surrogate(utf16[from - 1]) {
+        from += 1;
     }
-    if to > 0 && to < len {
-        if is_high_surrogate(utf16[to - 1]) && is_low_surrogate(utf16[to]) {
-            to -= 1;
-        }
+    if to > 0 && to < len && is_high_surrogate(utf16[to - 1]) && is_low_surrogate(utf16[to]) {
+        to -= 1;
     }
 
     String::from_utf16_lossy(&utf16[from..to])
@@ -228,6 +227,16 @@ pub fn truncate_utf16_safe(input: &str, max_len: usize) -> String {
     slice_utf16_safe(input, 0, Some(max_len))
 }
 
+/// First `EXCERPT_CHARS` characters of `text`, on one line.
+pub fn excerpt(text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() <= EXCERPT_CHARS {
+        return flat;
+    }
+    let cut: String = flat.chars().take(EXCERPT_CHARS).collect();
+    format!("{}…", cut.trim_end())
+}
+
 fn is_high_surrogate(code_unit: u16) -> bool {
     (0xD800..=0xDBFF).contains(&code_unit)
 }
@@ -359,4 +368,12 @@ mod tests {
     fn truncate_utf16_safe_truncates() {
         assert_eq!(truncate_utf16_safe("hello world", 5), "hello");
     }
+
+    #[test]
+    fn excerpts_are_flat_and_bounded() {
+        assert_eq!(excerpt("line one\n\nline   two"), "line one line two");
+        let long = excerpt(&"word ".repeat(200));
+        assert!(long.ends_with('…'));
+        assert!(long.chars().count() <= EXCERPT_CHARS + 1);
+    }
 }